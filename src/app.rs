@@ -18,11 +18,12 @@ use std::{
 };
 use tokio::time;
 
-use crate::types::{ClockIdentity, ParsedPacket};
+use crate::filter::PacketFilter;
+use crate::types::{ClockIdentity, ParsedPacket, PortIdentity};
 
 use crate::{
-    ptp::{PtpHost, PtpHostState},
-    service::{PtpService, PtpStatistics},
+    ptp::{PtpHost, PtpHostState, PtpSegment, StepsRemovedMismatch},
+    service::{PtpDomainSummary, PtpService, PtpStatistics},
     ui::ui,
 };
 use std::sync::Arc;
@@ -39,6 +40,8 @@ pub enum ActiveView {
     HostTable,
     HostDetails,
     PacketHistory,
+    /// Domain overview, replacing the host table and details
+    Domains,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -52,6 +55,7 @@ pub enum SortColumn {
     ClockClass,
     SelectedTransmitter,
     MessageCount,
+    Loss,
     LastSeen,
     Vendor,
 }
@@ -68,14 +72,16 @@ impl SortColumn {
             SortColumn::Priority => SortColumn::ClockClass,
             SortColumn::ClockClass => SortColumn::SelectedTransmitter,
             SortColumn::SelectedTransmitter => SortColumn::MessageCount,
-            SortColumn::MessageCount => SortColumn::LastSeen,
+            SortColumn::MessageCount => SortColumn::Loss,
+            SortColumn::Loss => SortColumn::LastSeen,
             SortColumn::LastSeen => SortColumn::State,
         }
     }
 
     pub fn previous(&self) -> Self {
         match self {
-            SortColumn::LastSeen => SortColumn::MessageCount,
+            SortColumn::LastSeen => SortColumn::Loss,
+            SortColumn::Loss => SortColumn::MessageCount,
             SortColumn::MessageCount => SortColumn::SelectedTransmitter,
             SortColumn::SelectedTransmitter => SortColumn::ClockClass,
             SortColumn::ClockClass => SortColumn::Priority,
@@ -101,17 +107,45 @@ impl SortColumn {
             SortColumn::ClockClass => "Clock Class",
             SortColumn::SelectedTransmitter => "Selected Transmitter",
             SortColumn::MessageCount => "Msg Count",
+            SortColumn::Loss => "Loss",
             SortColumn::LastSeen => "Last Seen",
         }
     }
 }
 
+/// What a row of the tree view shows
+#[derive(Clone)]
+pub enum TreeNodeKind {
+    /// A clock, or the view of one of its ports
+    Host(Box<PtpHost>),
+    /// The group row of a segment, `children` then holds the segment's tree
+    Segment(PtpSegment),
+}
+
 #[derive(Clone)]
 pub struct TreeNode {
-    pub host: PtpHost,
+    pub kind: TreeNodeKind,
     pub children: Vec<TreeNode>,
     pub depth: usize,
     pub is_grandmaster: bool,
+    /// Set for the port rows of clocks with several ports, the host then is the port's view
+    pub port: Option<PortIdentity>,
+    /// Mean path delay in nanoseconds from this receiver to its parent in the tree
+    pub path_delay_ns: Option<f64>,
+    /// Corrections show a transparent clock between this receiver and its parent
+    pub tc_in_path: bool,
+    /// Set for boundary clocks announcing a stepsRemoved inconsistent with their upstream
+    pub steps_removed_mismatch: Option<StepsRemovedMismatch>,
+}
+
+impl TreeNode {
+    /// The host of the row, None for segment rows
+    pub fn host(&self) -> Option<&PtpHost> {
+        match &self.kind {
+            TreeNodeKind::Host(host) => Some(host),
+            TreeNodeKind::Segment(_) => None,
+        }
+    }
 }
 
 pub struct App {
@@ -121,7 +155,11 @@ pub struct App {
     pub service: Arc<dyn PtpService>,
     pub cached_hosts: Vec<PtpHost>,
     pub cached_packet_history: Vec<crate::types::ParsedPacket>,
+    /// Datasets announced for the grandmaster of the selected host
+    pub cached_gm_dataset_history: Vec<(std::time::SystemTime, crate::ptp::AnnouncedDataset)>,
     pub cached_stats: PtpStatistics,
+    pub cached_domains: Vec<PtpDomainSummary>,
+    pub selected_domain_index: usize,
     pub reference_timestamp: Option<SystemTime>,
     pub last_update: Instant,
     pub selected_index: usize,
@@ -152,6 +190,10 @@ pub struct App {
     pub host_selection_changed: bool,
     pub packet_selection_changed: bool,
 
+    // Packet filter prompt - Some while the filter is being edited
+    pub filter_input: Option<String>,
+    pub filter_error: Option<String>,
+
     // Mouse support - track UI areas
     pub host_table_area: Option<Rect>,
     pub host_details_area: Option<Rect>,
@@ -183,7 +225,10 @@ impl App {
             service,
             cached_hosts: Vec::new(),
             cached_packet_history: Vec::new(),
+            cached_gm_dataset_history: Vec::new(),
             cached_stats: PtpStatistics::default(),
+            cached_domains: Vec::new(),
+            selected_domain_index: 0,
             reference_timestamp: None,
             last_update: Instant::now(),
             selected_index: 0,
@@ -212,6 +257,8 @@ impl App {
             host_details_visible_height: 10,
             host_selection_changed: true,
             packet_selection_changed: true,
+            filter_input: None,
+            filter_error: None,
             host_table_area: None,
             host_details_area: None,
             packet_history_area: None,
@@ -329,9 +376,47 @@ impl App {
         &mut self,
         key: crossterm::event::KeyEvent,
     ) -> Result<()> {
+        if self.filter_input.is_some() {
+            return self.handle_filter_input_key(key.code).await;
+        }
         self.handle_key_event(key.code, key.modifiers).await
     }
 
+    /// Keys while the filter prompt is open are used for text input
+    async fn handle_filter_input_key(&mut self, key_code: KeyCode) -> Result<()> {
+        let Some(input) = self.filter_input.as_mut() else {
+            return Ok(());
+        };
+
+        match key_code {
+            KeyCode::Char(c) => {
+                input.push(c);
+                self.filter_error = None;
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                self.filter_error = None;
+            }
+            KeyCode::Esc => {
+                self.filter_input = None;
+                self.filter_error = None;
+            }
+            KeyCode::Enter => match input.parse::<PacketFilter>() {
+                Ok(filter) => {
+                    self.service.set_filter(filter).await?;
+                    self.filter_input = None;
+                    self.filter_error = None;
+                    self.update_data().await?;
+                }
+                Err(e) => {
+                    self.filter_error = Some(e.to_string());
+                }
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
     async fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<()> {
         match mouse.kind {
             MouseEventKind::ScrollUp => {
@@ -360,6 +445,7 @@ impl App {
                                 self.move_packet_selection_up();
                             }
                         }
+                        ActiveView::Domains => self.move_domain_selection(-3),
                     }
                 }
                 return Ok(());
@@ -390,6 +476,7 @@ impl App {
                                 self.move_packet_selection_down();
                             }
                         }
+                        ActiveView::Domains => self.move_domain_selection(3),
                     }
                 }
                 return Ok(());
@@ -525,7 +612,8 @@ impl App {
                 self.active_view = match self.active_view {
                     ActiveView::HostTable => ActiveView::HostDetails,
                     ActiveView::HostDetails => ActiveView::PacketHistory,
-                    ActiveView::PacketHistory => ActiveView::HostTable,
+                    ActiveView::PacketHistory => ActiveView::Domains,
+                    ActiveView::Domains => ActiveView::HostTable,
                 };
                 // When switching to packet history, preserve selection unless it's invalid
                 if matches!(self.active_view, ActiveView::PacketHistory) {
@@ -560,11 +648,9 @@ impl App {
                     self.state = AppState::Quitting;
                 }
             }
-            KeyCode::Esc => {
-                if self.show_help {
-                    self.show_help = false;
-                }
+            KeyCode::Esc if self.show_help => {
                 // ESC only closes help now - use 'q' for modal/quit
+                self.show_help = false;
             }
             KeyCode::Char('h') | KeyCode::F(1) => {
                 self.show_help = !self.show_help;
@@ -598,6 +684,7 @@ impl App {
                         ActiveView::HostTable => self.move_selection_up(),
                         ActiveView::HostDetails => self.scroll_host_details_up(),
                         ActiveView::PacketHistory => self.move_packet_selection_up(),
+                        ActiveView::Domains => self.move_domain_selection(-1),
                     }
                 }
             }
@@ -609,6 +696,7 @@ impl App {
                         ActiveView::HostTable => self.move_selection_down(),
                         ActiveView::HostDetails => self.scroll_host_details_down(),
                         ActiveView::PacketHistory => self.move_packet_selection_down(),
+                        ActiveView::Domains => self.move_domain_selection(1),
                     }
                 }
             }
@@ -620,6 +708,7 @@ impl App {
                         ActiveView::HostTable => self.move_selection_page_up(),
                         ActiveView::HostDetails => self.scroll_host_details_page_up(),
                         ActiveView::PacketHistory => self.move_packet_selection_page_up(),
+                        ActiveView::Domains => self.move_domain_selection(-10),
                     }
                 }
             }
@@ -631,15 +720,12 @@ impl App {
                         ActiveView::HostTable => self.move_selection_page_down(self.visible_height),
                         ActiveView::HostDetails => self.scroll_host_details_page_down(),
                         ActiveView::PacketHistory => self.move_packet_selection_page_down(),
+                        ActiveView::Domains => self.move_domain_selection(10),
                     }
                 }
             }
-            KeyCode::Char(' ') => {
-                if self.show_packet_modal {
-                    self.scroll_modal_page_down(self.modal_visible_height);
-                } else {
-                    // Space does nothing when modal is not open
-                }
+            KeyCode::Char(' ') if self.show_packet_modal => {
+                self.scroll_modal_page_down(self.modal_visible_height);
             }
             KeyCode::Home => {
                 if self.show_packet_modal {
@@ -649,6 +735,7 @@ impl App {
                         ActiveView::HostTable => self.move_selection_to_top(),
                         ActiveView::HostDetails => self.scroll_host_details_to_top(),
                         ActiveView::PacketHistory => self.move_packet_selection_to_top(),
+                        ActiveView::Domains => self.move_domain_selection(isize::MIN),
                     }
                 }
             }
@@ -660,6 +747,7 @@ impl App {
                         ActiveView::HostTable => self.move_selection_to_bottom(self.visible_height),
                         ActiveView::HostDetails => self.scroll_host_details_to_bottom(),
                         ActiveView::PacketHistory => self.move_packet_selection_to_bottom(),
+                        ActiveView::Domains => self.move_domain_selection(isize::MAX),
                     }
                 }
             }
@@ -693,10 +781,17 @@ impl App {
             KeyCode::Char('x') => {
                 self.clear_packet_history().await;
             }
+            KeyCode::Char('f') => {
+                // Open the filter prompt, pre-filled with the active filter
+                self.filter_input = Some(self.cached_stats.filter.clone());
+                self.filter_error = None;
+            }
             KeyCode::Enter => {
                 if self.show_packet_modal {
                     // When modal is open, ENTER acts like cursor down
                     self.scroll_modal_down();
+                } else if matches!(self.active_view, ActiveView::Domains) {
+                    self.open_selected_domain();
                 } else if matches!(self.active_view, ActiveView::PacketHistory) {
                     let packet_count = self.get_packet_history().len();
                     if packet_count > 0
@@ -723,6 +818,10 @@ impl App {
 
         self.cached_hosts = self.service.get_hosts().await?;
         self.cached_stats = self.service.get_statistics().await?;
+        self.cached_domains = self.service.get_domains().await?;
+        self.selected_domain_index = self
+            .selected_domain_index
+            .min(self.cached_domains.len().saturating_sub(1));
         self.reference_timestamp = self.cached_stats.last_packet_timestamp;
 
         if let Some(ref id) = self.selected_host_id {
@@ -735,11 +834,54 @@ impl App {
             self.cached_packet_history.clear();
         }
 
+        let gm_identity = self
+            .selected_host_id
+            .and_then(|id| self.cached_hosts.iter().find(|h| h.clock_identity == id))
+            .and_then(|host| match &host.state {
+                PtpHostState::TimeTransmitter(s) | PtpHostState::Stale(s) => {
+                    s.dataset.map(|d| d.gm_identity)
+                }
+                _ => None,
+            });
+        self.cached_gm_dataset_history = match gm_identity {
+            Some(gm_identity) => self
+                .service
+                .get_gm_dataset_history(&gm_identity)
+                .await
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
         self.restore_host_selection();
         self.last_update = Instant::now();
         Ok(())
     }
 
+    /// Move the domain selection by the given number of rows, clamped to the list
+    fn move_domain_selection(&mut self, delta: isize) {
+        let max_index = self.cached_domains.len().saturating_sub(1);
+        self.selected_domain_index = self
+            .selected_domain_index
+            .saturating_add_signed(delta)
+            .min(max_index);
+    }
+
+    /// Drill into the selected domain: select its grandmaster in the host tree
+    fn open_selected_domain(&mut self) {
+        let Some(gm) = self
+            .cached_domains
+            .get(self.selected_domain_index)
+            .and_then(|domain| domain.grandmaster)
+        else {
+            return;
+        };
+        self.active_view = ActiveView::HostTable;
+        self.tree_view_mode = true;
+        self.selected_host_id = Some(gm.clock_identity);
+        self.host_selection_changed = true;
+        self.restore_host_selection();
+    }
+
     /// Helper method to update the selected host ID and reset packet scroll offset
     fn update_selected_host(&mut self, index: usize) {
         // Get the host clock identity based on current view mode
@@ -789,7 +931,8 @@ impl App {
         ) -> Option<ClockIdentity> {
             for node in nodes {
                 if *current_index == target_index {
-                    return Some(node.host.clock_identity);
+                    // Segment rows select no host
+                    return node.host().map(|host| host.clock_identity);
                 }
                 *current_index += 1;
 
@@ -994,12 +1137,14 @@ impl App {
                     let a_state_order = match &a.state {
                         PtpHostState::TimeTransmitter(_) => 0,
                         PtpHostState::TimeReceiver(_) => 1,
-                        PtpHostState::Listening => 2,
+                        PtpHostState::Stale(_) => 2,
+                        PtpHostState::Listening => 3,
                     };
                     let b_state_order = match &b.state {
                         PtpHostState::TimeTransmitter(_) => 0,
                         PtpHostState::TimeReceiver(_) => 1,
-                        PtpHostState::Listening => 2,
+                        PtpHostState::Stale(_) => 2,
+                        PtpHostState::Listening => 3,
                     };
                     a_state_order.cmp(&b_state_order)
                 }
@@ -1035,14 +1180,14 @@ impl App {
                         PtpHostState::TimeReceiver(s) => {
                             s.selected_transmitter_identity.unwrap_or_default()
                         }
-                        _ => ClockIdentity::default(),
+                        _ => PortIdentity::default(),
                     };
 
                     let b = match &b.state {
                         PtpHostState::TimeReceiver(s) => {
                             s.selected_transmitter_identity.unwrap_or_default()
                         }
-                        _ => ClockIdentity::default(),
+                        _ => PortIdentity::default(),
                     };
 
                     a.cmp(&b)
@@ -1050,6 +1195,10 @@ impl App {
                 SortColumn::MessageCount => a
                     .total_messages_sent_count
                     .cmp(&b.total_messages_sent_count),
+                SortColumn::Loss => a
+                    .loss_percent()
+                    .partial_cmp(&b.loss_percent())
+                    .unwrap_or(std::cmp::Ordering::Equal),
                 SortColumn::LastSeen => a.last_seen.cmp(&b.last_seen),
                 SortColumn::Vendor => {
                     let a_vendor = a.get_vendor_name().unwrap_or("");
@@ -1068,8 +1217,140 @@ impl App {
         hosts
     }
 
+    /// The hosts as trees of transmitters and their receivers, grouped by segment. Hosts in
+    /// several segments, e.g. boundary clocks, appear in each with the ports there.
+    /// Segments a boundary clock relays into are shown below it, so the tree follows the
+    /// whole chain from the grandmaster through the boundary clocks to the last receiver.
     pub fn get_hosts_tree(&self) -> Vec<TreeNode> {
-        let hosts = self.cached_hosts.iter().collect::<Vec<_>>();
+        let segments: std::collections::BTreeSet<PtpSegment> = self
+            .cached_hosts
+            .iter()
+            .flat_map(|host| host.segments())
+            .collect();
+        let downstream = self.boundary_clock_segments();
+        let nested: std::collections::HashSet<&PtpSegment> =
+            downstream.values().flatten().collect();
+
+        let mut shown = std::collections::HashSet::new();
+        let mut tree_nodes = Vec::new();
+        for segment in segments.iter().filter(|segment| !nested.contains(segment)) {
+            tree_nodes.push(self.build_segment_node(segment, 0, &downstream, &mut shown));
+        }
+        // Boundary clocks relaying into each other in a loop leave segments without a root
+        for segment in &segments {
+            if !shown.contains(segment) {
+                tree_nodes.push(self.build_segment_node(segment, 0, &downstream, &mut shown));
+            }
+        }
+
+        // Hosts only seen in responses or management messages haven't sent in any segment
+        let unsegmented: Vec<PtpHost> = self
+            .cached_hosts
+            .iter()
+            .filter(|host| host.segments().is_empty())
+            .cloned()
+            .collect();
+        tree_nodes.extend(self.build_tree(&unsegmented, 0));
+
+        tree_nodes
+    }
+
+    /// The segments boundary clocks relay into from another segment, by the clock they are
+    /// shown below: the boundary clock itself if it sends in the upstream segment too, e.g.
+    /// Delay_Req, otherwise its upstream transmitter
+    fn boundary_clock_segments(&self) -> std::collections::HashMap<ClockIdentity, Vec<PtpSegment>> {
+        let mut downstream: std::collections::HashMap<ClockIdentity, Vec<PtpSegment>> =
+            std::collections::HashMap::new();
+        for host in self
+            .cached_hosts
+            .iter()
+            .filter(|host| host.is_boundary_clock())
+        {
+            let Some(upstream) = host.boundary_clock_upstream(&self.cached_hosts) else {
+                continue;
+            };
+            let Some(upstream_segment) = self
+                .cached_hosts
+                .iter()
+                .find(|h| h.clock_identity == upstream.clock_identity)
+                .and_then(|h| h.ports.get(&upstream))
+                .and_then(|port| port.segment.clone())
+            else {
+                continue;
+            };
+
+            let segments = host.segments();
+            let anchor = if segments.contains(&upstream_segment) {
+                host.clock_identity
+            } else {
+                upstream.clock_identity
+            };
+            downstream.entry(anchor).or_default().extend(
+                segments
+                    .into_iter()
+                    .filter(|segment| *segment != upstream_segment),
+            );
+        }
+        downstream
+    }
+
+    /// The group row of a segment with its tree, and the segments behind its boundary clocks
+    fn build_segment_node(
+        &self,
+        segment: &PtpSegment,
+        depth: usize,
+        downstream: &std::collections::HashMap<ClockIdentity, Vec<PtpSegment>>,
+        shown: &mut std::collections::HashSet<PtpSegment>,
+    ) -> TreeNode {
+        shown.insert(segment.clone());
+        let hosts: Vec<PtpHost> = self
+            .cached_hosts
+            .iter()
+            .filter(|host| {
+                host.ports
+                    .values()
+                    .any(|port| port.segment.as_ref() == Some(segment))
+            })
+            .map(|host| host.segment_view(segment))
+            .collect();
+        let mut children = self.build_tree(&hosts, depth + 1);
+        self.attach_downstream_segments(&mut children, downstream, shown);
+
+        TreeNode {
+            kind: TreeNodeKind::Segment(segment.clone()),
+            children,
+            depth,
+            is_grandmaster: false,
+            port: None,
+            path_delay_ns: None,
+            tc_in_path: false,
+            steps_removed_mismatch: None,
+        }
+    }
+
+    fn attach_downstream_segments(
+        &self,
+        nodes: &mut [TreeNode],
+        downstream: &std::collections::HashMap<ClockIdentity, Vec<PtpSegment>>,
+        shown: &mut std::collections::HashSet<PtpSegment>,
+    ) {
+        for node in nodes {
+            self.attach_downstream_segments(&mut node.children, downstream, shown);
+            let clock_identity = match &node.kind {
+                TreeNodeKind::Host(host) if node.port.is_none() => host.clock_identity,
+                _ => continue,
+            };
+            for segment in downstream.get(&clock_identity).into_iter().flatten() {
+                if !shown.contains(segment) {
+                    let child = self.build_segment_node(segment, node.depth + 1, downstream, shown);
+                    node.children.push(child);
+                }
+            }
+        }
+    }
+
+    fn build_tree(&self, hosts: &[PtpHost], depth: usize) -> Vec<TreeNode> {
+        let hosts = hosts.iter().collect::<Vec<_>>();
         let mut tree_nodes = Vec::new();
         let mut processed = std::collections::HashSet::new();
 
@@ -1080,21 +1361,29 @@ impl App {
         > = std::collections::HashMap::new();
 
         for (i, host) in hosts.iter().enumerate() {
-            if let PtpHostState::TimeReceiver(receiver_state) = &host.state
-                && let Some(transmitter_id) = receiver_state.selected_transmitter_identity
+            if let Some(transmitter_id) = host.get_selected_transmitter()
+                && transmitter_id.clock_identity != host.clock_identity
             {
                 transmitter_to_receiver_indices
-                    .entry(transmitter_id)
+                    .entry(transmitter_id.clock_identity)
                     .or_default()
                     .push(i);
             }
         }
 
-        // Find root transmitters using indices to avoid cloning
+        // Find root transmitters using indices to avoid cloning. Transmitters that are
+        // themselves synchronised to a known transmitter (boundary clocks) are placed
+        // below it instead.
         let mut root_transmitter_indices: Vec<_> = hosts
             .iter()
             .enumerate()
             .filter(|(_, host)| matches!(host.state, PtpHostState::TimeTransmitter(_)))
+            .filter(|(_, host)| {
+                !host.get_selected_transmitter().is_some_and(|id| {
+                    id.clock_identity != host.clock_identity
+                        && hosts.iter().any(|h| h.clock_identity == id.clock_identity)
+                })
+            })
             .map(|(i, _)| i)
             .collect();
 
@@ -1114,7 +1403,7 @@ impl App {
                     transmitter_idx,
                     &transmitter_to_receiver_indices,
                     &mut processed,
-                    0,
+                    depth,
                 );
                 tree_nodes.push(node);
             }
@@ -1136,13 +1425,16 @@ impl App {
         });
 
         for &host_idx in &orphaned_indices {
-            let host = hosts[host_idx];
-            tree_nodes.push(TreeNode {
-                host: (*host).clone(),
-                children: Vec::new(),
-                depth: 0,
-                is_grandmaster: matches!(host.state, PtpHostState::TimeTransmitter(_)),
-            });
+            if !processed.contains(&hosts[host_idx].clock_identity) {
+                let node = self.build_tree_node(
+                    &hosts,
+                    host_idx,
+                    &transmitter_to_receiver_indices,
+                    &mut processed,
+                    depth,
+                );
+                tree_nodes.push(node);
+            }
         }
 
         tree_nodes
@@ -1166,12 +1458,14 @@ impl App {
                 let a_state_order = match &a.state {
                     PtpHostState::TimeTransmitter(_) => 0,
                     PtpHostState::TimeReceiver(_) => 1,
-                    PtpHostState::Listening => 2,
+                    PtpHostState::Stale(_) => 2,
+                    PtpHostState::Listening => 3,
                 };
                 let b_state_order = match &b.state {
                     PtpHostState::TimeTransmitter(_) => 0,
                     PtpHostState::TimeReceiver(_) => 1,
-                    PtpHostState::Listening => 2,
+                    PtpHostState::Stale(_) => 2,
+                    PtpHostState::Listening => 3,
                 };
                 a_state_order.cmp(&b_state_order)
             }
@@ -1203,14 +1497,14 @@ impl App {
                     PtpHostState::TimeReceiver(s) => {
                         s.selected_transmitter_identity.unwrap_or_default()
                     }
-                    _ => ClockIdentity::default(),
+                    _ => PortIdentity::default(),
                 };
 
                 let b_sel = match &b.state {
                     PtpHostState::TimeReceiver(s) => {
                         s.selected_transmitter_identity.unwrap_or_default()
                     }
-                    _ => ClockIdentity::default(),
+                    _ => PortIdentity::default(),
                 };
 
                 a_sel.cmp(&b_sel)
@@ -1218,6 +1512,10 @@ impl App {
             SortColumn::MessageCount => a
                 .total_messages_sent_count
                 .cmp(&b.total_messages_sent_count),
+            SortColumn::Loss => a
+                .loss_percent()
+                .partial_cmp(&b.loss_percent())
+                .unwrap_or(std::cmp::Ordering::Equal),
             SortColumn::LastSeen => a.last_seen.cmp(&b.last_seen),
             SortColumn::Vendor => {
                 let a_vendor = a.get_vendor_name().unwrap_or("");
@@ -1254,6 +1552,25 @@ impl App {
 
         let mut children = Vec::new();
 
+        // List the ports of boundary clocks and switches before their receivers
+        if host.has_multiple_ports() {
+            for port in host.ports.values() {
+                children.push(TreeNode {
+                    kind: TreeNodeKind::Host(Box::new(host.port_view(port))),
+                    children: Vec::new(),
+                    depth: depth + 1,
+                    is_grandmaster: matches!(
+                        &port.state,
+                        PtpHostState::TimeTransmitter(s) if s.is_bmca_winner
+                    ),
+                    port: Some(port.port_identity),
+                    path_delay_ns: None,
+                    tc_in_path: false,
+                    steps_removed_mismatch: None,
+                });
+            }
+        }
+
         // Find receivers for this transmitter and sort them
         if let Some(receiver_indices) = transmitter_to_receiver_indices.get(&host.clock_identity) {
             let mut sorted_receiver_indices = receiver_indices.clone();
@@ -1263,23 +1580,38 @@ impl App {
             for &receiver_idx in &sorted_receiver_indices {
                 let receiver = hosts[receiver_idx];
                 if !processed.contains(&receiver.clock_identity) {
-                    let child_node = self.build_tree_node(
+                    let mut child_node = self.build_tree_node(
                         hosts,
                         receiver_idx,
                         transmitter_to_receiver_indices,
                         processed,
                         depth + 1,
                     );
+                    child_node.path_delay_ns = receiver.mean_path_delay_to(&host.clock_identity);
+                    // The transmitter's Sync corrections cover the path to the capture point
+                    child_node.tc_in_path = receiver.has_transparent_clock_on_delay_path()
+                        || host.has_transparent_clock_on_sync_path();
                     children.push(child_node);
                 }
             }
         }
 
+        // Check the whole clock, the tree may only hold the ports of one segment
+        let steps_removed_mismatch = self
+            .cached_hosts
+            .iter()
+            .find(|h| h.clock_identity == host.clock_identity && h.is_boundary_clock())
+            .and_then(|h| h.steps_removed_mismatch(&self.cached_hosts));
+
         TreeNode {
-            host: (*host).clone(),
+            kind: TreeNodeKind::Host(Box::new((*host).clone())),
             children,
             depth,
             is_grandmaster,
+            port: None,
+            path_delay_ns: None,
+            tc_in_path: false,
+            steps_removed_mismatch,
         }
     }
 
@@ -1326,7 +1658,10 @@ impl App {
                 current_index: &mut usize,
             ) -> Option<usize> {
                 for node in nodes {
-                    if node.host.clock_identity == target_id {
+                    if node
+                        .host()
+                        .is_some_and(|host| host.clock_identity == target_id)
+                    {
                         return Some(*current_index);
                     }
                     *current_index += 1;
@@ -1521,6 +1856,13 @@ impl App {
         self.reference_timestamp
     }
 
+    /// Silence after which a host times out, `--host-timeout` if set, as for the events
+    pub fn host_timeout(&self, host: &PtpHost) -> Duration {
+        self.cached_stats
+            .host_timeout
+            .unwrap_or_else(|| host.default_timeout())
+    }
+
     fn scroll_modal_up(&mut self) {
         if self.modal_scroll_offset > 0 {
             self.modal_scroll_offset -= 1;
@@ -0,0 +1,310 @@
+//! Packet filters applied before packets reach the tracker
+//!
+//! A filter is written as space-separated `key=value[,value...]` terms, for example
+//! `domain=0,1 message-type=!signaling vlan=!100`. A value prefixed with `!` excludes
+//! matching packets. Within a key, a packet has to match one of the included values
+//! (if there are any) and none of the excluded ones. All keys have to match.
+
+use anyhow::Result;
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::source::RawPacket;
+use crate::types::{ClockIdentity, PtpHeader, PtpMessageType};
+
+/// Filter keys, as used on the command line and in filter expressions
+pub const FILTER_KEYS: [&str; 5] = ["domain", "message-type", "vlan", "clock-id", "src-ip"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FilterValue<T> {
+    value: T,
+    negate: bool,
+}
+
+/// Included and excluded values for a single packet field
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FilterSet<T> {
+    values: Vec<FilterValue<T>>,
+}
+
+impl<T> Default for FilterSet<T> {
+    fn default() -> Self {
+        Self { values: Vec::new() }
+    }
+}
+
+impl<T> FilterSet<T>
+where
+    T: FromStr<Err = anyhow::Error> + PartialEq + Display,
+{
+    fn parse(values: &[String]) -> Result<Self> {
+        let mut set = Self::default();
+        for value in values.iter().flat_map(|v| v.split(',')) {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            let (negate, value) = match value.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, value),
+            };
+            set.values.push(FilterValue {
+                value: value.parse()?,
+                negate,
+            });
+        }
+        Ok(set)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Fields that are unknown for a packet (e.g. no VLAN tag) only match exclusions
+    fn matches(&self, field: Option<&T>) -> bool {
+        let mut has_includes = false;
+        let mut included = false;
+
+        for filter in &self.values {
+            let is_match = field == Some(&filter.value);
+            if filter.negate {
+                if is_match {
+                    return false;
+                }
+            } else {
+                has_includes = true;
+                included |= is_match;
+            }
+        }
+
+        !has_includes || included
+    }
+}
+
+impl<T: Display> Display for FilterSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values: Vec<String> = self
+            .values
+            .iter()
+            .map(|v| {
+                if v.negate {
+                    format!("!{}", v.value)
+                } else {
+                    v.value.to_string()
+                }
+            })
+            .collect();
+        write!(f, "{}", values.join(","))
+    }
+}
+
+/// Newtype so that numeric fields share the same `anyhow` based parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Number<T>(T);
+
+impl<T: FromStr> FromStr for Number<T>
+where
+    T::Err: Display,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.parse()
+            .map(Number)
+            .map_err(|e| anyhow::anyhow!("Invalid number '{}': {}", s, e))
+    }
+}
+
+impl<T: Display> Display for Number<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Newtype for IP addresses with `anyhow` based parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Address(IpAddr);
+
+impl FromStr for Address {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.parse()
+            .map(Address)
+            .map_err(|_| anyhow::anyhow!("Invalid IP address '{}'", s))
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PacketFilter {
+    domains: FilterSet<Number<u8>>,
+    message_types: FilterSet<PtpMessageType>,
+    vlans: FilterSet<Number<u16>>,
+    clock_ids: FilterSet<ClockIdentity>,
+    src_ips: FilterSet<Address>,
+}
+
+impl PacketFilter {
+    /// Set the values for one key, replacing any previous values
+    pub fn set(&mut self, key: &str, values: &[String]) -> Result<()> {
+        match key {
+            "domain" => self.domains = FilterSet::parse(values)?,
+            "message-type" | "type" => self.message_types = FilterSet::parse(values)?,
+            "vlan" => self.vlans = FilterSet::parse(values)?,
+            "clock-id" | "clock" => self.clock_ids = FilterSet::parse(values)?,
+            "src-ip" | "ip" => self.src_ips = FilterSet::parse(values)?,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown filter key '{}' (available: {})",
+                    key,
+                    FILTER_KEYS.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+            && self.message_types.is_empty()
+            && self.vlans.is_empty()
+            && self.clock_ids.is_empty()
+            && self.src_ips.is_empty()
+    }
+
+    pub fn matches(&self, raw: &RawPacket, header: &PtpHeader) -> bool {
+        self.domains.matches(Some(&Number(header.domain_number)))
+            && self.message_types.matches(Some(&header.message_type))
+            && self.vlans.matches(raw.vlan_id.map(Number).as_ref())
+            && self
+                .clock_ids
+                .matches(Some(&header.source_port_identity.clock_identity))
+            && self
+                .src_ips
+                .matches(raw.source_addr.map(|a| Address(a.ip())).as_ref())
+    }
+}
+
+impl FromStr for PacketFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut filter = PacketFilter::default();
+        for term in s.split_whitespace() {
+            let (key, values) = term
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected key=value, got '{}'", term))?;
+            filter.set(key, &[values.to_string()])?;
+        }
+        Ok(filter)
+    }
+}
+
+impl Display for PacketFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<String> = [
+            ("domain", self.domains.to_string()),
+            ("message-type", self.message_types.to_string()),
+            ("vlan", self.vlans.to_string()),
+            ("clock-id", self.clock_ids.to_string()),
+            ("src-ip", self.src_ips.to_string()),
+        ]
+        .into_iter()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(key, values)| format!("{}={}", key, values))
+        .collect();
+        write!(f, "{}", terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(domain: u8, message_type: u8, vlan_id: Option<u16>) -> (RawPacket, PtpHeader) {
+        let mut payload = vec![0u8; 44];
+        payload[0] = message_type;
+        payload[1] = 0x02;
+        payload[4] = domain;
+
+        let raw = RawPacket {
+            timestamp: std::time::SystemTime::now(),
+            data: payload.clone(),
+            source_addr: Some("192.168.1.10:319".parse().unwrap()),
+            source_mac: None,
+            dest_addr: None,
+            dest_mac: None,
+            vlan_id,
+            ttl: None,
+            interface_name: "eth0".to_string(),
+            ptp_payload: payload.clone(),
+            tunnel: None,
+            redundancy: None,
+        };
+        let header = PtpHeader::try_from(&payload[..]).unwrap();
+        (raw, header)
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = PacketFilter::default();
+        let (raw, header) = packet(3, 0, None);
+        assert!(filter.is_empty());
+        assert!(filter.matches(&raw, &header));
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let filter: PacketFilter = "domain=0,1 message-type=!announce".parse().unwrap();
+
+        let (raw, header) = packet(0, 0x0, None);
+        assert!(filter.matches(&raw, &header));
+        let (raw, header) = packet(2, 0x0, None);
+        assert!(!filter.matches(&raw, &header));
+        let (raw, header) = packet(1, 0xb, None);
+        assert!(!filter.matches(&raw, &header));
+    }
+
+    #[test]
+    fn test_missing_field_only_matches_exclusions() {
+        let (raw, header) = packet(0, 0x0, None);
+        assert!(
+            !"vlan=100"
+                .parse::<PacketFilter>()
+                .unwrap()
+                .matches(&raw, &header)
+        );
+        assert!(
+            "vlan=!100"
+                .parse::<PacketFilter>()
+                .unwrap()
+                .matches(&raw, &header)
+        );
+
+        let (raw, header) = packet(0, 0x0, Some(100));
+        assert!(
+            !"vlan=!100"
+                .parse::<PacketFilter>()
+                .unwrap()
+                .matches(&raw, &header)
+        );
+    }
+
+    #[test]
+    fn test_round_trip_and_errors() {
+        let expression = "domain=!3 message-type=SYNC,FOLLOW_UP src-ip=192.168.1.10";
+        let filter: PacketFilter = expression.parse().unwrap();
+        assert_eq!(filter.to_string(), expression);
+
+        assert!("domain=300".parse::<PacketFilter>().is_err());
+        assert!("colour=red".parse::<PacketFilter>().is_err());
+        assert!("domain".parse::<PacketFilter>().is_err());
+    }
+}
@@ -1,8 +1,14 @@
 //! Logging utilities for headless mode
 
+use crate::ptp::{
+    DatasetChangeDirection, INTERVAL_HISTOGRAM_BOUNDS, IntervalStats, PtpHost, PtpPort,
+};
 use crate::service::events::PtpEvent;
+use crate::types::PtpMessageType;
 use chrono::Local;
+use serde_json::json;
 use std::io::IsTerminal;
+use std::time::Duration;
 
 /// Log level for headless mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -77,7 +83,7 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
     match event {
         // Error level events - critical network issues
         PtpEvent::GrandmasterChange {
-            domain,
+            segment,
             old_gm,
             new_gm,
         } => {
@@ -87,8 +93,106 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
                     .map(|gm| gm.to_string())
                     .unwrap_or_else(|| "(none)".to_string());
                 println!(
-                    "{}{}: Grandmaster changed in domain {} | old={} new={}",
-                    ts, level, domain, old_gm_str, new_gm
+                    "{}{}: Grandmaster changed in {} | old={} new={}",
+                    ts, level, segment, old_gm_str, new_gm
+                );
+            }
+        }
+
+        PtpEvent::CompetingGrandmasters { segment, sources } => {
+            if log_level >= LogLevel::Error {
+                let level = format_level("ERROR", colors::RED, config);
+                let sources_str = sources
+                    .iter()
+                    .map(|source| {
+                        let receivers = source
+                            .receivers
+                            .iter()
+                            .map(|r| r.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("{} receivers=[{}]", source.port_identity, receivers)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" vs ");
+                println!(
+                    "{}{}: Competing grandmasters in {} | {}",
+                    ts, level, segment, sources_str
+                );
+            }
+        }
+
+        PtpEvent::ClockIdentityConflict {
+            clock_identity,
+            conflict,
+        } => {
+            if conflict.is_duplicate() && log_level >= LogLevel::Error {
+                let level = format_level("ERROR", colors::RED, config);
+                println!(
+                    "{}{}: Duplicate clock identity {} | {}",
+                    ts, level, clock_identity, conflict
+                );
+            } else if !conflict.is_duplicate() && log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Clock identity {} doesn't match its MAC | {}",
+                    ts, level, clock_identity, conflict
+                );
+            }
+        }
+
+        PtpEvent::TimePropertiesIssue { segment, issue } => {
+            if issue.is_disagreement() && log_level >= LogLevel::Error {
+                let level = format_level("ERROR", colors::RED, config);
+                println!(
+                    "{}{}: Inconsistent time properties in {} | {}",
+                    ts, level, segment, issue
+                );
+            } else if !issue.is_disagreement() && log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Unexpected time properties in {} | {}",
+                    ts, level, segment, issue
+                );
+            }
+        }
+
+        PtpEvent::LeapSecondAnnounced {
+            port_identity,
+            leap,
+            at,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                let at = chrono::DateTime::<chrono::Utc>::from(*at);
+                println!(
+                    "{}{}: Leap second {} announced by {} | at={}",
+                    ts,
+                    level,
+                    leap,
+                    port_identity,
+                    at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+            }
+        }
+
+        PtpEvent::LeapSecondEnded {
+            port_identity,
+            leap,
+            old_utc_offset,
+            new_utc_offset,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                // Without the offset change the announcement was withdrawn
+                let outcome = if new_utc_offset - old_utc_offset == leap.offset_change() {
+                    "applied"
+                } else {
+                    "withdrawn"
+                };
+                println!(
+                    "{}{}: Leap second {} {} by {} | utc_offset={}s->{}s",
+                    ts, level, leap, outcome, port_identity, old_utc_offset, new_utc_offset
                 );
             }
         }
@@ -120,6 +224,27 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
             }
         }
 
+        PtpEvent::AnnounceTimeout {
+            port_identity,
+            domain,
+            silent_for,
+        } => {
+            if log_level >= LogLevel::Error {
+                let level = format_level("ERROR", colors::RED, config);
+                let domain = domain
+                    .map(|d| d.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                println!(
+                    "{}{}: Announce receipt timeout on {} | domain={} last_announce={:.1}s ago",
+                    ts,
+                    level,
+                    port_identity,
+                    domain,
+                    silent_for.as_secs_f64()
+                );
+            }
+        }
+
         PtpEvent::InterfaceLinkChange { interface, link_up } => {
             // Link down is ERROR, link up is WARN
             if !link_up && log_level >= LogLevel::Error {
@@ -147,7 +272,74 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
             }
         }
 
+        PtpEvent::GrandmasterTimeStep {
+            port_identity,
+            step_ns,
+            offset_ns,
+        } => {
+            if log_level >= LogLevel::Error {
+                let level = format_level("ERROR", colors::RED, config);
+                println!(
+                    "{}{}: Time step of {} | step={:+.6}s offset={:+.6}s",
+                    ts,
+                    level,
+                    port_identity,
+                    *step_ns as f64 / 1e9,
+                    *offset_ns as f64 / 1e9
+                );
+            }
+        }
+
         // Warn level events - state changes and updates
+        PtpEvent::ClockQualityImproved {
+            clock_identity,
+            old_class,
+            new_class,
+            old_accuracy,
+            new_accuracy,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Clock quality improved for {} | class: {}->{} accuracy: {}->{}",
+                    ts, level, clock_identity, old_class, new_class, old_accuracy, new_accuracy
+                );
+            }
+        }
+
+        PtpEvent::DatasetChanged {
+            port_identity,
+            gm_identity,
+            change,
+        } => {
+            // Degradations are WARN, other dataset changes INFO
+            let (level, min_level) = match change.direction {
+                DatasetChangeDirection::Degraded => {
+                    (format_level("WARN", colors::YELLOW, config), LogLevel::Warn)
+                }
+                _ => (format_level("INFO", colors::GREEN, config), LogLevel::Info),
+            };
+            if log_level >= min_level {
+                println!(
+                    "{}{}: Announced dataset of {} changed | gm={} {}",
+                    ts, level, port_identity, gm_identity, change
+                );
+            }
+        }
+
+        PtpEvent::HostRecovered {
+            clock_identity,
+            outage_secs,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Host {} recovered | outage={}s",
+                    ts, level, clock_identity, outage_secs
+                );
+            }
+        }
+
         PtpEvent::HostUpdated { host, changes } => {
             if log_level >= LogLevel::Warn {
                 let level = format_level("WARN", colors::YELLOW, config);
@@ -164,6 +356,61 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
             }
         }
 
+        PtpEvent::SyncFollowUpIssue {
+            port_identity,
+            issue,
+            count,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Sync/Follow_Up problem on {} | {} count={}",
+                    ts, level, port_identity, issue, count
+                );
+            }
+        }
+
+        PtpEvent::IntervalDeviation {
+            port_identity,
+            message_type,
+            advertised,
+            measured,
+            std_dev,
+            deviation_percent,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Message interval deviation on {} | type={} advertised={:.6}s measured={:.6}s std_dev={:.6}s deviation={:+.1}%",
+                    ts,
+                    level,
+                    port_identity,
+                    message_type,
+                    advertised.seconds().unwrap_or_default(),
+                    measured.as_secs_f64(),
+                    std_dev.as_secs_f64(),
+                    deviation_percent
+                );
+            }
+        }
+
+        PtpEvent::SequenceLoss {
+            port_identity,
+            message_type,
+            domain,
+            loss_percent,
+            lost,
+            expected,
+        } => {
+            if log_level >= LogLevel::Warn {
+                let level = format_level("WARN", colors::YELLOW, config);
+                println!(
+                    "{}{}: Packet loss on {} | type={} domain={} loss={:.2}% lost={}/{}",
+                    ts, level, port_identity, message_type, domain, loss_percent, lost, expected
+                );
+            }
+        }
+
         PtpEvent::InterfaceAddressChange {
             interface,
             added,
@@ -189,6 +436,16 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
         }
 
         // Info level events - new discoveries
+        PtpEvent::HostExpired { clock_identity } => {
+            if log_level >= LogLevel::Info {
+                let level = format_level("INFO", colors::GREEN, config);
+                println!(
+                    "{}{}: Host {} expired and removed",
+                    ts, level, clock_identity
+                );
+            }
+        }
+
         PtpEvent::HostDiscovered(host) => {
             if log_level >= LogLevel::Info {
                 let level = format_level("INFO", colors::GREEN, config);
@@ -244,3 +501,53 @@ pub fn log_event(event: &PtpEvent, log_level: LogLevel, config: &LoggerConfig) {
         }
     }
 }
+
+/// Log the measured message intervals of every stream, one JSON object per line
+pub fn log_interval_stats(hosts: &[PtpHost], tolerance_percent: f64) {
+    for host in hosts {
+        for port in host.ports.values() {
+            for (&message_type, stats) in &port.message_intervals {
+                println!(
+                    "{}",
+                    interval_stats_json(port, message_type, stats, tolerance_percent)
+                );
+            }
+        }
+    }
+}
+
+/// Inter-arrival statistics of one stream, with durations in seconds and null where there
+/// are too few intervals. Histogram buckets are relative to the advertised interval.
+fn interval_stats_json(
+    port: &PtpPort,
+    message_type: PtpMessageType,
+    stats: &IntervalStats,
+    tolerance_percent: f64,
+) -> serde_json::Value {
+    let secs = |duration: Option<Duration>| duration.map(|d| d.as_secs_f64());
+    let histogram: Vec<serde_json::Value> = INTERVAL_HISTOGRAM_BOUNDS
+        .iter()
+        .map(|&bound| Some(bound))
+        .chain([None])
+        .zip(stats.histogram)
+        .map(|(below_ratio, count)| json!({ "below_ratio": below_ratio, "count": count }))
+        .collect();
+
+    json!({
+        "type": "interval_stats",
+        "clock_identity": port.port_identity.clock_identity.to_string(),
+        "port_number": port.port_identity.port_number,
+        "domain": port.domain_number,
+        "message_type": message_type.to_string(),
+        "count": stats.count,
+        "advertised_interval_secs": stats.advertised.and_then(|interval| interval.seconds()),
+        "mean_secs": secs(stats.mean()),
+        "min_secs": secs(stats.min),
+        "max_secs": secs(stats.max),
+        "std_dev_secs": secs(stats.std_dev()),
+        "rate": stats.rate(),
+        "deviation_percent": stats.deviation_percent(),
+        "deviates": stats.deviates(tolerance_percent),
+        "histogram": histogram,
+    })
+}
@@ -8,11 +8,17 @@ pub mod logger;
 use crate::service::PtpService;
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use logger::{LogLevel, LoggerConfig};
 
-/// Run headless mode with event logging
-pub async fn run_headless_mode(service: Arc<dyn PtpService>, log_level: LogLevel) -> Result<()> {
+/// Run headless mode with event logging, and the message interval statistics of every stream
+/// as JSON lines every `interval_stats` if set
+pub async fn run_headless_mode(
+    service: Arc<dyn PtpService>,
+    log_level: LogLevel,
+    interval_stats: Option<Duration>,
+) -> Result<()> {
     let mut event_rx = service.subscribe_to_events().await?;
     let logger_config = LoggerConfig::detect();
 
@@ -25,9 +31,34 @@ pub async fn run_headless_mode(service: Arc<dyn PtpService>, log_level: LogLevel
         println!("Monitoring for PTP events...\n");
     }
 
-    while let Some(event) = event_rx.recv().await {
-        logger::log_event(&event, log_level, &logger_config);
+    let mut interval_stats_timer = interval_stats
+        .map(|period| tokio::time::interval_at(tokio::time::Instant::now() + period, period));
+
+    loop {
+        tokio::select! {
+            event = event_rx.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                logger::log_event(&event, log_level, &logger_config);
+            }
+            _ = tick(&mut interval_stats_timer) => {
+                let hosts = service.get_hosts().await?;
+                let statistics = service.get_statistics().await?;
+                logger::log_interval_stats(&hosts, statistics.interval_tolerance_percent);
+            }
+        }
     }
 
     Ok(())
 }
+
+/// The next tick of the timer, never without one
+async fn tick(timer: &mut Option<tokio::time::Interval>) {
+    match timer {
+        Some(timer) => {
+            timer.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
@@ -4,8 +4,10 @@ use std::time::Duration;
 
 mod app;
 mod bounded_vec;
+mod filter;
 mod headless;
 mod oui_map;
+mod privileges;
 mod ptp;
 mod service;
 mod source;
@@ -62,6 +64,74 @@ pub struct Cli {
     #[arg(short = 'f', long, value_name = "FILE", conflicts_with = "interface")]
     pcap_file: Option<String>,
 
+    /// Capture with ordinary UDP sockets on ports 319/320 instead of a raw socket. Does not require root
+    /// (only CAP_NET_BIND_SERVICE or a lowered net.ipv4.ip_unprivileged_port_start), but only sees
+    /// PTP over UDP and cannot report MAC addresses, VLAN IDs or gPTP traffic
+    #[arg(long, conflicts_with = "pcap_file")]
+    udp: bool,
+
+    /// Drop privileges to this user once the capture sockets are open
+    #[arg(long, value_name = "USER")]
+    user: Option<String>,
+
+    /// Drop privileges to this group once the capture sockets are open (defaults to the user's primary group)
+    #[arg(long, value_name = "GROUP")]
+    group: Option<String>,
+
+    /// Only process packets from these PTP domains. Comma-separated or repeated; prefix with '!' to exclude
+    #[arg(long, value_name = "DOMAIN")]
+    domain: Vec<String>,
+
+    /// Only process these message types (e.g. sync, announce). Comma-separated or repeated; prefix with '!' to exclude
+    #[arg(long, value_name = "TYPE")]
+    message_type: Vec<String>,
+
+    /// Only process packets on these VLANs. Comma-separated or repeated; prefix with '!' to exclude
+    #[arg(long, value_name = "VLAN")]
+    vlan: Vec<String>,
+
+    /// Only process packets from these clock identities. Comma-separated or repeated; prefix with '!' to exclude
+    #[arg(long, value_name = "CLOCK_ID")]
+    clock_id: Vec<String>,
+
+    /// Only process packets from these source IP addresses. Comma-separated or repeated; prefix with '!' to exclude
+    #[arg(long, value_name = "IP")]
+    src_ip: Vec<String>,
+
+    /// Packet loss in percent, from sequence id gaps, above which a packet loss event is emitted
+    #[arg(long, value_name = "PERCENT", default_value = "1.0")]
+    loss_threshold: f64,
+
+    /// Allowed deviation in percent of measured message intervals from the advertised logMessageInterval
+    #[arg(long, value_name = "PERCENT", default_value = "20.0")]
+    interval_tolerance: f64,
+
+    /// Announce intervals without an Announce after which a transmitter is considered stale (announceReceiptTimeout)
+    #[arg(long, value_name = "INTERVALS", default_value = "3", value_parser = clap::value_parser!(u8).range(2..))]
+    announce_receipt_timeout: u8,
+
+    /// Seconds without a packet after which a host timeout is reported. Defaults to 5 intervals of
+    /// the host's slowest message stream (at least 5s), or 60s for hosts that send nothing periodic
+    #[arg(long, value_name = "SECONDS")]
+    host_timeout: Option<u64>,
+
+    /// Seconds without a packet after which a host is removed from the host table
+    #[arg(long, value_name = "SECONDS")]
+    host_expiry: Option<u64>,
+
+    /// UTC offset in seconds that grandmasters on the PTP timescale should announce
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value = "37",
+        allow_negative_numbers = true
+    )]
+    expected_utc_offset: i16,
+
+    /// Timescale grandmasters should announce: ptp, arb or any
+    #[arg(long, value_name = "TIMESCALE", default_value = "ptp")]
+    expected_timescale: ptp::ExpectedTimescale,
+
     /// Update interval in milliseconds
     #[arg(short, long, default_value = "1000")]
     update_interval: u64,
@@ -84,6 +154,10 @@ pub struct Cli {
     /// Log level for headless mode: error (critical events), warn (error + state changes), info (warn + discoveries, default), debug (info + all packets)
     #[arg(long, default_value = "info", requires = "headless")]
     log_level: String,
+
+    /// Print the measured message intervals of every stream as JSON lines every SECONDS seconds (headless mode)
+    #[arg(long, value_name = "SECONDS", requires = "headless", value_parser = clap::value_parser!(u64).range(1..))]
+    interval_stats: Option<u64>,
 }
 
 #[derive(Parser)]
@@ -112,16 +186,41 @@ async fn main() -> Result<()> {
         ThemeName::Default
     });
 
+    // Build packet filter from command line options
+    let mut packet_filter = filter::PacketFilter::default();
+    packet_filter.set("domain", &cli.domain)?;
+    packet_filter.set("message-type", &cli.message_type)?;
+    packet_filter.set("vlan", &cli.vlan)?;
+    packet_filter.set("clock-id", &cli.clock_id)?;
+    packet_filter.set("src-ip", &cli.src_ip)?;
+
     // Create packet source (either from network interfaces or pcap file)
     let raw_socket_receiver = if let Some(pcap_path) = &cli.pcap_file {
         source::create_pcap_receiver(pcap_path).await?
+    } else if cli.udp {
+        source::create_udp_socket_receiver(&cli.interface).await?
     } else {
         source::create_raw_socket_receiver(&cli.interface).await?
     };
 
+    // Capture sockets are open, so root is no longer needed
+    if cli.user.is_some() || cli.group.is_some() {
+        privileges::drop_privileges(cli.user.as_deref(), cli.group.as_deref())?;
+    }
+
     // Always create service layer and start gRPC server
     use service::PtpServiceImpl;
-    let service = PtpServiceImpl::new(raw_socket_receiver).await?;
+    let config = service::ServiceConfig {
+        filter: packet_filter,
+        loss_threshold_percent: cli.loss_threshold,
+        interval_tolerance_percent: cli.interval_tolerance,
+        announce_receipt_timeout: cli.announce_receipt_timeout,
+        host_timeout: cli.host_timeout.map(Duration::from_secs),
+        host_expiry: cli.host_expiry.map(Duration::from_secs),
+        expected_utc_offset: cli.expected_utc_offset,
+        expected_timescale: cli.expected_timescale,
+    };
+    let service = PtpServiceImpl::new(raw_socket_receiver, config).await?;
 
     // Run in headless mode or TUI mode
     if cli.headless {
@@ -137,7 +236,12 @@ async fn main() -> Result<()> {
 
         // Run headless mode - log events and anomalies
         use headless::run_headless_mode;
-        run_headless_mode(service, log_level).await?;
+        run_headless_mode(
+            service,
+            log_level,
+            cli.interval_stats.map(Duration::from_secs),
+        )
+        .await?;
     } else {
         let update_interval = Duration::from_millis(cli.update_interval);
         let mut app = App::new(
@@ -0,0 +1,116 @@
+//! Dropping root privileges after the capture sockets have been opened
+//!
+//! Live capture needs root (or CAP_NET_RAW) only to open the datalink channels
+//! and bind the PTP ports. Everything after that, including the TUI and event
+//! processing, can run as an unprivileged user.
+//!
+//! Capture does not reopen channels when interfaces come and go, so no
+//! capabilities are retained after switching user.
+
+use anyhow::Result;
+
+/// Switch to the given user and/or group.
+///
+/// If only `user` is given, the user's primary group is used. Supplementary
+/// groups are always cleared. When `user` is given and the process is still
+/// able to act as root afterwards, an error is returned so the caller can
+/// refuse to continue.
+#[cfg(unix)]
+pub fn drop_privileges(user: Option<&str>, group: Option<&str>) -> Result<()> {
+    use std::ffi::CString;
+
+    let passwd = match user {
+        Some(name) => {
+            let c_name = CString::new(name)?;
+            // SAFETY: getpwnam returns either null or a pointer to static storage,
+            // which is copied out immediately
+            let pw = unsafe { libc::getpwnam(c_name.as_ptr()) };
+            if pw.is_null() {
+                return Err(anyhow::anyhow!("Unknown user '{}'", name));
+            }
+            let pw = unsafe { &*pw };
+            Some((pw.pw_uid, pw.pw_gid))
+        }
+        None => None,
+    };
+
+    let gid = match group {
+        Some(name) => {
+            let c_name = CString::new(name)?;
+            // SAFETY: as above, getgrnam returns null or static storage
+            let gr = unsafe { libc::getgrnam(c_name.as_ptr()) };
+            if gr.is_null() {
+                return Err(anyhow::anyhow!("Unknown group '{}'", name));
+            }
+            Some(unsafe { (*gr).gr_gid })
+        }
+        None => passwd.map(|(_, gid)| gid),
+    };
+
+    // The group has to be changed first, as it is no longer permitted after setuid
+    if let Some(gid) = gid {
+        // SAFETY: plain syscalls with valid arguments
+        if unsafe { libc::setgroups(1, &gid) } != 0 {
+            return Err(anyhow::anyhow!(
+                "Failed to clear supplementary groups: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        if unsafe { libc::setgid(gid) } != 0 {
+            return Err(anyhow::anyhow!(
+                "Failed to switch to group {}: {}",
+                gid,
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+
+    if let Some((uid, _)) = passwd {
+        // SAFETY: plain syscall; setuid as root also sets the saved set-user-ID
+        if unsafe { libc::setuid(uid) } != 0 {
+            return Err(anyhow::anyhow!(
+                "Failed to switch to user {}: {}",
+                uid,
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        verify_not_root()?;
+    }
+
+    println!(
+        "Dropped privileges to uid={} gid={}",
+        unsafe { libc::getuid() },
+        unsafe { libc::getgid() }
+    );
+
+    Ok(())
+}
+
+/// Make sure root cannot be regained after switching user
+#[cfg(unix)]
+fn verify_not_root() -> Result<()> {
+    // SAFETY: plain syscalls
+    let (uid, euid) = unsafe { (libc::getuid(), libc::geteuid()) };
+    if uid == 0 || euid == 0 {
+        return Err(anyhow::anyhow!(
+            "Still running as root after dropping privileges, refusing to continue"
+        ));
+    }
+
+    // If this succeeds, the saved set-user-ID was still root
+    if unsafe { libc::setuid(0) } == 0 {
+        return Err(anyhow::anyhow!(
+            "Able to regain root after dropping privileges, refusing to continue"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn drop_privileges(_user: Option<&str>, _group: Option<&str>) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "--user and --group are only supported on Unix platforms"
+    ))
+}
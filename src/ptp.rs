@@ -1,25 +1,38 @@
 use anyhow::Result;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     net::IpAddr,
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
     bounded_vec::BoundedVec,
+    filter::PacketFilter,
+    source::{CaptureMode, RedundancyLan, RedundancyProtocol, RedundancyTag, TunnelInfo},
     types::{
         AnnounceMessage, ClockIdentity, DelayRespMessage, FollowUpMessage,
-        PDelayRespFollowUpMessage, PDelayRespMessage, ParsedPacket, PtpClockAccuracy,
-        PtpClockClass, PtpCorrectionField, PtpHeader, PtpMessage, PtpTimestamp, PtpUtcOffset,
+        MANAGEMENT_ACTION_RESPONSE, MacAddress, PDelayRespFollowUpMessage, PDelayRespMessage,
+        ParsedPacket, PortIdentity, PtpClockAccuracy, PtpClockClass, PtpCorrectionField, PtpHeader,
+        PtpHeaderFlags, PtpLogInterval, PtpMessage, PtpMessageType, PtpTimestamp, PtpUtcOffset,
         PtpVersion, SyncMessage,
     },
 };
 
 use std::sync::Arc;
 
+/// Announce interval assumed when a transmitter doesn't advertise one (logAnnounceInterval 1)
+const DEFAULT_ANNOUNCE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default announceReceiptTimeout, in announce intervals
+pub const DEFAULT_ANNOUNCE_RECEIPT_TIMEOUT: u8 = 3;
+
+/// Announces needed within `FOREIGN_MASTER_TIME_WINDOW` intervals to qualify a transmitter
+/// for the BMCA (IEEE 1588-2019 9.3.2.4.4)
+const FOREIGN_MASTER_THRESHOLD: usize = 2;
+const FOREIGN_MASTER_TIME_WINDOW: u32 = 4;
+
 #[derive(Debug, Clone, Default)]
 pub struct PtpHostStateTimeTransmitter {
-    pub last_sync_timestamp: Option<Instant>,
     pub priority1: Option<u8>,
     pub priority2: Option<u8>,
     pub clock_class: Option<PtpClockClass>,
@@ -35,16 +48,39 @@ pub struct PtpHostStateTimeTransmitter {
     /// True if this transmitter has been selected as the Best Master Clock in its domain
     /// BMCA winners are displayed as "GM" (Grandmaster) in the UI
     pub is_bmca_winner: bool,
+    /// True if this port announces another clock's time, i.e. it is a boundary clock port
+    /// relaying the grandmaster rather than a grandmaster candidate. Displayed as "BC".
+    pub is_relay: bool,
+    /// Capture time of the last Announce
+    pub last_announce_time: Option<SystemTime>,
+    /// Advertised logAnnounceInterval
+    pub announce_interval: Option<PtpLogInterval>,
+    /// Capture times of the most recent Announces, for foreign master qualification
+    recent_announce_times: VecDeque<SystemTime>,
+    /// The dataset of the last Announce
+    pub dataset: Option<AnnouncedDataset>,
+    /// Capture time of the first Announce since the port started announcing
+    pub first_announce_time: Option<SystemTime>,
+    /// Capture time of the last Sync
+    pub last_sync_time: Option<SystemTime>,
 }
 
 impl PtpHostStateTimeTransmitter {
-    fn from_announce(announce: &AnnounceMessage) -> Self {
+    fn from_announce(announce: &AnnounceMessage, time: SystemTime) -> Self {
         let mut s = PtpHostStateTimeTransmitter::default();
-        s.update_from_announce(announce);
+        s.update_from_announce(announce, time);
         s
     }
 
-    fn update_from_announce(&mut self, msg: &AnnounceMessage) {
+    fn update_from_announce(&mut self, msg: &AnnounceMessage, time: SystemTime) {
+        self.first_announce_time.get_or_insert(time);
+        self.last_announce_time = Some(time);
+        self.announce_interval = Some(msg.header.log_message_interval);
+        self.recent_announce_times.push_back(time);
+        if self.recent_announce_times.len() > FOREIGN_MASTER_THRESHOLD {
+            self.recent_announce_times.pop_front();
+        }
+
         self.priority1 = Some(msg.priority1);
         self.priority2 = Some(msg.priority2);
         self.clock_class = Some(msg.clock_class);
@@ -53,19 +89,47 @@ impl PtpHostStateTimeTransmitter {
         self.steps_removed = Some(msg.steps_removed);
         self.time_source = Some(msg.time_source);
         self.gm_identifier = Some(msg.gm_identity);
+        self.is_relay = msg.steps_removed > 0
+            && msg.gm_identity != msg.header.source_port_identity.clock_identity;
         self.current_utc_offset = Some(msg.current_utc_offset);
         self.last_announce_origin_timestamp = Some(msg.origin_timestamp);
+        self.dataset = Some(AnnouncedDataset::from_announce(msg));
+    }
+
+    /// The advertised announce interval, or the default if it isn't specified
+    pub fn announce_interval_duration(&self) -> Duration {
+        self.announce_interval
+            .and_then(|interval| interval.seconds())
+            .map_or(DEFAULT_ANNOUNCE_INTERVAL, Duration::from_secs_f64)
+    }
+
+    /// No Announce within `receipt_timeout` announce intervals
+    pub fn is_announce_timed_out(&self, now: SystemTime, receipt_timeout: u8) -> bool {
+        self.last_announce_time.is_some_and(|last| {
+            now.duration_since(last).unwrap_or_default()
+                > self.announce_interval_duration() * receipt_timeout as u32
+        })
+    }
+
+    /// Enough recent Announces to qualify as a foreign master
+    pub fn is_qualified(&self, now: SystemTime) -> bool {
+        let window = self.announce_interval_duration() * FOREIGN_MASTER_TIME_WINDOW;
+        self.recent_announce_times.len() >= FOREIGN_MASTER_THRESHOLD
+            && self
+                .recent_announce_times
+                .iter()
+                .all(|t| now.duration_since(*t).unwrap_or_default() <= window)
     }
 
-    fn from_sync(msg: &SyncMessage) -> Self {
+    fn from_sync(msg: &SyncMessage, time: SystemTime) -> Self {
         let mut s = PtpHostStateTimeTransmitter::default();
-        s.update_from_sync(msg);
+        s.update_from_sync(msg, time);
         s
     }
 
-    fn update_from_sync(&mut self, msg: &SyncMessage) {
+    fn update_from_sync(&mut self, msg: &SyncMessage, time: SystemTime) {
         self.last_sync_origin_timestamp = Some(msg.origin_timestamp);
-        self.last_sync_timestamp = Some(Instant::now());
+        self.last_sync_time = Some(time);
     }
 
     fn from_follow_up(msg: &FollowUpMessage) -> Self {
@@ -78,108 +142,495 @@ impl PtpHostStateTimeTransmitter {
         self.last_followup_origin_timestamp = Some(msg.precise_origin_timestamp);
     }
 
+    /// The BMCA dataset of this transmitter's Announce, sent from the given port. None until
+    /// an Announce has been seen.
+    pub fn bmca_dataset(&self, sender: PortIdentity) -> Option<BmcaDataset> {
+        Some(BmcaDataset {
+            gm_identity: self.gm_identifier?,
+            gm_priority1: self.priority1?,
+            gm_clock_class: self.clock_class?.class(),
+            gm_clock_accuracy: self.clock_accuracy?.accuracy,
+            gm_variance: self.offset_scaled_log_variance?,
+            gm_priority2: self.priority2?,
+            steps_removed: self.steps_removed?,
+            sender,
+            receiver: None,
+        })
+    }
+
     /// Compare this transmitter with another for BMCA (Best Master Clock Algorithm)
     ///
-    /// Implements IEEE 1588 BMCA comparison algorithm. Returns std::cmp::Ordering where:
+    /// Returns std::cmp::Ordering where:
     /// - Less = this transmitter is better (should win)
     /// - Greater = other transmitter is better
-    /// - Equal = transmitters are equivalent (shouldn't happen due to clock identity tiebreaker)
-    ///
-    /// Comparison order follows IEEE 1588 specification:
-    /// 1. Priority1 (lower wins)
-    /// 2. Clock Class (lower wins)
-    /// 3. Clock Accuracy (lower wins)
-    /// 4. Offset Scaled Log Variance (lower wins)
-    /// 5. Priority2 (lower wins)
-    /// 6. Clock Identity (lower wins, final tiebreaker)
+    /// - Equal = both are the same Announce path (error cases of the comparison)
     ///
-    /// If a transmitter has data and the other doesn't, the one with data wins.
+    /// Transmitters with Announce data are compared with the full dataset comparison
+    /// (see `BmcaDataset::compare`). A transmitter with Announce data wins over one without,
+    /// and two without are ordered by port identity.
     pub fn compare_for_bmca(
         &self,
         other: &Self,
-        our_clock_id: ClockIdentity,
-        other_clock_id: ClockIdentity,
+        our_port: PortIdentity,
+        other_port: PortIdentity,
     ) -> std::cmp::Ordering {
         use std::cmp::Ordering;
 
-        // 1. Priority1 comparison (lower is better)
-        if let (Some(our_p1), Some(other_p1)) = (self.priority1, other.priority1) {
-            match our_p1.cmp(&other_p1) {
-                Ordering::Equal => {}
-                other => return other,
-            }
-        } else if self.priority1.is_some() && other.priority1.is_none() {
-            return Ordering::Less; // We have priority1, other doesn't
-        } else if self.priority1.is_none() && other.priority1.is_some() {
-            return Ordering::Greater; // Other has priority1, we don't
+        match (self.bmca_dataset(our_port), other.bmca_dataset(other_port)) {
+            (Some(ours), Some(theirs)) => ours.compare(&theirs).ordering(),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => our_port.cmp(&other_port),
         }
+    }
+}
 
-        // 2. Clock Class comparison (lower is better)
-        if let (Some(our_cc), Some(other_cc)) = (self.clock_class, other.clock_class) {
-            match our_cc.class().cmp(&other_cc.class()) {
-                Ordering::Equal => {}
-                other => return other,
-            }
-        } else if self.clock_class.is_some() && other.clock_class.is_none() {
-            return Ordering::Less; // We have clock class, other doesn't
-        } else if self.clock_class.is_none() && other.clock_class.is_some() {
-            return Ordering::Greater; // Other has clock class, we don't
+/// Result of comparing BMCA datasets A and B (IEEE 1588-2019 Figures 34 and 35)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BmcaComparison {
+    ABetter,
+    ABetterByTopology,
+    BBetter,
+    BBetterByTopology,
+    /// A was sent by the clock that received it (error-1)
+    Error1,
+    /// A and B are the same Announce path (error-2)
+    Error2,
+}
+
+impl BmcaComparison {
+    pub fn ordering(self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        match self {
+            BmcaComparison::ABetter | BmcaComparison::ABetterByTopology => Ordering::Less,
+            BmcaComparison::BBetter | BmcaComparison::BBetterByTopology => Ordering::Greater,
+            BmcaComparison::Error1 | BmcaComparison::Error2 => Ordering::Equal,
+        }
+    }
+}
+
+/// The data of an Announce message compared by the BMCA (IEEE 1588-2019 9.3.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BmcaDataset {
+    pub gm_identity: ClockIdentity,
+    pub gm_priority1: u8,
+    pub gm_clock_class: u8,
+    pub gm_clock_accuracy: u8,
+    pub gm_variance: u16,
+    pub gm_priority2: u8,
+    pub steps_removed: u16,
+    /// Port that sent the Announce
+    pub sender: PortIdentity,
+    /// Port that received the Announce. A passive observer doesn't know it, in which case
+    /// the comparisons that need it fall back to the path with fewer steps.
+    pub receiver: Option<PortIdentity>,
+}
+
+impl BmcaDataset {
+    /// Data set comparison algorithm, comparing `self` (A) with `other` (B)
+    pub fn compare(&self, other: &Self) -> BmcaComparison {
+        use BmcaComparison::*;
+        use std::cmp::Ordering;
+
+        // Part 1: different grandmasters, the better grandmaster wins
+        if self.gm_identity != other.gm_identity {
+            let quality = |d: &Self| {
+                (
+                    d.gm_priority1,
+                    d.gm_clock_class,
+                    d.gm_clock_accuracy,
+                    d.gm_variance,
+                    d.gm_priority2,
+                    d.gm_identity,
+                )
+            };
+            return if quality(self) < quality(other) {
+                ABetter
+            } else {
+                BBetter
+            };
+        }
+
+        // Part 2: the same grandmaster through different paths, the shorter path wins
+        let (a_steps, b_steps) = (self.steps_removed as u32, other.steps_removed as u32);
+        if a_steps > b_steps + 1 {
+            return BBetter;
+        }
+        if a_steps + 1 < b_steps {
+            return ABetter;
         }
 
-        // 3. Clock Accuracy comparison (lower is better)
-        if let (Some(our_acc), Some(other_acc)) = (self.clock_accuracy, other.clock_accuracy) {
-            match our_acc.accuracy.cmp(&other_acc.accuracy) {
-                Ordering::Equal => {}
-                other => return other,
+        // One step apart: the longer path may be our own Announce coming back
+        let returned_path = |d: &Self| {
+            d.receiver
+                .map(|receiver| receiver.clock_identity.cmp(&d.sender.clock_identity))
+        };
+        match a_steps.cmp(&b_steps) {
+            Ordering::Greater => {
+                return match returned_path(self) {
+                    Some(Ordering::Less) => BBetter,
+                    Some(Ordering::Greater) | None => BBetterByTopology,
+                    Some(Ordering::Equal) => Error1,
+                };
+            }
+            Ordering::Less => {
+                return match returned_path(other) {
+                    Some(Ordering::Less) => ABetter,
+                    Some(Ordering::Greater) | None => ABetterByTopology,
+                    Some(Ordering::Equal) => Error1,
+                };
             }
-        } else if self.clock_accuracy.is_some() && other.clock_accuracy.is_none() {
-            return Ordering::Less; // We have accuracy, other doesn't
-        } else if self.clock_accuracy.is_none() && other.clock_accuracy.is_some() {
-            return Ordering::Greater; // Other has accuracy, we don't
+            Ordering::Equal => {}
+        }
+
+        // Same number of steps: the lower sender, then the lower receiver port wins
+        match self.sender.cmp(&other.sender) {
+            Ordering::Less => ABetterByTopology,
+            Ordering::Greater => BBetterByTopology,
+            Ordering::Equal => match (self.receiver, other.receiver) {
+                (Some(a), Some(b)) if a.port_number < b.port_number => ABetterByTopology,
+                (Some(a), Some(b)) if a.port_number > b.port_number => BBetterByTopology,
+                _ => Error2,
+            },
+        }
+    }
+}
+
+/// The grandmaster properties announced by a transmitter port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnouncedDataset {
+    pub gm_identity: ClockIdentity,
+    pub priority1: u8,
+    pub priority2: u8,
+    pub clock_class: PtpClockClass,
+    pub clock_accuracy: PtpClockAccuracy,
+    pub offset_scaled_log_variance: u16,
+    pub time_source: u8,
+    pub current_utc_offset: PtpUtcOffset,
+    pub current_utc_offset_valid: bool,
+    /// Leap second announced for the end of the current UTC day
+    pub leap: Option<LeapSecond>,
+    pub ptp_timescale: bool,
+    pub time_traceable: bool,
+    pub frequency_traceable: bool,
+}
+
+impl AnnouncedDataset {
+    fn from_announce(msg: &AnnounceMessage) -> Self {
+        Self {
+            gm_identity: msg.gm_identity,
+            priority1: msg.priority1,
+            priority2: msg.priority2,
+            clock_class: msg.clock_class,
+            clock_accuracy: msg.clock_accuracy,
+            offset_scaled_log_variance: msg.offset_scaled_log_variance,
+            time_source: msg.time_source,
+            current_utc_offset: msg.current_utc_offset,
+            current_utc_offset_valid: msg.header.flags.current_utc_offset_valid(),
+            leap: LeapSecond::from_flags(&msg.header.flags),
+            ptp_timescale: msg.header.flags.ptp_timescale(),
+            time_traceable: msg.header.flags.time_traceable(),
+            frequency_traceable: msg.header.flags.frequency_traceable(),
+        }
+    }
+
+    /// The fields describing the distributed time rather than the clock quality
+    pub const TIME_PROPERTIES: [DatasetField; 6] = [
+        DatasetField::UtcOffset,
+        DatasetField::UtcOffsetValid,
+        DatasetField::Leap,
+        DatasetField::PtpTimescale,
+        DatasetField::TimeTraceable,
+        DatasetField::FrequencyTraceable,
+    ];
+
+    /// The value of a time property field, None for the other fields
+    pub fn time_property(&self, field: DatasetField) -> Option<String> {
+        match field {
+            DatasetField::UtcOffset => Some(self.current_utc_offset.to_string()),
+            DatasetField::UtcOffsetValid => Some(self.current_utc_offset_valid.to_string()),
+            DatasetField::Leap => Some(
+                self.leap
+                    .map_or("none".to_string(), |leap| leap.to_string()),
+            ),
+            DatasetField::PtpTimescale => Some(self.ptp_timescale.to_string()),
+            DatasetField::TimeTraceable => Some(self.time_traceable.to_string()),
+            DatasetField::FrequencyTraceable => Some(self.frequency_traceable.to_string()),
+            _ => None,
         }
+    }
+
+    /// How the clock quality (class and accuracy) changed since `old`, degradations first.
+    /// None if neither changed.
+    pub fn quality_change_from(&self, old: &Self) -> Option<DatasetChangeDirection> {
+        let directions: Vec<DatasetChangeDirection> = self
+            .changes_from(old)
+            .into_iter()
+            .filter(|change| {
+                matches!(
+                    change.field,
+                    DatasetField::ClockClass | DatasetField::ClockAccuracy
+                )
+            })
+            .map(|change| change.direction)
+            .collect();
+        [
+            DatasetChangeDirection::Degraded,
+            DatasetChangeDirection::Improved,
+        ]
+        .into_iter()
+        .find(|direction| directions.contains(direction))
+    }
+
+    /// The fields that differ from `old`, classified as degradation or improvement where the
+    /// field has a quality order. Leap second announcements have events of their own.
+    pub fn changes_from(&self, old: &Self) -> Vec<DatasetChange> {
+        use DatasetChangeDirection::*;
 
-        // 4. Offset Scaled Log Variance comparison (lower is better)
-        if let (Some(our_var), Some(other_var)) = (
-            self.offset_scaled_log_variance,
-            other.offset_scaled_log_variance,
+        let ordered = |worse: bool| if worse { Degraded } else { Improved };
+        // Higher values are worse for accuracy and variance, as in the BMCA
+        let class_worse = match (
+            self.clock_class.degradation_rank(),
+            old.clock_class.degradation_rank(),
         ) {
-            match our_var.cmp(&other_var) {
-                Ordering::Equal => {}
-                other => return other,
+            (Some(new), Some(old)) => new > old,
+            _ => self.clock_class.class() > old.clock_class.class(),
+        };
+
+        let mut changes = Vec::new();
+        let mut push = |field, changed: bool, old: String, new: String, direction| {
+            if changed {
+                changes.push(DatasetChange {
+                    field,
+                    old,
+                    new,
+                    direction,
+                });
             }
-        } else if self.offset_scaled_log_variance.is_some()
-            && other.offset_scaled_log_variance.is_none()
-        {
-            return Ordering::Less; // We have variance, other doesn't
-        } else if self.offset_scaled_log_variance.is_none()
-            && other.offset_scaled_log_variance.is_some()
-        {
-            return Ordering::Greater; // Other has variance, we don't
+        };
+        push(
+            DatasetField::GmIdentity,
+            self.gm_identity != old.gm_identity,
+            old.gm_identity.to_string(),
+            self.gm_identity.to_string(),
+            Changed,
+        );
+        push(
+            DatasetField::Priority1,
+            self.priority1 != old.priority1,
+            old.priority1.to_string(),
+            self.priority1.to_string(),
+            Changed,
+        );
+        push(
+            DatasetField::Priority2,
+            self.priority2 != old.priority2,
+            old.priority2.to_string(),
+            self.priority2.to_string(),
+            Changed,
+        );
+        push(
+            DatasetField::ClockClass,
+            self.clock_class != old.clock_class,
+            old.clock_class.class().to_string(),
+            self.clock_class.class().to_string(),
+            ordered(class_worse),
+        );
+        push(
+            DatasetField::ClockAccuracy,
+            self.clock_accuracy != old.clock_accuracy,
+            old.clock_accuracy.to_string(),
+            self.clock_accuracy.to_string(),
+            ordered(self.clock_accuracy.accuracy > old.clock_accuracy.accuracy),
+        );
+        push(
+            DatasetField::Variance,
+            self.offset_scaled_log_variance != old.offset_scaled_log_variance,
+            old.offset_scaled_log_variance.to_string(),
+            self.offset_scaled_log_variance.to_string(),
+            ordered(self.offset_scaled_log_variance > old.offset_scaled_log_variance),
+        );
+        push(
+            DatasetField::TimeSource,
+            self.time_source != old.time_source,
+            format!("0x{:02x}", old.time_source),
+            format!("0x{:02x}", self.time_source),
+            Changed,
+        );
+        push(
+            DatasetField::UtcOffset,
+            self.current_utc_offset != old.current_utc_offset,
+            old.current_utc_offset.to_string(),
+            self.current_utc_offset.to_string(),
+            Changed,
+        );
+        push(
+            DatasetField::UtcOffsetValid,
+            self.current_utc_offset_valid != old.current_utc_offset_valid,
+            old.current_utc_offset_valid.to_string(),
+            self.current_utc_offset_valid.to_string(),
+            ordered(!self.current_utc_offset_valid),
+        );
+        push(
+            DatasetField::PtpTimescale,
+            self.ptp_timescale != old.ptp_timescale,
+            old.ptp_timescale.to_string(),
+            self.ptp_timescale.to_string(),
+            Changed,
+        );
+        push(
+            DatasetField::TimeTraceable,
+            self.time_traceable != old.time_traceable,
+            old.time_traceable.to_string(),
+            self.time_traceable.to_string(),
+            ordered(!self.time_traceable),
+        );
+        push(
+            DatasetField::FrequencyTraceable,
+            self.frequency_traceable != old.frequency_traceable,
+            old.frequency_traceable.to_string(),
+            self.frequency_traceable.to_string(),
+            ordered(!self.frequency_traceable),
+        );
+        changes
+    }
+}
+
+/// A leap second announced by the leap61/leap59 flags, taking effect at the end of the
+/// current UTC day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LeapSecond {
+    /// The last minute of the day has 61 seconds
+    Insert,
+    /// The last minute of the day has 59 seconds
+    Delete,
+}
+
+impl LeapSecond {
+    pub fn from_flags(flags: &PtpHeaderFlags) -> Option<Self> {
+        match (flags.leap61(), flags.leap59()) {
+            (true, false) => Some(LeapSecond::Insert),
+            (false, true) => Some(LeapSecond::Delete),
+            // Both set is invalid, treat it as no announcement
+            _ => None,
         }
+    }
 
-        // 5. Priority2 comparison (lower is better)
-        if let (Some(our_p2), Some(other_p2)) = (self.priority2, other.priority2) {
-            match our_p2.cmp(&other_p2) {
-                Ordering::Equal => {}
-                other => return other,
-            }
-        } else if self.priority2.is_some() && other.priority2.is_none() {
-            return Ordering::Less; // We have priority2, other doesn't
-        } else if self.priority2.is_none() && other.priority2.is_some() {
-            return Ordering::Greater; // Other has priority2, we don't
+    /// How the UTC offset changes once the leap second took effect
+    pub fn offset_change(&self) -> i16 {
+        match self {
+            LeapSecond::Insert => 1,
+            LeapSecond::Delete => -1,
+        }
+    }
+}
+
+impl std::fmt::Display for LeapSecond {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeapSecond::Insert => write!(f, "+1s"),
+            LeapSecond::Delete => write!(f, "-1s"),
+        }
+    }
+}
+
+/// The next UTC midnight after `time`, when an announced leap second takes effect
+pub fn next_utc_midnight(time: SystemTime) -> SystemTime {
+    const DAY: u64 = 86_400;
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    UNIX_EPOCH + Duration::from_secs((secs / DAY + 1) * DAY)
+}
+
+/// A field of the announced dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DatasetField {
+    GmIdentity,
+    Priority1,
+    Priority2,
+    ClockClass,
+    ClockAccuracy,
+    Variance,
+    TimeSource,
+    UtcOffset,
+    UtcOffsetValid,
+    Leap,
+    PtpTimescale,
+    TimeTraceable,
+    FrequencyTraceable,
+}
+
+impl std::fmt::Display for DatasetField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DatasetField::GmIdentity => "gm_identity",
+            DatasetField::Priority1 => "priority1",
+            DatasetField::Priority2 => "priority2",
+            DatasetField::ClockClass => "clock_class",
+            DatasetField::ClockAccuracy => "clock_accuracy",
+            DatasetField::Variance => "variance",
+            DatasetField::TimeSource => "time_source",
+            DatasetField::UtcOffset => "utc_offset",
+            DatasetField::UtcOffsetValid => "utc_offset_valid",
+            DatasetField::Leap => "leap",
+            DatasetField::PtpTimescale => "ptp_timescale",
+            DatasetField::TimeTraceable => "time_traceable",
+            DatasetField::FrequencyTraceable => "frequency_traceable",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Whether a dataset change makes the grandmaster worse or better
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetChangeDirection {
+    Degraded,
+    Improved,
+    /// The field has no quality order, e.g. priorities or the UTC offset
+    Changed,
+}
+
+impl std::fmt::Display for DatasetChangeDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatasetChangeDirection::Degraded => write!(f, "degraded"),
+            DatasetChangeDirection::Improved => write!(f, "improved"),
+            DatasetChangeDirection::Changed => write!(f, "changed"),
         }
+    }
+}
 
-        // 6. Clock Identity comparison (lower is better - used as tiebreaker)
-        our_clock_id.clock_id.cmp(&other_clock_id.clock_id)
+/// A field that differs between two announced datasets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetChange {
+    pub field: DatasetField,
+    pub old: String,
+    pub new: String,
+    pub direction: DatasetChangeDirection,
+}
+
+impl std::fmt::Display for DatasetChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} ({})",
+            self.field, self.old, self.new, self.direction
+        )
     }
 }
 
+/// Announced datasets kept per grandmaster
+const GM_DATASET_HISTORY: usize = 64;
+
 #[derive(Debug, Clone)]
 pub struct PtpHostStateTimeReceiver {
     pub last_delay_response_origin_timestamp: Option<PtpTimestamp>,
     pub last_pdelay_response_origin_timestamp: Option<PtpTimestamp>,
     pub last_pdelay_follow_up_timestamp: Option<PtpTimestamp>,
-    pub selected_transmitter_identity: Option<ClockIdentity>,
+    pub selected_transmitter_identity: Option<PortIdentity>,
     pub selected_transmitter_confidence: f32, // 0.0 to 1.0 confidence score
 }
 
@@ -196,13 +647,13 @@ impl Default for PtpHostStateTimeReceiver {
 }
 
 impl PtpHostStateTimeReceiver {
-    fn from_recent_sync_sender(recent_sync_sender: ClockIdentity, age: Duration) -> Self {
+    fn from_recent_sync_sender(recent_sync_sender: PortIdentity, age: Duration) -> Self {
         let mut s = PtpHostStateTimeReceiver::default();
         s.update_from_recent_sync_sender(recent_sync_sender, age);
         s
     }
 
-    fn update_from_recent_sync_sender(&mut self, recent_sync_sender: ClockIdentity, age: Duration) {
+    fn update_from_recent_sync_sender(&mut self, recent_sync_sender: PortIdentity, age: Duration) {
         if self.selected_transmitter_confidence < 1.0 {
             // We assume that the most recent sync sender in this domain is the chosen transmitter
             self.selected_transmitter_identity = Some(recent_sync_sender);
@@ -213,6 +664,23 @@ impl PtpHostStateTimeReceiver {
         }
     }
 
+    fn from_link_peer(transmitter: PortIdentity, confidence: f32) -> Self {
+        let mut s = PtpHostStateTimeReceiver::default();
+        s.update_from_link_peer(transmitter, confidence);
+        s
+    }
+
+    fn update_from_link_peer(&mut self, transmitter: PortIdentity, confidence: f32) {
+        // A Delay_Resp names the transmitter for sure, peering only makes it likely
+        if self.selected_transmitter_confidence < 1.0
+            && (confidence >= self.selected_transmitter_confidence
+                || self.selected_transmitter_identity == Some(transmitter))
+        {
+            self.selected_transmitter_identity = Some(transmitter);
+            self.selected_transmitter_confidence = confidence;
+        }
+    }
+
     fn from_delay_resp(msg: &DelayRespMessage) -> Self {
         let mut s = PtpHostStateTimeReceiver::default();
         s.update_from_delay_resp(msg);
@@ -221,26 +689,14 @@ impl PtpHostStateTimeReceiver {
 
     fn update_from_delay_resp(&mut self, msg: &DelayRespMessage) {
         self.last_delay_response_origin_timestamp = Some(msg.receive_timestamp);
-        self.selected_transmitter_identity = Some(msg.header.source_port_identity.clock_identity);
+        self.selected_transmitter_identity = Some(msg.header.source_port_identity);
         self.selected_transmitter_confidence = 1.0;
     }
 
-    fn from_pdelay_resp(msg: &PDelayRespMessage) -> Self {
-        let mut s = PtpHostStateTimeReceiver::default();
-        s.update_from_pdelay_resp(msg);
-        s
-    }
-
     fn update_from_pdelay_resp(&mut self, msg: &PDelayRespMessage) {
         self.last_pdelay_response_origin_timestamp = Some(msg.request_receipt_timestamp);
     }
 
-    fn from_pdelay_resp_follow_up(msg: &PDelayRespFollowUpMessage) -> Self {
-        let mut s = PtpHostStateTimeReceiver::default();
-        s.update_from_pdelay_resp_follow_up(msg);
-        s
-    }
-
     fn update_from_pdelay_resp_follow_up(&mut self, msg: &PDelayRespFollowUpMessage) {
         self.last_pdelay_follow_up_timestamp = Some(msg.response_origin_timestamp);
     }
@@ -252,6 +708,9 @@ pub enum PtpHostState {
     Listening,
     TimeTransmitter(PtpHostStateTimeTransmitter),
     TimeReceiver(PtpHostStateTimeReceiver),
+    /// A transmitter whose Announces stopped for longer than the announceReceiptTimeout.
+    /// Keeps the last announced data but no longer takes part in the BMCA.
+    Stale(PtpHostStateTimeTransmitter),
 }
 
 impl std::fmt::Display for PtpHostState {
@@ -261,44 +720,68 @@ impl std::fmt::Display for PtpHostState {
             PtpHostState::TimeTransmitter(s) => {
                 if s.is_bmca_winner {
                     write!(f, "Grandmaster")
+                } else if s.is_relay {
+                    write!(f, "Boundary Clock")
                 } else {
                     write!(f, "Time Transmitter")
                 }
             }
             PtpHostState::TimeReceiver(_) => write!(f, "Time Receiver"),
+            PtpHostState::Stale(_) => write!(f, "Stale Time Transmitter"),
         }
     }
 }
 
 impl PtpHostState {
     /// Transition to TimeTransmitter state
-    fn update_from_announce(&mut self, announce: &AnnounceMessage) {
+    fn update_from_announce(&mut self, announce: &AnnounceMessage, time: SystemTime) {
         match self {
             PtpHostState::TimeTransmitter(state) => {
-                state.update_from_announce(announce);
+                state.update_from_announce(announce, time);
+            }
+            PtpHostState::Stale(state) => {
+                // Announcing again, it has to qualify anew before it takes part in the BMCA
+                let mut state = std::mem::take(state);
+                state.first_announce_time = None;
+                state.update_from_announce(announce, time);
+                *self = PtpHostState::TimeTransmitter(state);
             }
             _ => {
                 *self = PtpHostState::TimeTransmitter(PtpHostStateTimeTransmitter::from_announce(
-                    announce,
+                    announce, time,
                 ));
             }
         }
     }
 
-    fn update_from_sync(&mut self, msg: &SyncMessage) {
+    /// Transition a transmitter that stopped announcing to the Stale state
+    fn expire_announce(&mut self, now: SystemTime, receipt_timeout: u8) {
+        if let PtpHostState::TimeTransmitter(state) = self
+            && state.is_announce_timed_out(now, receipt_timeout)
+        {
+            let mut state = std::mem::take(state);
+            state.is_bmca_winner = false;
+            *self = PtpHostState::Stale(state);
+        }
+    }
+
+    fn update_from_sync(&mut self, msg: &SyncMessage, time: SystemTime) {
         match self {
-            PtpHostState::TimeTransmitter(state) => {
-                state.update_from_sync(msg);
+            // Syncs alone don't revive a stale transmitter, only Announces do
+            PtpHostState::TimeTransmitter(state) | PtpHostState::Stale(state) => {
+                state.update_from_sync(msg, time);
             }
             _ => {
-                *self = PtpHostState::TimeTransmitter(PtpHostStateTimeTransmitter::from_sync(msg));
+                *self = PtpHostState::TimeTransmitter(PtpHostStateTimeTransmitter::from_sync(
+                    msg, time,
+                ));
             }
         }
     }
 
     fn update_from_follow_up(&mut self, msg: &FollowUpMessage) {
         match self {
-            PtpHostState::TimeTransmitter(state) => {
+            PtpHostState::TimeTransmitter(state) | PtpHostState::Stale(state) => {
                 state.update_from_follow_up(msg);
             }
             _ => {
@@ -309,7 +792,7 @@ impl PtpHostState {
     }
 
     // Transition to TimeReceiver state
-    fn update_from_recent_sync_sender(&mut self, recent_sync_sender: ClockIdentity, age: Duration) {
+    fn update_from_recent_sync_sender(&mut self, recent_sync_sender: PortIdentity, age: Duration) {
         match self {
             PtpHostState::TimeReceiver(state) => {
                 state.update_from_recent_sync_sender(recent_sync_sender, age);
@@ -333,27 +816,45 @@ impl PtpHostState {
         }
     }
 
-    fn update_from_pdelay_resp(&mut self, msg: &PDelayRespMessage) {
+    /// Transition to TimeReceiver state, synchronized by the Sync sender on the other end
+    /// of a peer-to-peer link
+    fn update_from_link_peer(&mut self, transmitter: PortIdentity, confidence: f32) {
         match self {
             PtpHostState::TimeReceiver(state) => {
-                state.update_from_pdelay_resp(msg);
+                state.update_from_link_peer(transmitter, confidence);
             }
-            _ => {
-                *self = PtpHostState::TimeReceiver(PtpHostStateTimeReceiver::from_pdelay_resp(msg));
+            PtpHostState::Listening => {
+                *self = PtpHostState::TimeReceiver(PtpHostStateTimeReceiver::from_link_peer(
+                    transmitter,
+                    confidence,
+                ));
             }
+            _ => {}
+        }
+    }
+
+    // Every port of a P2P link measures the peer delay, transmitters included, so the
+    // responses don't establish a role, see `PtpTracker::infer_link_receivers`
+    fn update_from_pdelay_resp(&mut self, msg: &PDelayRespMessage) {
+        if let PtpHostState::TimeReceiver(state) = self {
+            state.update_from_pdelay_resp(msg);
         }
     }
 
     fn update_from_pdelay_resp_follow_up(&mut self, msg: &PDelayRespFollowUpMessage) {
+        if let PtpHostState::TimeReceiver(state) = self {
+            state.update_from_pdelay_resp_follow_up(msg);
+        }
+    }
+
+    /// Order used to summarise the states of a clock's ports, higher wins
+    fn precedence(&self) -> u8 {
         match self {
-            PtpHostState::TimeReceiver(state) => {
-                state.update_from_pdelay_resp_follow_up(msg);
-            }
-            _ => {
-                *self = PtpHostState::TimeReceiver(
-                    PtpHostStateTimeReceiver::from_pdelay_resp_follow_up(msg),
-                );
-            }
+            PtpHostState::Listening => 0,
+            PtpHostState::Stale(_) => 1,
+            PtpHostState::TimeReceiver(_) => 2,
+            PtpHostState::TimeTransmitter(state) if state.is_bmca_winner => 4,
+            PtpHostState::TimeTransmitter(_) => 3,
         }
     }
 
@@ -363,129 +864,2073 @@ impl PtpHostState {
             PtpHostState::TimeTransmitter(state) => {
                 if state.is_bmca_winner {
                     "GM"
+                } else if state.is_relay {
+                    "BC"
                 } else {
                     "TT"
                 }
             }
             PtpHostState::TimeReceiver(_) => "TR",
+            PtpHostState::Stale(_) => "ST",
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PtpHost {
-    pub clock_identity: ClockIdentity,
-    pub ip_addresses: HashMap<IpAddr, Vec<String>>,
-    pub interfaces: HashSet<String>, // For gPTP hosts without IP addresses
-    pub vlan_id: Option<u16>,
-    pub domain_number: Option<u8>,
-    pub last_version: Option<PtpVersion>,
-    pub last_seen: SystemTime,
+/// Sync intervals without a Sync after which a transmitter port counts as not sending Sync
+const SYNC_RECEIPT_INTERVALS: u32 = 3;
 
-    pub announce_count: u32,
-    pub sync_count: u32,
-    pub follow_up_count: u32,
-    pub delay_req_count: u32,
-    pub delay_resp_count: u32,
-    pub pdelay_req_count: u32,
-    pub pdelay_resp_count: u32,
-    pub pdelay_resp_follow_up_count: u32,
-    pub total_messages_sent_count: u32,
-    pub total_messages_received_count: u32,
-    pub signaling_message_count: u32,
-    pub management_message_count: u32,
+/// Sync interval assumed when a port doesn't advertise one (logSyncInterval 0)
+const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(1);
 
-    pub state: PtpHostState,
-    pub last_correction_field: Option<PtpCorrectionField>,
-    pub packet_history: BoundedVec<Arc<ParsedPacket>>,
-}
+/// Two-step Syncs without any Follow_Up after which a transmitter port is considered faulty
+const FAULTY_MISSING_FOLLOW_UPS: u32 = 8;
 
-impl PtpHost {
-    pub fn new(clock_identity: ClockIdentity) -> Self {
-        Self {
-            clock_identity,
-            ip_addresses: HashMap::new(),
-            interfaces: HashSet::new(),
-            vlan_id: None,
-            domain_number: None,
-            last_seen: SystemTime::now(),
+/// How long a portState from a PORT_DATA_SET management response is trusted
+const MANAGEMENT_STATE_MAX_AGE: Duration = Duration::from_secs(60);
 
-            announce_count: 0,
-            sync_count: 0,
-            follow_up_count: 0,
-            delay_req_count: 0,
-            delay_resp_count: 0,
-            pdelay_req_count: 0,
-            pdelay_resp_count: 0,
-            pdelay_resp_follow_up_count: 0,
-            total_messages_sent_count: 0,
-            total_messages_received_count: 0,
-            signaling_message_count: 0,
-            management_message_count: 0,
+/// IEEE 1588 port state (IEEE 1588-2019 9.2.5), with the inclusive terms for the
+/// transmitter and receiver states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PortState {
+    Initializing,
+    Faulty,
+    Disabled,
+    #[default]
+    Listening,
+    PreTimeTransmitter,
+    TimeTransmitter,
+    Passive,
+    Uncalibrated,
+    TimeReceiver,
+}
 
-            state: PtpHostState::Listening,
-            last_version: None,
-            last_correction_field: None,
-            packet_history: BoundedVec::new(1000), // Default max history
+impl PortState {
+    /// The portState enumeration of the PORT_DATA_SET
+    pub fn from_port_data_set(state: u8) -> Option<Self> {
+        match state {
+            1 => Some(PortState::Initializing),
+            2 => Some(PortState::Faulty),
+            3 => Some(PortState::Disabled),
+            4 => Some(PortState::Listening),
+            5 => Some(PortState::PreTimeTransmitter),
+            6 => Some(PortState::TimeTransmitter),
+            7 => Some(PortState::Passive),
+            8 => Some(PortState::Uncalibrated),
+            9 => Some(PortState::TimeReceiver),
+            _ => None,
         }
     }
 
-    fn update_from_ptp_header(&mut self, header: &PtpHeader) {
-        self.domain_number = Some(header.domain_number);
-        self.last_version = Some(header.version);
-        self.last_correction_field = Some(header.correction_field);
+    pub fn short_string(&self) -> &'static str {
+        match self {
+            PortState::Initializing => "IN",
+            PortState::Faulty => "FA",
+            PortState::Disabled => "DI",
+            PortState::Listening => "L",
+            PortState::PreTimeTransmitter => "PT",
+            PortState::TimeTransmitter => "TT",
+            PortState::Passive => "PA",
+            PortState::Uncalibrated => "UC",
+            PortState::TimeReceiver => "TR",
+        }
     }
 
-    pub fn get_vendor_name(&self) -> Option<&'static str> {
-        self.clock_identity.extract_vendor_name()
+    /// States that the role summary in `PtpHostState` doesn't tell apart
+    pub fn refines_role(&self) -> bool {
+        !matches!(
+            self,
+            PortState::Listening | PortState::TimeTransmitter | PortState::TimeReceiver
+        )
     }
+}
 
-    pub fn is_transmitter(&self) -> bool {
-        matches!(self.state, PtpHostState::TimeTransmitter(_))
+impl std::fmt::Display for PortState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PortState::Initializing => "Initializing",
+            PortState::Faulty => "Faulty",
+            PortState::Disabled => "Disabled",
+            PortState::Listening => "Listening",
+            PortState::PreTimeTransmitter => "Pre-Time Transmitter",
+            PortState::TimeTransmitter => "Time Transmitter",
+            PortState::Passive => "Passive",
+            PortState::Uncalibrated => "Uncalibrated",
+            PortState::TimeReceiver => "Time Receiver",
+        };
+        write!(f, "{}", name)
     }
+}
 
-    pub fn is_receiver(&self) -> bool {
-        matches!(self.state, PtpHostState::TimeReceiver(_))
-    }
+/// The port state of a port with the observations it was inferred from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InferredPortState {
+    pub state: PortState,
+    pub evidence: String,
+    /// Reported by the port itself in a PORT_DATA_SET management response
+    pub from_management: bool,
+}
 
-    pub fn time_since_last_seen(&self, reference_time: Option<SystemTime>) -> Duration {
-        let reference = reference_time.unwrap_or_else(SystemTime::now);
-        reference.duration_since(self.last_seen).unwrap_or_default()
+impl InferredPortState {
+    fn observed(state: PortState, evidence: String) -> Self {
+        Self {
+            state,
+            evidence,
+            from_management: false,
+        }
     }
+}
 
-    pub fn add_ip_address(&mut self, ip: IpAddr, vlan_id: Option<u16>, interface: String) {
-        self.ip_addresses.entry(ip).or_default();
-        self.vlan_id = vlan_id;
+/// How long the first copy of a PRP/HSR frame is remembered while waiting for the
+/// copy from the other LAN (EntryForgetTime in IEC 62439-3)
+const REDUNDANCY_ENTRY_FORGET_TIME: Duration = Duration::from_millis(400);
 
-        let v = self.ip_addresses.get_mut(&ip).unwrap();
+/// Per-host reception statistics for PRP/HSR redundant frames
+#[derive(Debug, Clone)]
+pub struct RedundancyStats {
+    pub protocol: RedundancyProtocol,
+    pub lan_a_count: u32,
+    pub lan_b_count: u32,
+    /// Frames seen on at least one LAN, i.e. after de-duplication
+    pub unique_count: u32,
+}
 
-        if !v.contains(&interface) {
-            v.push(interface);
+impl RedundancyStats {
+    fn new(protocol: RedundancyProtocol) -> Self {
+        Self {
+            protocol,
+            lan_a_count: 0,
+            lan_b_count: 0,
+            unique_count: 0,
         }
     }
 
-    pub fn add_interface(&mut self, interface: String) {
-        self.interfaces.insert(interface);
+    fn record(&mut self, tag: &RedundancyTag, duplicate: bool) {
+        self.protocol = tag.protocol;
+        match tag.lan {
+            RedundancyLan::A => self.lan_a_count += 1,
+            RedundancyLan::B => self.lan_b_count += 1,
+        }
+        if !duplicate {
+            self.unique_count += 1;
+        }
     }
 
-    pub fn get_interfaces(&self) -> &HashSet<String> {
-        &self.interfaces
+    pub fn duplicates_discarded(&self) -> u32 {
+        (self.lan_a_count + self.lan_b_count).saturating_sub(self.unique_count)
     }
 
-    /// Returns a vector of all interface names the host was seen on
-    pub fn get_interface_names(&self) -> Vec<String> {
-        let mut interface_names = std::collections::HashSet::new();
+    /// Frames that only arrived over LAN B
+    pub fn missing_on_lan_a(&self) -> u32 {
+        self.unique_count.saturating_sub(self.lan_a_count)
+    }
 
-        // Add interfaces from the interfaces HashSet (for gPTP hosts)
-        for interface in &self.interfaces {
-            interface_names.insert(interface.clone());
-        }
+    /// Frames that only arrived over LAN A
+    pub fn missing_on_lan_b(&self) -> u32 {
+        self.unique_count.saturating_sub(self.lan_b_count)
+    }
 
-        // Add interfaces from IP addresses (for PTP over UDP)
-        for interfaces in self.ip_addresses.values() {
-            for interface in interfaces {
-                interface_names.insert(interface.clone());
-            }
+    /// Loss rate on LAN B minus loss rate on LAN A, in percent
+    pub fn loss_asymmetry_percent(&self) -> f64 {
+        if self.unique_count == 0 {
+            return 0.0;
+        }
+        (self.missing_on_lan_b() as f64 - self.missing_on_lan_a() as f64) * 100.0
+            / self.unique_count as f64
+    }
+}
+
+/// How long a two-step Sync waits for its Follow_Up before it is counted as missing
+const FOLLOW_UP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Follow_Ups arriving later than this after their Sync are counted as late
+const FOLLOW_UP_LATE_THRESHOLD: Duration = Duration::from_millis(10);
+
+/// Number of recent Sync to Follow_Up latencies kept for percentiles
+const FOLLOW_UP_LATENCY_SAMPLES: usize = 256;
+
+/// Problems found while pairing Sync and Follow_Up messages of a port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncFollowUpIssue {
+    MissingFollowUp,
+    LateFollowUp,
+    FollowUpWithoutSync,
+    /// A Follow_Up for a Sync that was sent without the two-step flag
+    TwoStepMismatch,
+}
+
+impl SyncFollowUpIssue {
+    pub const ALL: [SyncFollowUpIssue; 4] = [
+        SyncFollowUpIssue::MissingFollowUp,
+        SyncFollowUpIssue::LateFollowUp,
+        SyncFollowUpIssue::FollowUpWithoutSync,
+        SyncFollowUpIssue::TwoStepMismatch,
+    ];
+}
+
+impl std::fmt::Display for SyncFollowUpIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncFollowUpIssue::MissingFollowUp => write!(f, "missing Follow_Up"),
+            SyncFollowUpIssue::LateFollowUp => write!(f, "late Follow_Up"),
+            SyncFollowUpIssue::FollowUpWithoutSync => write!(f, "Follow_Up without Sync"),
+            SyncFollowUpIssue::TwoStepMismatch => write!(f, "two-step flag mismatch"),
+        }
+    }
+}
+
+/// Pairing of Sync and Follow_Up messages by sequence id, using capture timestamps
+#[derive(Debug, Clone)]
+pub struct SyncFollowUpStats {
+    /// Syncs still waiting for a Follow_Up: sequence id -> (capture time, two-step flag, correction)
+    pending: HashMap<u16, (SystemTime, bool, PtpCorrectionField)>,
+    pub matched_count: u32,
+    pub missing_follow_up_count: u32,
+    pub late_follow_up_count: u32,
+    pub follow_up_without_sync_count: u32,
+    pub two_step_mismatch_count: u32,
+    pub min_latency: Option<Duration>,
+    pub max_latency: Option<Duration>,
+    total_latency: Duration,
+    recent_latencies: BoundedVec<Duration>,
+}
+
+impl Default for SyncFollowUpStats {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            matched_count: 0,
+            missing_follow_up_count: 0,
+            late_follow_up_count: 0,
+            follow_up_without_sync_count: 0,
+            two_step_mismatch_count: 0,
+            min_latency: None,
+            max_latency: None,
+            total_latency: Duration::ZERO,
+            recent_latencies: BoundedVec::new(FOLLOW_UP_LATENCY_SAMPLES),
+        }
+    }
+}
+
+impl SyncFollowUpStats {
+    fn record_sync(
+        &mut self,
+        sequence_id: u16,
+        two_step: bool,
+        correction: PtpCorrectionField,
+        timestamp: SystemTime,
+    ) {
+        self.expire_pending(timestamp);
+        self.pending
+            .insert(sequence_id, (timestamp, two_step, correction));
+    }
+
+    /// Pair a Follow_Up with its Sync. Returns the capture time and correction of a
+    /// two-step Sync, which the Follow_Up's precise origin timestamp belongs to.
+    fn record_follow_up(
+        &mut self,
+        sequence_id: u16,
+        timestamp: SystemTime,
+    ) -> Option<(SystemTime, PtpCorrectionField)> {
+        self.expire_pending(timestamp);
+
+        let Some((sync_timestamp, two_step, correction)) = self.pending.remove(&sequence_id) else {
+            self.follow_up_without_sync_count += 1;
+            return None;
+        };
+
+        if !two_step {
+            self.two_step_mismatch_count += 1;
+        }
+
+        let latency = timestamp.duration_since(sync_timestamp).unwrap_or_default();
+        if latency > FOLLOW_UP_LATE_THRESHOLD {
+            self.late_follow_up_count += 1;
+        }
+
+        self.matched_count += 1;
+        self.total_latency += latency;
+        self.min_latency = Some(self.min_latency.map_or(latency, |l| l.min(latency)));
+        self.max_latency = Some(self.max_latency.map_or(latency, |l| l.max(latency)));
+        self.recent_latencies.push(latency);
+
+        two_step.then_some((sync_timestamp, correction))
+    }
+
+    /// Count two-step Syncs whose Follow_Up didn't arrive in time. Timeouts are measured
+    /// in capture time, so that pcap files give the same results as live capture.
+    fn expire_pending(&mut self, now: SystemTime) {
+        let mut missing = 0;
+        self.pending.retain(|_, (timestamp, two_step, _)| {
+            let expired = now.duration_since(*timestamp).unwrap_or_default() > FOLLOW_UP_TIMEOUT;
+            if expired && *two_step {
+                missing += 1;
+            }
+            !expired
+        });
+        self.missing_follow_up_count += missing;
+    }
+
+    pub fn issue_count(&self, issue: SyncFollowUpIssue) -> u32 {
+        match issue {
+            SyncFollowUpIssue::MissingFollowUp => self.missing_follow_up_count,
+            SyncFollowUpIssue::LateFollowUp => self.late_follow_up_count,
+            SyncFollowUpIssue::FollowUpWithoutSync => self.follow_up_without_sync_count,
+            SyncFollowUpIssue::TwoStepMismatch => self.two_step_mismatch_count,
+        }
+    }
+
+    pub fn mean_latency(&self) -> Option<Duration> {
+        (self.matched_count > 0).then(|| self.total_latency / self.matched_count)
+    }
+
+    /// Latency percentile (0-100) over the most recent pairs
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut latencies: Vec<Duration> = self.recent_latencies.items.iter().copied().collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort();
+        let index = ((latencies.len() - 1) as f64 * percentile / 100.0).round() as usize;
+        latencies.get(index).copied()
+    }
+}
+
+/// Offset samples kept per transmitter port
+const GM_OFFSET_SAMPLES: usize = 256;
+
+/// Change of the offset between two consecutive samples that counts as a time step of
+/// the transmitter (or of the local clock), well above capture timestamp jitter
+pub const GM_TIME_STEP_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Timescale announced by a transmitter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpTimescale {
+    /// TAI, with the announced current UTC offset in seconds
+    Ptp { utc_offset: i16 },
+    /// Arbitrary timescale, not related to UTC
+    Arbitrary,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GmOffsetSample {
+    pub capture_time: SystemTime,
+    /// Transmitter time minus local capture time, in nanoseconds
+    pub offset_ns: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GmTimeStep {
+    pub capture_time: SystemTime,
+    /// Change of the offset from the previous sample, in nanoseconds
+    pub step_ns: i64,
+}
+
+/// Offset of the time a transmitter port distributes against the local capture clock.
+///
+/// Estimated from the Sync origin timestamp plus correction, converted to UTC with the
+/// announced UTC offset, minus the capture timestamp of the Sync. The path delay is
+/// assumed to be zero, so the estimate is low by the path delay. For the arbitrary
+/// timescale the offset is meaningless as such, but time steps are still detected.
+#[derive(Debug, Clone)]
+pub struct GmOffsetStats {
+    /// None until the first Announce of the port, no offsets are estimated before that
+    pub timescale: Option<PtpTimescale>,
+    pub samples: BoundedVec<GmOffsetSample>,
+    pub step_count: u32,
+    pub last_step: Option<GmTimeStep>,
+}
+
+impl Default for GmOffsetStats {
+    fn default() -> Self {
+        Self {
+            timescale: None,
+            samples: BoundedVec::new(GM_OFFSET_SAMPLES),
+            step_count: 0,
+            last_step: None,
+        }
+    }
+}
+
+impl GmOffsetStats {
+    fn record(
+        &mut self,
+        capture_time: SystemTime,
+        origin_timestamp: PtpTimestamp,
+        correction: PtpCorrectionField,
+    ) {
+        let Some(transmitter_ns) = self.utc_nanos(origin_timestamp) else {
+            return;
+        };
+        let Some(capture_ns) = unix_nanos(capture_time) else {
+            return;
+        };
+        let offset_ns = (transmitter_ns + correction.nanoseconds() as i128 - capture_ns)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64;
+
+        if let Some(last) = self.samples.items.back() {
+            let step_ns = offset_ns.saturating_sub(last.offset_ns);
+            if step_ns.unsigned_abs() > GM_TIME_STEP_THRESHOLD.as_nanos() as u64 {
+                self.step_count += 1;
+                self.last_step = Some(GmTimeStep {
+                    capture_time,
+                    step_ns,
+                });
+            }
+        }
+
+        self.samples.push(GmOffsetSample {
+            capture_time,
+            offset_ns,
+        });
+    }
+
+    /// A timestamp of this transmitter in nanoseconds since the Unix epoch, converted to UTC
+    /// with the announced UTC offset. None until the timescale is known.
+    pub fn utc_nanos(&self, timestamp: PtpTimestamp) -> Option<i128> {
+        let utc_offset_ns = match self.timescale? {
+            PtpTimescale::Ptp { utc_offset } => utc_offset as i128 * 1_000_000_000,
+            PtpTimescale::Arbitrary => 0,
+        };
+        Some(timestamp.total_nanoseconds() as i128 - utc_offset_ns)
+    }
+
+    /// Rate of change of the offset over the kept samples, in parts per million.
+    /// None if a time step lies within them.
+    pub fn drift_ppm(&self) -> Option<f64> {
+        let first = self.samples.items.front()?;
+        let last = self.samples.items.back()?;
+        if self
+            .last_step
+            .is_some_and(|step| step.capture_time > first.capture_time)
+        {
+            return None;
+        }
+        let elapsed = last.capture_time.duration_since(first.capture_time).ok()?;
+        (!elapsed.is_zero())
+            .then(|| (last.offset_ns - first.offset_ns) as f64 / elapsed.as_nanos() as f64 * 1e6)
+    }
+
+    pub fn latest(&self) -> Option<i64> {
+        self.samples.items.back().map(|s| s.offset_ns)
+    }
+
+    /// Mean, min and max offset over the kept samples, in nanoseconds
+    pub fn summary(&self) -> Option<(i64, i64, i64)> {
+        let offsets = self.samples.items.iter().map(|s| s.offset_ns as i128);
+        let count = self.samples.len() as i128;
+        let min = offsets.clone().min()?;
+        let max = offsets.clone().max()?;
+        let mean = offsets.sum::<i128>() / count;
+        Some((mean as i64, min as i64, max as i64))
+    }
+}
+
+/// Correction samples kept per stream for the residence time distribution
+const CORRECTION_SAMPLES: usize = 256;
+
+/// Corrections above this are implausible as transparent clock residence times
+pub const CORRECTION_HUGE_THRESHOLD: Duration = Duration::from_millis(10);
+
+/// correctionField statistics of one message type on a transmitter/receiver path.
+///
+/// Transparent clocks add their residence time to the correction of event messages, so
+/// non-zero, varying corrections show a TC between the sender and the capture point
+/// (Sync, Follow_Up), or between the requester and the transmitter (Delay_Resp, which
+/// echoes the correction accumulated by the Delay_Req).
+#[derive(Debug, Clone)]
+pub struct CorrectionStats {
+    pub count: u32,
+    pub nonzero_count: u32,
+    pub negative_count: u32,
+    pub huge_count: u32,
+    /// Corrections with a sub-nanosecond fractional part
+    pub fractional_count: u32,
+    pub min_ns: i64,
+    pub max_ns: i64,
+    total_ns: i128,
+    recent_ns: BoundedVec<i64>,
+}
+
+impl Default for CorrectionStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            nonzero_count: 0,
+            negative_count: 0,
+            huge_count: 0,
+            fractional_count: 0,
+            min_ns: i64::MAX,
+            max_ns: i64::MIN,
+            total_ns: 0,
+            recent_ns: BoundedVec::new(CORRECTION_SAMPLES),
+        }
+    }
+}
+
+impl CorrectionStats {
+    fn record(&mut self, correction: PtpCorrectionField) {
+        let ns = correction.nanoseconds();
+        self.count += 1;
+        if correction.value != 0 {
+            self.nonzero_count += 1;
+        }
+        if correction.value < 0 {
+            self.negative_count += 1;
+        }
+        if ns.unsigned_abs() > CORRECTION_HUGE_THRESHOLD.as_nanos() as u64 {
+            self.huge_count += 1;
+        }
+        if correction.value & 0xffff != 0 {
+            self.fractional_count += 1;
+        }
+        self.min_ns = self.min_ns.min(ns);
+        self.max_ns = self.max_ns.max(ns);
+        self.total_ns += ns as i128;
+        self.recent_ns.push(ns);
+    }
+
+    pub fn mean_ns(&self) -> Option<i64> {
+        (self.count > 0).then(|| (self.total_ns / self.count as i128) as i64)
+    }
+
+    /// Correction percentile (0-100) over the most recent messages, in nanoseconds
+    pub fn percentile_ns(&self, percentile: f64) -> Option<i64> {
+        let mut values: Vec<i64> = self.recent_ns.items.iter().copied().collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort();
+        let index = ((values.len() - 1) as f64 * percentile / 100.0).round() as usize;
+        values.get(index).copied()
+    }
+
+    /// Non-zero corrections that vary by at least a nanosecond, as added by a transparent
+    /// clock, rather than a constant value set by the sender
+    pub fn indicates_transparent_clock(&self) -> bool {
+        self.nonzero_count > 0 && self.max_ns > self.min_ns
+    }
+
+    pub fn is_suspicious(&self) -> bool {
+        self.negative_count > 0 || self.huge_count > 0
+    }
+}
+
+/// Capture time in nanoseconds since the Unix epoch
+fn unix_nanos(time: SystemTime) -> Option<i128> {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_nanos() as i128)
+}
+
+/// Time after which a delay request or two-step Pdelay_Resp without its counterpart is dropped
+const DELAY_EXCHANGE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DelayMechanism {
+    /// End-to-end: Delay_Req / Delay_Resp with a transmitter
+    E2e,
+    /// Peer-to-peer: Pdelay_Req / Pdelay_Resp (/ Pdelay_Resp_Follow_Up) with the link peer
+    P2p,
+}
+
+impl std::fmt::Display for DelayMechanism {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DelayMechanism::E2e => write!(f, "E2E"),
+            DelayMechanism::P2p => write!(f, "P2P"),
+        }
+    }
+}
+
+/// Mean path delay measurements between a requesting port and one responder
+#[derive(Debug, Clone)]
+pub struct PathDelayStats {
+    pub mechanism: DelayMechanism,
+    pub count: u32,
+    pub last_ns: i64,
+    pub min_ns: i64,
+    pub max_ns: i64,
+    // Running mean and sum of squared differences (Welford's algorithm)
+    mean_ns: f64,
+    m2: f64,
+    /// Last residence-free turnaround of the peer (t3 - t2), P2P only
+    pub turnaround_ns: Option<i64>,
+}
+
+impl PathDelayStats {
+    fn new(mechanism: DelayMechanism) -> Self {
+        Self {
+            mechanism,
+            count: 0,
+            last_ns: 0,
+            min_ns: i64::MAX,
+            max_ns: i64::MIN,
+            mean_ns: 0.0,
+            m2: 0.0,
+            turnaround_ns: None,
+        }
+    }
+
+    fn record(&mut self, delay_ns: i64) {
+        self.count += 1;
+        self.last_ns = delay_ns;
+        self.min_ns = self.min_ns.min(delay_ns);
+        self.max_ns = self.max_ns.max(delay_ns);
+
+        let delta = delay_ns as f64 - self.mean_ns;
+        self.mean_ns += delta / self.count as f64;
+        self.m2 += delta * (delay_ns as f64 - self.mean_ns);
+    }
+
+    pub fn mean_ns(&self) -> f64 {
+        self.mean_ns
+    }
+
+    pub fn std_dev_ns(&self) -> f64 {
+        if self.count > 1 {
+            (self.m2 / self.count as f64).sqrt()
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Two-step Pdelay_Resp waiting for its Pdelay_Resp_Follow_Up
+#[derive(Debug, Clone)]
+struct PendingPDelayResponse {
+    responder: PortIdentity,
+    received: SystemTime,
+    round_trip: Duration,
+    request_receipt_timestamp: PtpTimestamp,
+    correction: PtpCorrectionField,
+}
+
+/// Delay request/response exchanges of a requesting port, correlated by sequence id.
+///
+/// The request and response are timestamped by the capture clock, so the delay is the one
+/// seen from the capture point; it is the port's own path delay when capturing on that host.
+/// E2E needs the offset of the transmitter against the capture clock to relate its t1 and t4
+/// timestamps to the captured t2 and t3 (see `GmOffsetStats`).
+#[derive(Debug, Clone, Default)]
+pub struct PathDelays {
+    /// Capture times of Delay_Req / Pdelay_Req by mechanism and sequence id
+    requests: HashMap<(DelayMechanism, u16), SystemTime>,
+    pdelay_responses: HashMap<u16, PendingPDelayResponse>,
+    /// Measurements per responding port
+    pub links: BTreeMap<PortIdentity, PathDelayStats>,
+}
+
+impl PathDelays {
+    fn record_request(&mut self, mechanism: DelayMechanism, sequence_id: u16, time: SystemTime) {
+        let expired =
+            |t: &SystemTime| time.duration_since(*t).unwrap_or_default() > DELAY_EXCHANGE_TIMEOUT;
+        self.requests.retain(|_, t| !expired(t));
+        self.pdelay_responses.retain(|_, r| !expired(&r.received));
+        self.requests.insert((mechanism, sequence_id), time);
+    }
+
+    /// Delay_Resp with the transmitter's t4 in UTC nanoseconds, corrected, and the last offset
+    /// of the transmitter against the capture clock
+    fn record_delay_resp(
+        &mut self,
+        responder: PortIdentity,
+        sequence_id: u16,
+        receive_ns: i128,
+        transmitter_offset_ns: i64,
+    ) {
+        let Some(request_ns) = self
+            .requests
+            .remove(&(DelayMechanism::E2e, sequence_id))
+            .and_then(unix_nanos)
+        else {
+            return;
+        };
+        // The Sync direction (t2 - t1) is the negated offset of the transmitter
+        let delay_ns = (receive_ns - request_ns - transmitter_offset_ns as i128) / 2;
+        self.link(responder, DelayMechanism::E2e)
+            .record(delay_ns as i64);
+    }
+
+    fn record_pdelay_resp(&mut self, msg: &PDelayRespMessage, received: SystemTime) {
+        let sequence_id = msg.header.sequence_id;
+        let Some(round_trip) = self
+            .requests
+            .remove(&(DelayMechanism::P2p, sequence_id))
+            .and_then(|request| received.duration_since(request).ok())
+        else {
+            return;
+        };
+        let responder = msg.header.source_port_identity;
+
+        if msg.header.flags.two_step() {
+            self.pdelay_responses.insert(
+                sequence_id,
+                PendingPDelayResponse {
+                    responder,
+                    received,
+                    round_trip,
+                    request_receipt_timestamp: msg.request_receipt_timestamp,
+                    correction: msg.header.correction_field,
+                },
+            );
+        } else {
+            // One-step responders put the turnaround into the correction field
+            self.record_pdelay(
+                responder,
+                round_trip,
+                msg.header.correction_field.nanoseconds(),
+            );
+        }
+    }
+
+    fn record_pdelay_resp_follow_up(&mut self, msg: &PDelayRespFollowUpMessage) {
+        let Some(response) = self.pdelay_responses.remove(&msg.header.sequence_id) else {
+            return;
+        };
+        if response.responder != msg.header.source_port_identity {
+            return;
+        }
+        let turnaround_ns = msg.response_origin_timestamp.total_nanoseconds() as i128
+            - response.request_receipt_timestamp.total_nanoseconds() as i128
+            + response.correction.nanoseconds() as i128
+            + msg.header.correction_field.nanoseconds() as i128;
+        self.record_pdelay(
+            response.responder,
+            response.round_trip,
+            turnaround_ns as i64,
+        );
+    }
+
+    fn record_pdelay(&mut self, responder: PortIdentity, round_trip: Duration, turnaround_ns: i64) {
+        let delay_ns = (round_trip.as_nanos() as i64 - turnaround_ns) / 2;
+        let link = self.link(responder, DelayMechanism::P2p);
+        link.record(delay_ns);
+        link.turnaround_ns = Some(turnaround_ns);
+    }
+
+    fn link(&mut self, responder: PortIdentity, mechanism: DelayMechanism) -> &mut PathDelayStats {
+        self.links
+            .entry(responder)
+            .or_insert_with(|| PathDelayStats::new(mechanism))
+    }
+}
+
+/// Sequence ids further ahead of or behind the last one than this are taken as a restart
+/// of the sender (e.g. after a reboot) rather than as lost or reordered packets
+const SEQUENCE_WINDOW: u16 = 256;
+
+/// Sequence id analysis of one message stream, i.e. one message type and domain of a port
+#[derive(Debug, Clone, Default)]
+pub struct SequenceStats {
+    last_sequence_id: Option<u16>,
+    pub received_count: u32,
+    /// Sequence ids skipped over, minus those that arrived late
+    pub lost_count: u32,
+    pub duplicate_count: u32,
+    pub reordered_count: u32,
+    pub restart_count: u32,
+}
+
+impl SequenceStats {
+    fn record(&mut self, sequence_id: u16) {
+        self.received_count += 1;
+
+        let Some(last) = self.last_sequence_id else {
+            self.last_sequence_id = Some(sequence_id);
+            return;
+        };
+
+        // Wrapping difference, so that 65535 -> 0 is a step of one
+        let step = sequence_id.wrapping_sub(last);
+        if step == 0 {
+            self.duplicate_count += 1;
+        } else if step <= SEQUENCE_WINDOW {
+            self.lost_count += (step - 1) as u32;
+            self.last_sequence_id = Some(sequence_id);
+        } else if last.wrapping_sub(sequence_id) <= SEQUENCE_WINDOW {
+            // An older packet that was counted as lost when it was skipped over. It may also
+            // be a late duplicate, which can't be told apart without keeping every id.
+            self.reordered_count += 1;
+            self.lost_count = self.lost_count.saturating_sub(1);
+        } else {
+            self.restart_count += 1;
+            self.last_sequence_id = Some(sequence_id);
+        }
+    }
+
+    /// Packets expected from the sequence ids seen so far
+    pub fn expected_count(&self) -> u32 {
+        self.received_count - self.duplicate_count + self.lost_count
+    }
+
+    pub fn loss_percent(&self) -> f64 {
+        match self.expected_count() {
+            0 => 0.0,
+            expected => self.lost_count as f64 * 100.0 / expected as f64,
+        }
+    }
+}
+
+/// Upper bounds of the interval histogram buckets, relative to the advertised interval.
+/// The last bucket counts everything above the last bound.
+pub const INTERVAL_HISTOGRAM_BOUNDS: [f64; 6] = [0.5, 0.9, 0.99, 1.01, 1.1, 2.0];
+
+/// Intervals needed before the measured rate is compared with the advertised one
+const INTERVAL_MIN_SAMPLES: u32 = 4;
+
+/// Inter-arrival statistics of one message type sent by a port, from capture timestamps
+#[derive(Debug, Clone, Default)]
+pub struct IntervalStats {
+    last_timestamp: Option<SystemTime>,
+    /// Last advertised logMessageInterval, None if unspecified (0x7f)
+    pub advertised: Option<PtpLogInterval>,
+    pub count: u32,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+    // Running mean and sum of squared differences in seconds (Welford's algorithm)
+    mean_secs: f64,
+    m2: f64,
+    /// Interval counts per `INTERVAL_HISTOGRAM_BOUNDS` bucket, only while an interval is advertised
+    pub histogram: [u32; INTERVAL_HISTOGRAM_BOUNDS.len() + 1],
+}
+
+impl IntervalStats {
+    fn record(&mut self, timestamp: SystemTime, advertised: PtpLogInterval) {
+        self.advertised = advertised.seconds().is_some().then_some(advertised);
+
+        let last = self.last_timestamp.replace(timestamp);
+        // Packets reordered in capture time are skipped
+        let Some(interval) = last.and_then(|last| timestamp.duration_since(last).ok()) else {
+            return;
+        };
+
+        self.count += 1;
+        self.min = Some(self.min.map_or(interval, |m| m.min(interval)));
+        self.max = Some(self.max.map_or(interval, |m| m.max(interval)));
+
+        let secs = interval.as_secs_f64();
+        let delta = secs - self.mean_secs;
+        self.mean_secs += delta / self.count as f64;
+        self.m2 += delta * (secs - self.mean_secs);
+
+        if let Some(advertised_secs) = advertised.seconds() {
+            let ratio = secs / advertised_secs;
+            let bucket = INTERVAL_HISTOGRAM_BOUNDS
+                .iter()
+                .position(|&bound| ratio < bound)
+                .unwrap_or(INTERVAL_HISTOGRAM_BOUNDS.len());
+            self.histogram[bucket] += 1;
+        }
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_secs_f64(self.mean_secs))
+    }
+
+    pub fn std_dev(&self) -> Option<Duration> {
+        (self.count > 1).then(|| Duration::from_secs_f64((self.m2 / self.count as f64).sqrt()))
+    }
+
+    /// Measured messages per second
+    pub fn rate(&self) -> Option<f64> {
+        (self.count > 0 && self.mean_secs > 0.0).then(|| 1.0 / self.mean_secs)
+    }
+
+    /// Deviation of the mean interval from the advertised one, in percent
+    pub fn deviation_percent(&self) -> Option<f64> {
+        let advertised_secs = self.advertised?.seconds()?;
+        (self.count >= INTERVAL_MIN_SAMPLES)
+            .then(|| (self.mean_secs - advertised_secs) * 100.0 / advertised_secs)
+    }
+
+    pub fn deviates(&self, tolerance_percent: f64) -> bool {
+        self.deviation_percent()
+            .is_some_and(|d| d.abs() > tolerance_percent)
+    }
+}
+
+/// Missed intervals of a host's slowest message stream before it is considered gone
+const HOST_TIMEOUT_INTERVALS: u32 = 5;
+
+/// Lower bound of the message-type-aware host timeout
+const MIN_HOST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Host timeout when the host sends no periodic messages, e.g. it was only addressed
+pub const DEFAULT_HOST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How PTP messages are carried
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PtpTransport {
+    /// IEEE 802.3 / gPTP, EtherType 0x88f7
+    Ethernet,
+    UdpIpv4,
+    UdpIpv6,
+}
+
+impl std::fmt::Display for PtpTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PtpTransport::Ethernet => write!(f, "L2"),
+            PtpTransport::UdpIpv4 => write!(f, "UDP/IPv4"),
+            PtpTransport::UdpIpv6 => write!(f, "UDP/IPv6"),
+        }
+    }
+}
+
+/// A separate PTP network: the same domain number on another interface, VLAN or transport
+/// is a different network with its own grandmaster, so the BMCA runs per segment
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PtpSegment {
+    pub interface_name: String,
+    pub vlan_id: Option<u16>,
+    pub domain_number: u8,
+    pub transport: PtpTransport,
+}
+
+impl PtpSegment {
+    pub fn from_packet(raw: &crate::source::RawPacket, domain_number: u8) -> Self {
+        let transport = match raw.source_addr {
+            Some(addr) if addr.is_ipv4() => PtpTransport::UdpIpv4,
+            Some(_) => PtpTransport::UdpIpv6,
+            None => PtpTransport::Ethernet,
+        };
+        Self {
+            interface_name: raw.interface_name.clone(),
+            vlan_id: raw.vlan_id,
+            domain_number,
+            transport,
+        }
+    }
+}
+
+impl std::fmt::Display for PtpSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "domain {} on {}",
+            self.domain_number, self.interface_name
+        )?;
+        if let Some(vlan_id) = self.vlan_id {
+            write!(f, " VLAN {}", vlan_id)?;
+        }
+        write!(f, " ({})", self.transport)
+    }
+}
+
+/// PTP profile of a segment, inferred from its transport, domain and messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpProfile {
+    /// IEEE 802.1AS, majorSdoId 1
+    Gptp,
+    /// SMPTE ST 2059-2, default domain 127
+    Smpte2059,
+    /// AES67 media profile, Sync 8 per second over UDP
+    Aes67,
+    /// ITU-T G.8275.1 telecom profile over Ethernet, domains 24 to 43
+    TelecomG8275_1,
+    /// ITU-T G.8275.2 telecom profile over UDP, domains 44 to 63
+    TelecomG8275_2,
+    /// IEEE C37.238 power profile, P2P over Ethernet in domain 254
+    Power,
+    DefaultE2e,
+    DefaultP2p,
+    Unknown,
+}
+
+impl PtpProfile {
+    /// Best guess from what is visible on the wire; profiles don't announce themselves
+    pub fn infer(
+        segment: &PtpSegment,
+        major_sdo_id: Option<u8>,
+        delay_mechanism: Option<DelayMechanism>,
+        sync_interval: Option<PtpLogInterval>,
+    ) -> Self {
+        let ethernet = segment.transport == PtpTransport::Ethernet;
+        match (segment.domain_number, delay_mechanism) {
+            _ if major_sdo_id == Some(1) => PtpProfile::Gptp,
+            (127, _) => PtpProfile::Smpte2059,
+            (24..=43, _) if ethernet => PtpProfile::TelecomG8275_1,
+            (44..=63, _) if !ethernet => PtpProfile::TelecomG8275_2,
+            (254, Some(DelayMechanism::P2p)) if ethernet => PtpProfile::Power,
+            (_, Some(DelayMechanism::E2e))
+                if !ethernet && sync_interval.is_some_and(|i| i.exponent == -3) =>
+            {
+                PtpProfile::Aes67
+            }
+            (_, Some(DelayMechanism::E2e)) => PtpProfile::DefaultE2e,
+            (_, Some(DelayMechanism::P2p)) => PtpProfile::DefaultP2p,
+            (_, None) => PtpProfile::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for PtpProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PtpProfile::Gptp => write!(f, "gPTP (802.1AS)"),
+            PtpProfile::Smpte2059 => write!(f, "SMPTE ST 2059-2"),
+            PtpProfile::Aes67 => write!(f, "AES67"),
+            PtpProfile::TelecomG8275_1 => write!(f, "Telecom G.8275.1"),
+            PtpProfile::TelecomG8275_2 => write!(f, "Telecom G.8275.2"),
+            PtpProfile::Power => write!(f, "Power (C37.238)"),
+            PtpProfile::DefaultE2e => write!(f, "Default E2E"),
+            PtpProfile::DefaultP2p => write!(f, "Default P2P"),
+            PtpProfile::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// Host and message counts of one segment
+#[derive(Debug, Clone)]
+pub struct SegmentStatistics {
+    pub segment: PtpSegment,
+    pub host_count: usize,
+    pub transmitter_count: usize,
+    pub receiver_count: usize,
+    pub listening_count: usize,
+    /// Clock of the BMCA winner port
+    pub grandmaster: Option<ClockIdentity>,
+    /// Messages sent by the ports in this segment
+    pub message_count: u64,
+}
+
+/// A port sending Sync, and the receivers following it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncSource {
+    pub port_identity: PortIdentity,
+    /// Receiver ports that got a Delay_Resp from this port
+    pub receivers: Vec<PortIdentity>,
+}
+
+/// Unrelated clocks sending Sync in one segment, splitting its receivers between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompetingGrandmasters {
+    pub segment: PtpSegment,
+    pub sources: Vec<SyncSource>,
+}
+
+/// UTC offset announced by default, valid since the leap second at the end of 2016
+pub const DEFAULT_EXPECTED_UTC_OFFSET: i16 = 37;
+
+/// The timescale transmitters are expected to announce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpectedTimescale {
+    /// PTP timescale (TAI), e.g. for the default and telecom profiles
+    #[default]
+    Ptp,
+    /// Arbitrary timescale, e.g. a media clock not traceable to TAI
+    Arbitrary,
+    /// Don't check the timescale
+    Any,
+}
+
+impl ExpectedTimescale {
+    pub fn matches(&self, ptp_timescale: bool) -> bool {
+        match self {
+            ExpectedTimescale::Ptp => ptp_timescale,
+            ExpectedTimescale::Arbitrary => !ptp_timescale,
+            ExpectedTimescale::Any => true,
+        }
+    }
+}
+
+impl std::str::FromStr for ExpectedTimescale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ptp" => Ok(ExpectedTimescale::Ptp),
+            "arb" | "arbitrary" => Ok(ExpectedTimescale::Arbitrary),
+            "any" => Ok(ExpectedTimescale::Any),
+            _ => Err(format!(
+                "invalid timescale '{}', expected ptp, arb or any",
+                s
+            )),
+        }
+    }
+}
+
+/// A problem with the time properties (UTC offset, leap flags, timescale, traceability)
+/// announced in a segment
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TimePropertiesIssue {
+    /// Transmitters of the segment announce different values for a field
+    Disagreement {
+        field: DatasetField,
+        /// The value announced by each transmitter port
+        values: Vec<(PortIdentity, String)>,
+    },
+    /// A transmitter on the PTP timescale announces a valid UTC offset other than expected
+    UnexpectedUtcOffset {
+        port_identity: PortIdentity,
+        announced: i16,
+        expected: i16,
+    },
+    /// A transmitter announces a timescale other than expected
+    UnexpectedTimescale {
+        port_identity: PortIdentity,
+        ptp_timescale: bool,
+    },
+}
+
+impl TimePropertiesIssue {
+    /// Transmitters disagreeing confuse receivers, a wrong but consistent value usually
+    /// means a misconfiguration
+    pub fn is_disagreement(&self) -> bool {
+        matches!(self, TimePropertiesIssue::Disagreement { .. })
+    }
+}
+
+impl std::fmt::Display for TimePropertiesIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimePropertiesIssue::Disagreement { field, values } => {
+                let values = values
+                    .iter()
+                    .map(|(port_identity, value)| format!("{}={}", port_identity, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "transmitters disagree on {}: {}", field, values)
+            }
+            TimePropertiesIssue::UnexpectedUtcOffset {
+                port_identity,
+                announced,
+                expected,
+            } => write!(
+                f,
+                "{} announces UTC offset {}s, expected {}s",
+                port_identity, announced, expected
+            ),
+            TimePropertiesIssue::UnexpectedTimescale {
+                port_identity,
+                ptp_timescale,
+            } => write!(
+                f,
+                "{} announces the {} timescale",
+                port_identity,
+                if *ptp_timescale { "PTP" } else { "ARB" }
+            ),
+        }
+    }
+}
+
+/// A leap second announced by a grandmaster, taking effect at the next UTC midnight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpcomingLeapSecond {
+    pub port_identity: PortIdentity,
+    pub segment: PtpSegment,
+    pub leap: LeapSecond,
+    pub current_utc_offset: i16,
+    pub at: SystemTime,
+}
+
+/// Peers are forgotten when no Pdelay exchange was seen for this long
+const LINK_PEER_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// The port on the other end of a peer-to-peer link, learned from Pdelay exchanges
+#[derive(Debug, Clone)]
+pub struct LinkPeer {
+    /// This port sent Pdelay_Req that the peer answered
+    pub requested: bool,
+    /// The peer sent Pdelay_Req that this port answered
+    pub responded: bool,
+    /// Capture interface of the last exchange
+    pub interface_name: String,
+    pub last_seen: SystemTime,
+}
+
+/// A boundary clock port announcing a stepsRemoved that doesn't match its upstream
+/// transmitter's plus one, e.g. because it relays a different path than it is synchronised to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepsRemovedMismatch {
+    pub port: PortIdentity,
+    pub upstream: PortIdentity,
+    pub upstream_steps: u16,
+    pub announced: u16,
+}
+
+/// MACs and interfaces older than this are not compared for identity conflicts, so a
+/// replaced NIC or a moved cable don't count as a duplicate
+const IDENTITY_CONFLICT_WINDOW: Duration = Duration::from_secs(60);
+
+/// A sign that a clock identity isn't unique, e.g. a cloned VM or a badly provisioned device
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClockIdentityConflict {
+    /// One port sent from several MAC addresses
+    DuplicateMac {
+        port_identity: PortIdentity,
+        macs: Vec<MacAddress>,
+    },
+    /// One port was captured on several interfaces that otherwise see different clocks,
+    /// i.e. that aren't connected to the same network
+    DuplicateInterface {
+        port_identity: PortIdentity,
+        interfaces: Vec<String>,
+    },
+    /// The clock identity is an EUI-64 derived from a MAC address, but not from the one
+    /// the clock sends from
+    MacMismatch {
+        derived: MacAddress,
+        seen: Vec<MacAddress>,
+    },
+}
+
+impl ClockIdentityConflict {
+    /// Duplicates mean two devices are merged into one host, a mismatch only looks wrong
+    pub fn is_duplicate(&self) -> bool {
+        !matches!(self, ClockIdentityConflict::MacMismatch { .. })
+    }
+}
+
+impl std::fmt::Display for ClockIdentityConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |items: Vec<String>| items.join(", ");
+        match self {
+            ClockIdentityConflict::DuplicateMac {
+                port_identity,
+                macs,
+            } => write!(
+                f,
+                "port {} sends from several MACs: {}",
+                port_identity.port_number,
+                join(macs.iter().map(|mac| mac.to_string()).collect())
+            ),
+            ClockIdentityConflict::DuplicateInterface {
+                port_identity,
+                interfaces,
+            } => write!(
+                f,
+                "port {} seen on separate networks: {}",
+                port_identity.port_number,
+                join(interfaces.clone())
+            ),
+            ClockIdentityConflict::MacMismatch { derived, seen } => write!(
+                f,
+                "identity derived from {} but sent from {}",
+                derived,
+                join(seen.iter().map(|mac| mac.to_string()).collect())
+            ),
+        }
+    }
+}
+
+/// Messages of a clock in one PTP domain
+///
+/// Clocks can take part in several domains at once, e.g. a grandmaster serving domains 0
+/// and 127, so the role and counters are kept per domain.
+#[derive(Debug, Clone)]
+pub struct DomainParticipation {
+    pub first_seen: SystemTime,
+    pub last_seen: SystemTime,
+    pub messages_sent: u32,
+    pub messages_received: u32,
+    /// The clock sent Announce or Sync in this domain
+    pub transmits: bool,
+    /// The clock sent Delay_Req in this domain
+    pub receives: bool,
+}
+
+impl DomainParticipation {
+    fn new(time: SystemTime) -> Self {
+        Self {
+            first_seen: time,
+            last_seen: time,
+            messages_sent: 0,
+            messages_received: 0,
+            transmits: false,
+            receives: false,
+        }
+    }
+
+    fn record(&mut self, message_type: PtpMessageType, sent: bool, time: SystemTime) {
+        if sent {
+            self.messages_sent += 1;
+        } else {
+            self.messages_received += 1;
+        }
+        match message_type {
+            PtpMessageType::Announce | PtpMessageType::Sync if sent => self.transmits = true,
+            PtpMessageType::DelayReq if sent => self.receives = true,
+            _ => {}
+        }
+        self.last_seen = self.last_seen.max(time);
+    }
+
+    /// Role of the clock in this domain, from the messages it sent
+    pub fn role(&self) -> PortState {
+        if self.transmits {
+            PortState::TimeTransmitter
+        } else if self.receives {
+            PortState::TimeReceiver
+        } else {
+            PortState::Listening
+        }
+    }
+}
+
+/// A single port of a PTP clock
+///
+/// Ordinary clocks have one port, boundary clocks and multi-port switches have several,
+/// each with its own state. Messages are counted on the port that sent them, or for
+/// responses on the port that requested them.
+#[derive(Debug, Clone)]
+pub struct PtpPort {
+    pub port_identity: PortIdentity,
+    pub domain_number: Option<u8>,
+    pub last_seen: SystemTime,
+
+    pub announce_count: u32,
+    pub sync_count: u32,
+    pub follow_up_count: u32,
+    pub delay_req_count: u32,
+    pub delay_resp_count: u32,
+    pub pdelay_req_count: u32,
+    pub pdelay_resp_count: u32,
+    pub pdelay_resp_follow_up_count: u32,
+    pub total_messages_sent_count: u32,
+    pub total_messages_received_count: u32,
+    pub signaling_message_count: u32,
+    pub management_message_count: u32,
+
+    pub state: PtpHostState,
+    pub sync_follow_up: SyncFollowUpStats,
+    pub gm_offset: GmOffsetStats,
+    /// Path delay measurements of the delay requests this port sends
+    pub path_delays: PathDelays,
+    /// correctionField statistics of the Sync, Follow_Up and Delay_Req messages this port
+    /// sends, and of the Delay_Resp messages it is sent
+    pub corrections: BTreeMap<PtpMessageType, CorrectionStats>,
+    /// Sequence id analysis per message type and domain, for messages the port sends
+    /// with its own sequence ids (not responses, which echo the request's)
+    pub sequence_streams: BTreeMap<(PtpMessageType, u8), SequenceStats>,
+    /// Measured message intervals of the same messages, per message type
+    pub message_intervals: BTreeMap<PtpMessageType, IntervalStats>,
+    pub packet_history: BoundedVec<Arc<ParsedPacket>>,
+    /// 1588 port state inferred from the observations above, see `infer_state`
+    pub inferred_state: InferredPortState,
+    /// portState from the last PORT_DATA_SET management response, with its capture time
+    pub management_state: Option<(PortState, SystemTime)>,
+    /// Ports this port exchanged Pdelay messages with
+    pub link_peers: BTreeMap<PortIdentity, LinkPeer>,
+    /// Capture interface of the last Sync this port sent
+    pub sync_interface_name: Option<String>,
+    /// Network of the last message this port sent
+    pub segment: Option<PtpSegment>,
+    /// majorSdoId of the last message this port sent
+    pub major_sdo_id: Option<u8>,
+    /// Source MACs of the multicast messages the port sent, with the time last seen.
+    /// Unicast messages may have been routed and carry a router's MAC.
+    pub source_macs: BTreeMap<MacAddress, SystemTime>,
+    /// Capture interfaces of the messages the port sent, with the time last seen
+    pub capture_interfaces: BTreeMap<String, SystemTime>,
+}
+
+impl PtpPort {
+    pub fn new(port_identity: PortIdentity, max_history: usize) -> Self {
+        Self {
+            port_identity,
+            domain_number: None,
+            last_seen: SystemTime::now(),
+
+            announce_count: 0,
+            sync_count: 0,
+            follow_up_count: 0,
+            delay_req_count: 0,
+            delay_resp_count: 0,
+            pdelay_req_count: 0,
+            pdelay_resp_count: 0,
+            pdelay_resp_follow_up_count: 0,
+            total_messages_sent_count: 0,
+            total_messages_received_count: 0,
+            signaling_message_count: 0,
+            management_message_count: 0,
+
+            state: PtpHostState::Listening,
+            sync_follow_up: SyncFollowUpStats::default(),
+            gm_offset: GmOffsetStats::default(),
+            path_delays: PathDelays::default(),
+            corrections: BTreeMap::new(),
+            sequence_streams: BTreeMap::new(),
+            message_intervals: BTreeMap::new(),
+            packet_history: BoundedVec::new(max_history),
+            inferred_state: InferredPortState::default(),
+            management_state: None,
+            link_peers: BTreeMap::new(),
+            sync_interface_name: None,
+            segment: None,
+            major_sdo_id: None,
+            source_macs: BTreeMap::new(),
+            capture_interfaces: BTreeMap::new(),
+        }
+    }
+
+    /// Whether this port is a transmitter whose Syncs arrive within the sync receipt timeout
+    pub fn is_sending_sync(&self, now: SystemTime) -> bool {
+        let PtpHostState::TimeTransmitter(s) = &self.state else {
+            return false;
+        };
+        let sync_interval = self
+            .message_intervals
+            .get(&PtpMessageType::Sync)
+            .and_then(|stats| stats.advertised)
+            .and_then(|interval| interval.seconds())
+            .map_or(DEFAULT_SYNC_INTERVAL, Duration::from_secs_f64);
+        s.last_sync_time.is_some_and(|time| {
+            now.duration_since(time).unwrap_or_default() <= sync_interval * SYNC_RECEIPT_INTERVALS
+        })
+    }
+
+    /// Note a Pdelay exchange with the port on the other end of the link
+    fn record_link_peer(
+        &mut self,
+        peer: PortIdentity,
+        requested: bool,
+        interface_name: &str,
+        time: SystemTime,
+    ) {
+        let link_peer = self.link_peers.entry(peer).or_insert_with(|| LinkPeer {
+            requested: false,
+            responded: false,
+            interface_name: String::new(),
+            last_seen: time,
+        });
+        if requested {
+            link_peer.requested = true;
+        } else {
+            link_peer.responded = true;
+        }
+        link_peer.interface_name = interface_name.to_string();
+        link_peer.last_seen = link_peer.last_seen.max(time);
+    }
+
+    /// Infer the 1588 port state. A recent PORT_DATA_SET response is taken as is, otherwise
+    /// the state is derived from the messages the port sends:
+    /// - Faulty: two-step Syncs but never a Follow_Up
+    /// - Pre-Time Transmitter: announcing for less than the qualificationTimeout, no Sync yet
+    /// - Passive: announcing without Sync and not the BMCA winner, e.g. a backup grandmaster
+    /// - Uncalibrated: delay requests that were never answered
+    pub fn infer_state(&self, now: SystemTime) -> InferredPortState {
+        use InferredPortState as S;
+
+        if let Some((state, time)) = self.management_state {
+            let age = now.duration_since(time).unwrap_or_default();
+            if age <= MANAGEMENT_STATE_MAX_AGE {
+                return InferredPortState {
+                    state,
+                    evidence: format!("PORT_DATA_SET response {:.1}s ago", age.as_secs_f64()),
+                    from_management: true,
+                };
+            }
+        }
+
+        let ago = |time: SystemTime| now.duration_since(time).unwrap_or_default().as_secs_f64();
+        match &self.state {
+            PtpHostState::Listening => S::observed(
+                PortState::Listening,
+                "No Announce, Sync or answered delay request".to_string(),
+            ),
+            PtpHostState::Stale(s) => S::observed(
+                PortState::Listening,
+                format!(
+                    "No Announce for {:.1}s",
+                    s.last_announce_time.map_or(0.0, ago)
+                ),
+            ),
+            PtpHostState::TimeTransmitter(s) => {
+                let follow_ups = &self.sync_follow_up;
+                if follow_ups.matched_count == 0
+                    && follow_ups.missing_follow_up_count >= FAULTY_MISSING_FOLLOW_UPS
+                {
+                    return S::observed(
+                        PortState::Faulty,
+                        format!(
+                            "{} two-step Syncs, none followed by a Follow_Up",
+                            follow_ups.missing_follow_up_count
+                        ),
+                    );
+                }
+
+                if self.is_sending_sync(now) {
+                    let role = if s.is_bmca_winner {
+                        ", BMCA winner"
+                    } else if s.is_relay {
+                        ", relaying the grandmaster"
+                    } else {
+                        ""
+                    };
+                    return S::observed(
+                        PortState::TimeTransmitter,
+                        format!("Announce and Sync{}", role),
+                    );
+                }
+                let Some(first_announce) = s.first_announce_time else {
+                    // Sync without Announce, e.g. an 802.1AS port whose Announces were filtered
+                    return S::observed(
+                        PortState::TimeTransmitter,
+                        "Sync without Announce".to_string(),
+                    );
+                };
+
+                // qualificationTimeout is (stepsRemoved + 1) announce intervals (9.2.6.11)
+                let qualification_timeout =
+                    s.announce_interval_duration() * (s.steps_removed.unwrap_or(0) as u32 + 1);
+                let announcing_for = now.duration_since(first_announce).unwrap_or_default();
+                if s.last_sync_time.is_none() && announcing_for <= qualification_timeout {
+                    S::observed(
+                        PortState::PreTimeTransmitter,
+                        format!(
+                            "Announcing for {:.1}s without Sync, qualificationTimeout {:.1}s",
+                            announcing_for.as_secs_f64(),
+                            qualification_timeout.as_secs_f64()
+                        ),
+                    )
+                } else if !s.is_bmca_winner && !s.is_relay {
+                    S::observed(
+                        PortState::Passive,
+                        match s.last_sync_time {
+                            Some(time) => {
+                                format!("Announcing, lost the BMCA, no Sync for {:.1}s", ago(time))
+                            }
+                            None => "Announcing, lost the BMCA, never sent Sync".to_string(),
+                        },
+                    )
+                } else {
+                    S::observed(
+                        PortState::TimeTransmitter,
+                        "Announce without Sync".to_string(),
+                    )
+                }
+            }
+            PtpHostState::TimeReceiver(_) => {
+                let unanswered = |requests: u32, responses: u32, name: &str| {
+                    (requests > 0 && responses == 0).then(|| {
+                        S::observed(
+                            PortState::Uncalibrated,
+                            format!("{} {} without response", requests, name),
+                        )
+                    })
+                };
+                unanswered(self.delay_req_count, self.delay_resp_count, "Delay_Req")
+                    .or_else(|| {
+                        unanswered(self.pdelay_req_count, self.pdelay_resp_count, "Pdelay_Req")
+                    })
+                    .unwrap_or_else(|| {
+                        S::observed(
+                            PortState::TimeReceiver,
+                            if self.delay_resp_count > 0 || self.pdelay_resp_count > 0 {
+                                "Delay requests answered".to_string()
+                            } else {
+                                "Delay requests while Sync is sent".to_string()
+                            },
+                        )
+                    })
+            }
+        }
+    }
+
+    fn record_packet(&mut self, packet: &Arc<ParsedPacket>, sent: bool) {
+        if sent {
+            self.total_messages_sent_count += 1;
+        } else {
+            self.total_messages_received_count += 1;
+        }
+
+        match packet.ptp {
+            PtpMessage::Announce(_) => self.announce_count += 1,
+            PtpMessage::Sync(_) => self.sync_count += 1,
+            PtpMessage::FollowUp(_) => self.follow_up_count += 1,
+            PtpMessage::DelayReq(_) => self.delay_req_count += 1,
+            PtpMessage::DelayResp(_) => self.delay_resp_count += 1,
+            PtpMessage::PDelayReq(_) => self.pdelay_req_count += 1,
+            PtpMessage::PDelayResp(_) => self.pdelay_resp_count += 1,
+            PtpMessage::PDelayRespFollowup(_) => self.pdelay_resp_follow_up_count += 1,
+            PtpMessage::Signaling(_) => self.signaling_message_count += 1,
+            PtpMessage::Management(_) => self.management_message_count += 1,
+        }
+
+        let header = packet.ptp.header();
+        if sent {
+            self.segment = Some(PtpSegment::from_packet(&packet.raw, header.domain_number));
+            self.major_sdo_id = Some(header.major_sdo_id);
+            self.capture_interfaces
+                .insert(packet.raw.interface_name.clone(), packet.raw.timestamp);
+            if let (Some(source_mac), Some(dest_mac)) = (packet.raw.source_mac, packet.raw.dest_mac)
+                && dest_mac[0] & 0x01 != 0
+            {
+                self.source_macs
+                    .insert(MacAddress(source_mac), packet.raw.timestamp);
+            }
+        }
+        if sent
+            && !matches!(
+                header.message_type,
+                PtpMessageType::DelayResp
+                    | PtpMessageType::PDelayResp
+                    | PtpMessageType::PDelayRespFollowUp
+            )
+        {
+            self.sequence_streams
+                .entry((header.message_type, header.domain_number))
+                .or_default()
+                .record(header.sequence_id);
+            self.message_intervals
+                .entry(header.message_type)
+                .or_default()
+                .record(packet.raw.timestamp, header.log_message_interval);
+        }
+
+        let own_path = match header.message_type {
+            PtpMessageType::Sync | PtpMessageType::FollowUp | PtpMessageType::DelayReq => sent,
+            PtpMessageType::DelayResp => !sent,
+            _ => false,
+        };
+        if own_path {
+            self.corrections
+                .entry(header.message_type)
+                .or_default()
+                .record(header.correction_field);
+        }
+
+        self.domain_number = Some(header.domain_number);
+        self.last_seen = packet.raw.timestamp;
+        self.packet_history.push(packet.clone());
+    }
+
+    /// Transparent clock seen in the corrections of the given message types
+    fn transparent_clock_in(&self, message_types: &[PtpMessageType]) -> bool {
+        message_types.iter().any(|message_type| {
+            self.corrections
+                .get(message_type)
+                .is_some_and(CorrectionStats::indicates_transparent_clock)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PtpHost {
+    pub clock_identity: ClockIdentity,
+    pub ip_addresses: HashMap<IpAddr, Vec<String>>,
+    pub interfaces: HashSet<String>, // For gPTP hosts without IP addresses
+    pub vlan_id: Option<u16>,
+    /// Mirror sessions (ERSPAN/GRE/VXLAN) the host's traffic was received through
+    pub tunnels: HashSet<TunnelInfo>,
+    /// PRP/HSR LAN statistics, if the host sends redundant frames
+    pub redundancy: Option<RedundancyStats>,
+    /// Lowest domain the host is active in, see `active_domains`
+    pub domain_number: Option<u8>,
+    /// Participation per domain, including domains the host has left
+    pub domains: BTreeMap<u8, DomainParticipation>,
+    pub last_version: Option<PtpVersion>,
+    pub last_seen: SystemTime,
+
+    pub announce_count: u32,
+    pub sync_count: u32,
+    pub follow_up_count: u32,
+    pub delay_req_count: u32,
+    pub delay_resp_count: u32,
+    pub pdelay_req_count: u32,
+    pub pdelay_resp_count: u32,
+    pub pdelay_resp_follow_up_count: u32,
+    pub total_messages_sent_count: u32,
+    pub total_messages_received_count: u32,
+    pub signaling_message_count: u32,
+    pub management_message_count: u32,
+
+    /// Summary of the port states: the best transmitter port if there is one,
+    /// otherwise the first receiving port
+    pub state: PtpHostState,
+    pub ports: BTreeMap<PortIdentity, PtpPort>,
+    pub last_correction_field: Option<PtpCorrectionField>,
+    pub packet_history: BoundedVec<Arc<ParsedPacket>>,
+    /// Inferred port state of the port summarised in `state`
+    pub inferred_state: InferredPortState,
+    /// Signs that several devices use this clock identity, see `detect_identity_conflicts`
+    pub identity_conflicts: Vec<ClockIdentityConflict>,
+}
+
+impl PtpHost {
+    pub fn new(clock_identity: ClockIdentity) -> Self {
+        Self {
+            clock_identity,
+            ip_addresses: HashMap::new(),
+            interfaces: HashSet::new(),
+            vlan_id: None,
+            tunnels: HashSet::new(),
+            redundancy: None,
+            domain_number: None,
+            domains: BTreeMap::new(),
+            last_seen: SystemTime::now(),
+
+            announce_count: 0,
+            sync_count: 0,
+            follow_up_count: 0,
+            delay_req_count: 0,
+            delay_resp_count: 0,
+            pdelay_req_count: 0,
+            pdelay_resp_count: 0,
+            pdelay_resp_follow_up_count: 0,
+            total_messages_sent_count: 0,
+            total_messages_received_count: 0,
+            signaling_message_count: 0,
+            management_message_count: 0,
+
+            state: PtpHostState::Listening,
+            ports: BTreeMap::new(),
+            last_version: None,
+            last_correction_field: None,
+            packet_history: BoundedVec::new(1000), // Default max history
+            inferred_state: InferredPortState::default(),
+            identity_conflicts: Vec::new(),
+        }
+    }
+
+    fn update_from_ptp_header(&mut self, header: &PtpHeader) {
+        self.last_version = Some(header.version);
+        self.last_correction_field = Some(header.correction_field);
+    }
+
+    /// Record a message sent from or requested by one of this clock's ports
+    fn record_packet(
+        &mut self,
+        port_identity: PortIdentity,
+        packet: &Arc<ParsedPacket>,
+        sent: bool,
+    ) -> &mut PtpPort {
+        self.last_seen = packet.raw.timestamp;
+        self.add_packet(packet.clone());
+
+        let header = packet.ptp.header();
+        self.domains
+            .entry(header.domain_number)
+            .or_insert_with(|| DomainParticipation::new(packet.raw.timestamp))
+            .record(header.message_type, sent, packet.raw.timestamp);
+        let primary_domain = self.active_domains(self.default_timeout()).next();
+        self.domain_number = primary_domain.or(Some(header.domain_number));
+
+        let max_history = self.packet_history.max_size;
+        let port = self
+            .ports
+            .entry(port_identity)
+            .or_insert_with(|| PtpPort::new(port_identity, max_history));
+        port.record_packet(packet, sent);
+        port
+    }
+
+    /// Recalculate the clock-wide counters and state from its ports
+    fn refresh_from_ports(&mut self) {
+        let ports = &self.ports;
+        let sum = |count: fn(&PtpPort) -> u32| ports.values().map(count).sum();
+
+        self.announce_count = sum(|p| p.announce_count);
+        self.sync_count = sum(|p| p.sync_count);
+        self.follow_up_count = sum(|p| p.follow_up_count);
+        self.delay_req_count = sum(|p| p.delay_req_count);
+        self.delay_resp_count = sum(|p| p.delay_resp_count);
+        self.pdelay_req_count = sum(|p| p.pdelay_req_count);
+        self.pdelay_resp_count = sum(|p| p.pdelay_resp_count);
+        self.pdelay_resp_follow_up_count = sum(|p| p.pdelay_resp_follow_up_count);
+        self.total_messages_sent_count = sum(|p| p.total_messages_sent_count);
+        self.total_messages_received_count = sum(|p| p.total_messages_received_count);
+        self.signaling_message_count = sum(|p| p.signaling_message_count);
+        self.management_message_count = sum(|p| p.management_message_count);
+
+        // On equal precedence the lowest port number wins
+        let summary = self
+            .ports
+            .values()
+            .rev()
+            .max_by_key(|p| p.state.precedence());
+        self.state = summary.map(|p| p.state.clone()).unwrap_or_default();
+        self.inferred_state = summary
+            .map(|p| p.inferred_state.clone())
+            .unwrap_or_default();
+    }
+
+    /// Short state for the host table and tree: the role, unless the inferred port state
+    /// tells more, e.g. "PA" for a passive transmitter
+    pub fn state_short_string(&self) -> &str {
+        if self.inferred_state.state.refines_role() {
+            self.inferred_state.state.short_string()
+        } else {
+            self.state.short_string()
+        }
+    }
+
+    /// The transmitter selected by the first receiving port, e.g. the upstream
+    /// transmitter of a boundary clock
+    pub fn get_selected_transmitter(&self) -> Option<PortIdentity> {
+        self.ports.values().find_map(|p| match &p.state {
+            PtpHostState::TimeReceiver(s) => s.selected_transmitter_identity,
+            _ => None,
+        })
+    }
+
+    /// Ports announcing another clock's time, with stepsRemoved > 0
+    fn relay_ports(&self) -> impl Iterator<Item = (&PtpPort, &PtpHostStateTimeTransmitter)> {
+        self.ports.values().filter_map(|port| match &port.state {
+            PtpHostState::TimeTransmitter(s) | PtpHostState::Stale(s) if s.is_relay => {
+                Some((port, s))
+            }
+            _ => None,
+        })
+    }
+
+    /// A boundary clock relays a grandmaster's time on at least one of its ports
+    pub fn is_boundary_clock(&self) -> bool {
+        self.relay_ports().next().is_some()
+    }
+
+    /// The transmitter a boundary clock takes its time from: the one its receiving port
+    /// selected, or else the only port announcing the same grandmaster one step closer
+    pub fn boundary_clock_upstream(&self, hosts: &[PtpHost]) -> Option<PortIdentity> {
+        if let Some(selected) = self.get_selected_transmitter() {
+            return Some(selected);
+        }
+
+        let (_, relay) = self.relay_ports().next()?;
+        let (gm_identity, steps_removed) = (relay.gm_identifier?, relay.steps_removed?);
+        let mut candidates = hosts
+            .iter()
+            .filter(|host| host.clock_identity != self.clock_identity)
+            .flat_map(|host| host.ports.values())
+            .filter(|port| match &port.state {
+                PtpHostState::TimeTransmitter(s) => {
+                    s.gm_identifier == Some(gm_identity)
+                        && s.steps_removed == Some(steps_removed - 1)
+                }
+                _ => false,
+            })
+            .map(|port| port.port_identity);
+        let upstream = candidates.next();
+        candidates.next().is_none().then_some(upstream).flatten()
+    }
+
+    /// stepsRemoved of this boundary clock's ports that doesn't follow from the one of its
+    /// upstream transmitter, which should be exactly one less
+    pub fn steps_removed_mismatch(&self, hosts: &[PtpHost]) -> Option<StepsRemovedMismatch> {
+        let upstream = self.boundary_clock_upstream(hosts)?;
+        let upstream_steps = hosts
+            .iter()
+            .find(|host| host.clock_identity == upstream.clock_identity)?
+            .ports
+            .get(&upstream)
+            .and_then(|port| match &port.state {
+                PtpHostState::TimeTransmitter(s) | PtpHostState::Stale(s) => s.steps_removed,
+                _ => None,
+            })?;
+        // No valid stepsRemoved follows an upstream one of 65535
+        let expected = upstream_steps.checked_add(1)?;
+        self.relay_ports()
+            .filter_map(|(port, s)| Some((port.port_identity, s.steps_removed?)))
+            .find(|(_, announced)| *announced != expected)
+            .map(|(port, announced)| StepsRemovedMismatch {
+                port,
+                upstream,
+                upstream_steps,
+                announced,
+            })
+    }
+
+    /// A copy of this host with the state, counters and history of one of its ports
+    pub fn port_view(&self, port: &PtpPort) -> PtpHost {
+        PtpHost {
+            domain_number: port.domain_number,
+            last_seen: port.last_seen,
+            announce_count: port.announce_count,
+            sync_count: port.sync_count,
+            follow_up_count: port.follow_up_count,
+            delay_req_count: port.delay_req_count,
+            delay_resp_count: port.delay_resp_count,
+            pdelay_req_count: port.pdelay_req_count,
+            pdelay_resp_count: port.pdelay_resp_count,
+            pdelay_resp_follow_up_count: port.pdelay_resp_follow_up_count,
+            total_messages_sent_count: port.total_messages_sent_count,
+            total_messages_received_count: port.total_messages_received_count,
+            signaling_message_count: port.signaling_message_count,
+            management_message_count: port.management_message_count,
+            state: port.state.clone(),
+            ports: BTreeMap::from([(port.port_identity, port.clone())]),
+            packet_history: port.packet_history.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// The segments this host's ports send in
+    pub fn segments(&self) -> BTreeSet<PtpSegment> {
+        self.ports
+            .values()
+            .filter_map(|port| port.segment.clone())
+            .collect()
+    }
+
+    /// Domains with messages of this host within `timeout` of its last message, in
+    /// ascending order
+    pub fn active_domains(&self, timeout: Duration) -> impl Iterator<Item = u8> + '_ {
+        self.domains
+            .iter()
+            .filter(move |(_, participation)| {
+                self.last_seen
+                    .duration_since(participation.last_seen)
+                    .unwrap_or_default()
+                    <= timeout
+            })
+            .map(|(domain, _)| *domain)
+    }
+
+    /// Active domains for display, e.g. "0,127"
+    pub fn domains_string(&self, timeout: Duration) -> String {
+        let domains: Vec<String> = self
+            .active_domains(timeout)
+            .map(|domain| domain.to_string())
+            .collect();
+        if domains.is_empty() {
+            "-".to_string()
+        } else {
+            domains.join(",")
+        }
+    }
+
+    /// A copy of this host with only the ports in the given segment, e.g. one side of a
+    /// boundary clock
+    pub fn segment_view(&self, segment: &PtpSegment) -> PtpHost {
+        let mut host = PtpHost {
+            domain_number: Some(segment.domain_number),
+            domains: self
+                .domains
+                .iter()
+                .filter(|(domain, _)| **domain == segment.domain_number)
+                .map(|(domain, participation)| (*domain, participation.clone()))
+                .collect(),
+            ports: self
+                .ports
+                .iter()
+                .filter(|(_, port)| port.segment.as_ref() == Some(segment))
+                .map(|(id, port)| (*id, port.clone()))
+                .collect(),
+            ..self.clone()
+        };
+        host.refresh_from_ports();
+        host
+    }
+
+    /// Mean path delay in nanoseconds measured by any port of this host to a port of the
+    /// given clock
+    pub fn mean_path_delay_to(&self, clock_identity: &ClockIdentity) -> Option<f64> {
+        self.ports
+            .values()
+            .flat_map(|p| p.path_delays.links.iter())
+            .find(|(responder, _)| responder.clock_identity == *clock_identity)
+            .map(|(_, stats)| stats.mean_ns())
+    }
+
+    /// Transparent clock between this transmitter and the capture point
+    pub fn has_transparent_clock_on_sync_path(&self) -> bool {
+        self.ports
+            .values()
+            .any(|p| p.transparent_clock_in(&[PtpMessageType::Sync, PtpMessageType::FollowUp]))
+    }
+
+    /// Transparent clock between this receiver and the transmitter it sends delay requests to
+    pub fn has_transparent_clock_on_delay_path(&self) -> bool {
+        self.ports
+            .values()
+            .any(|p| p.transparent_clock_in(&[PtpMessageType::DelayReq, PtpMessageType::DelayResp]))
+    }
+
+    /// Packet loss over all sequence streams of all ports, in percent
+    pub fn loss_percent(&self) -> Option<f64> {
+        let (lost, expected) = self
+            .ports
+            .values()
+            .flat_map(|p| p.sequence_streams.values())
+            .fold((0u64, 0u64), |(lost, expected), s| {
+                (
+                    lost + s.lost_count as u64,
+                    expected + s.expected_count() as u64,
+                )
+            });
+        (expected > 0).then(|| lost as f64 * 100.0 / expected as f64)
+    }
+
+    pub fn has_multiple_ports(&self) -> bool {
+        self.ports.len() > 1
+    }
+
+    pub fn get_vendor_name(&self) -> Option<&'static str> {
+        self.clock_identity.extract_vendor_name()
+    }
+
+    pub fn is_transmitter(&self) -> bool {
+        matches!(self.state, PtpHostState::TimeTransmitter(_))
+    }
+
+    pub fn is_receiver(&self) -> bool {
+        matches!(self.state, PtpHostState::TimeReceiver(_))
+    }
+
+    pub fn time_since_last_seen(&self, reference_time: Option<SystemTime>) -> Duration {
+        let reference = reference_time.unwrap_or_else(SystemTime::now);
+        reference.duration_since(self.last_seen).unwrap_or_default()
+    }
+
+    /// Silence after which the host counts as gone: `HOST_TIMEOUT_INTERVALS` intervals of its
+    /// slowest message stream, advertised or else measured, or `DEFAULT_HOST_TIMEOUT`
+    pub fn default_timeout(&self) -> Duration {
+        self.ports
+            .values()
+            .flat_map(|port| port.message_intervals.values())
+            .filter_map(|stats| {
+                stats
+                    .advertised
+                    .and_then(|interval| interval.seconds())
+                    .map(Duration::from_secs_f64)
+                    .or_else(|| stats.mean())
+            })
+            .max()
+            .map_or(DEFAULT_HOST_TIMEOUT, |interval| {
+                (interval * HOST_TIMEOUT_INTERVALS).max(MIN_HOST_TIMEOUT)
+            })
+    }
+
+    pub fn add_ip_address(&mut self, ip: IpAddr, vlan_id: Option<u16>, interface: String) {
+        self.ip_addresses.entry(ip).or_default();
+        self.vlan_id = vlan_id;
+
+        let v = self.ip_addresses.get_mut(&ip).unwrap();
+
+        if !v.contains(&interface) {
+            v.push(interface);
+        }
+    }
+
+    pub fn add_interface(&mut self, interface: String) {
+        self.interfaces.insert(interface);
+    }
+
+    fn record_redundancy(&mut self, tag: &RedundancyTag, duplicate: bool) {
+        self.redundancy
+            .get_or_insert_with(|| RedundancyStats::new(tag.protocol))
+            .record(tag, duplicate);
+    }
+
+    pub fn get_interfaces(&self) -> &HashSet<String> {
+        &self.interfaces
+    }
+
+    /// Returns a vector of all interface names the host was seen on
+    pub fn get_interface_names(&self) -> Vec<String> {
+        let mut interface_names = std::collections::HashSet::new();
+
+        // Add interfaces from the interfaces HashSet (for gPTP hosts)
+        for interface in &self.interfaces {
+            interface_names.insert(interface.clone());
+        }
+
+        // Add interfaces from IP addresses (for PTP over UDP)
+        for interfaces in self.ip_addresses.values() {
+            for interface in interfaces {
+                interface_names.insert(interface.clone());
+            }
         }
 
         let mut result: Vec<String> = interface_names.into_iter().collect();
@@ -559,10 +3004,14 @@ impl PtpHost {
     }
 
     pub fn set_max_packet_history(&mut self, max_history: usize) {
-        self.packet_history.max_size = max_history;
-        // Truncate existing history if needed
-        while self.packet_history.len() > max_history {
-            self.packet_history.items.pop_front();
+        let histories = std::iter::once(&mut self.packet_history)
+            .chain(self.ports.values_mut().map(|p| &mut p.packet_history));
+        for history in histories {
+            history.max_size = max_history;
+            // Truncate existing history if needed
+            while history.len() > max_history {
+                history.items.pop_front();
+            }
         }
     }
 
@@ -576,6 +3025,9 @@ impl PtpHost {
 
     pub fn clear_packet_history(&mut self) {
         self.packet_history.clear();
+        for port in self.ports.values_mut() {
+            port.packet_history.clear();
+        }
     }
 }
 
@@ -701,33 +3153,1190 @@ mod tests {
             Some(1),
             "eth0".to_string(),
         );
-        assert_eq!(host.get_primary_interface(), Some(&"eth0".to_string()));
-        assert!(!host.has_multiple_interfaces());
-
-        // Add second interface via IP address - should now have multiple
-        host.add_ip_address(
-            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)),
-            Some(1),
-            "eth1".to_string(),
+        assert_eq!(host.get_primary_interface(), Some(&"eth0".to_string()));
+        assert!(!host.has_multiple_interfaces());
+
+        // Add second interface via IP address - should now have multiple
+        host.add_ip_address(
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)),
+            Some(1),
+            "eth1".to_string(),
+        );
+        assert!(host.has_multiple_interfaces());
+        // Primary interface should still be the first one
+        let primary = host.get_primary_interface();
+        assert!(primary.is_some());
+
+        // Add interface directly (gPTP) - should still detect multiple
+        host.add_interface("wlan0".to_string());
+        assert!(host.has_multiple_interfaces());
+        assert_eq!(host.get_interface_count(), 3);
+
+        // Test with only direct interfaces (gPTP scenario)
+        let mut host2 = PtpHost::new(ClockIdentity::default());
+        host2.add_interface("wlan0".to_string());
+        assert_eq!(host2.get_primary_interface(), Some(&"wlan0".to_string()));
+        assert!(!host2.has_multiple_interfaces());
+
+        host2.add_interface("eth0".to_string());
+        assert!(host2.has_multiple_interfaces());
+    }
+
+    fn create_clock_identity(id: u8) -> ClockIdentity {
+        ClockIdentity {
+            clock_id: [0, 0, 0, 0, 0, 0, 0, id],
+        }
+    }
+
+    fn ptp_packet(
+        message_type: u8,
+        clock_id: u8,
+        port_number: u16,
+        len: usize,
+    ) -> (Vec<u8>, crate::source::RawPacket) {
+        let mut payload = vec![0u8; len];
+        payload[0] = message_type;
+        payload[1] = 0x02;
+        payload[2..4].copy_from_slice(&(len as u16).to_be_bytes());
+        payload[27] = clock_id;
+        payload[28..30].copy_from_slice(&port_number.to_be_bytes());
+
+        let raw = crate::source::RawPacket {
+            timestamp: SystemTime::now(),
+            data: payload.clone(),
+            source_addr: None,
+            source_mac: None,
+            dest_addr: None,
+            dest_mac: None,
+            vlan_id: None,
+            ttl: None,
+            interface_name: "eth0".to_string(),
+            ptp_payload: payload.clone(),
+            tunnel: None,
+            redundancy: None,
+        };
+        (payload, raw)
+    }
+
+    fn announce_packet(clock_id: u8, port_number: u16, priority1: u8) -> crate::source::RawPacket {
+        let (mut payload, mut raw) = ptp_packet(0x0b, clock_id, port_number, 64);
+        payload[47] = priority1;
+        payload[48] = 248;
+        payload[52] = 128;
+        payload[60] = clock_id;
+        raw.ptp_payload = payload;
+        raw
+    }
+
+    fn delay_resp_packet(
+        clock_id: u8,
+        port_number: u16,
+        requesting_clock_id: u8,
+        requesting_port_number: u16,
+    ) -> crate::source::RawPacket {
+        let (mut payload, mut raw) = ptp_packet(0x09, clock_id, port_number, 54);
+        payload[51] = requesting_clock_id;
+        payload[52..54].copy_from_slice(&requesting_port_number.to_be_bytes());
+        raw.ptp_payload = payload;
+        raw
+    }
+
+    fn pdelay_resp_packet(
+        clock_id: u8,
+        port_number: u16,
+        requesting_clock_id: u8,
+        requesting_port_number: u16,
+    ) -> crate::source::RawPacket {
+        let (mut payload, mut raw) = ptp_packet(0x03, clock_id, port_number, 54);
+        payload[51] = requesting_clock_id;
+        payload[52..54].copy_from_slice(&requesting_port_number.to_be_bytes());
+        raw.ptp_payload = payload;
+        raw
+    }
+
+    /// An Announce of grandmaster `gm`, relayed over `steps_removed` boundary clocks
+    fn gm_announce_packet(
+        clock_id: u8,
+        port_number: u16,
+        gm: u8,
+        steps_removed: u16,
+    ) -> crate::source::RawPacket {
+        let mut raw = announce_packet(clock_id, port_number, 100);
+        raw.ptp_payload[60] = gm;
+        raw.ptp_payload[61..63].copy_from_slice(&steps_removed.to_be_bytes());
+        raw
+    }
+
+    /// A Sync from port 1 of the given clock
+    fn sync_from(clock_id: u8) -> crate::source::RawPacket {
+        ptp_packet(0x00, clock_id, 1, 44).1
+    }
+
+    /// The packet as captured on another interface
+    fn on(mut raw: crate::source::RawPacket, interface_name: &str) -> crate::source::RawPacket {
+        raw.interface_name = interface_name.to_string();
+        raw
+    }
+
+    /// The packet as captured the given number of seconds into the capture
+    fn at(mut raw: crate::source::RawPacket, secs: u64) -> crate::source::RawPacket {
+        raw.timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs);
+        raw
+    }
+
+    fn port_id(clock_id: u8, port_number: u16) -> PortIdentity {
+        PortIdentity {
+            clock_identity: create_clock_identity(clock_id),
+            port_number,
+        }
+    }
+
+    fn sync_packet(
+        message_type: u8,
+        sequence_id: u16,
+        two_step: bool,
+        offset_ms: u64,
+    ) -> crate::source::RawPacket {
+        let (mut payload, mut raw) = ptp_packet(message_type, 1, 1, 44);
+        payload[6] = if two_step { 0x02 } else { 0x00 };
+        payload[30..32].copy_from_slice(&sequence_id.to_be_bytes());
+        raw.ptp_payload = payload;
+        raw.timestamp = SystemTime::UNIX_EPOCH + Duration::from_millis(1_000_000 + offset_ms);
+        raw
+    }
+
+    fn redundant_sync_packet(lan: RedundancyLan, sequence: u16) -> crate::source::RawPacket {
+        let mut payload = vec![0u8; 44];
+        payload[1] = 0x02;
+        payload[2..4].copy_from_slice(&44u16.to_be_bytes());
+        payload[20..28].copy_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7]);
+        payload[30..32].copy_from_slice(&sequence.to_be_bytes());
+
+        crate::source::RawPacket {
+            timestamp: SystemTime::now(),
+            data: payload.clone(),
+            source_addr: None,
+            source_mac: Some([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            dest_addr: None,
+            dest_mac: None,
+            vlan_id: None,
+            ttl: None,
+            interface_name: "eth0".to_string(),
+            ptp_payload: payload,
+            tunnel: None,
+            redundancy: Some(RedundancyTag {
+                protocol: RedundancyProtocol::Prp,
+                lan,
+                sequence,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prp_duplicates_are_discarded() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            redundant_sync_packet(RedundancyLan::A, 1),
+            redundant_sync_packet(RedundancyLan::B, 1),
+            redundant_sync_packet(RedundancyLan::A, 2),
+            // LAN B copy of sequence 2 lost
+            redundant_sync_packet(RedundancyLan::B, 3),
+            redundant_sync_packet(RedundancyLan::A, 3),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let host = tracker.get_hosts()[0];
+        assert_eq!(host.sync_count, 3);
+
+        let redundancy = host.redundancy.as_ref().unwrap();
+        assert_eq!(redundancy.lan_a_count, 3);
+        assert_eq!(redundancy.lan_b_count, 2);
+        assert_eq!(redundancy.duplicates_discarded(), 2);
+        assert_eq!(redundancy.missing_on_lan_a(), 0);
+        assert_eq!(redundancy.missing_on_lan_b(), 1);
+        assert!(redundancy.loss_asymmetry_percent() > 33.0);
+    }
+
+    #[tokio::test]
+    async fn test_boundary_clock_ports_are_tracked_separately() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Grandmaster 1 and the boundary clock 2 announcing on its second port
+            announce_packet(1, 1, 100),
+            announce_packet(2, 2, 128),
+            // The boundary clock's first port synchronises to the grandmaster
+            ptp_packet(0x01, 2, 1, 44).1,
+            delay_resp_packet(1, 1, 2, 1),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let boundary_clock = tracker
+            .get_host_by_clock_identity(&create_clock_identity(2))
+            .unwrap();
+        assert_eq!(boundary_clock.ports.len(), 2);
+        assert!(boundary_clock.is_transmitter());
+
+        let ports: Vec<&PtpPort> = boundary_clock.ports.values().collect();
+        match &ports[0].state {
+            PtpHostState::TimeReceiver(s) => {
+                let selected = s.selected_transmitter_identity.unwrap();
+                assert_eq!(selected.clock_identity, create_clock_identity(1));
+                assert_eq!(selected.port_number, 1);
+            }
+            state => panic!("expected port 1 to be a receiver, got {}", state),
+        }
+        assert_eq!(ports[0].delay_req_count, 1);
+        assert_eq!(ports[0].delay_resp_count, 1);
+        assert_eq!(ports[0].packet_history.len(), 2);
+
+        match &ports[1].state {
+            PtpHostState::TimeTransmitter(s) => assert!(!s.is_bmca_winner),
+            state => panic!("expected port 2 to be a transmitter, got {}", state),
+        }
+        assert_eq!(ports[1].announce_count, 1);
+
+        // Clock-wide counters are the sum over all ports
+        assert_eq!(boundary_clock.total_messages_sent_count, 2);
+        assert_eq!(boundary_clock.total_messages_received_count, 1);
+        assert_eq!(
+            boundary_clock.get_selected_transmitter(),
+            Some(PortIdentity {
+                clock_identity: create_clock_identity(1),
+                port_number: 1,
+            })
+        );
+
+        let grandmaster = tracker
+            .get_host_by_clock_identity(&create_clock_identity(1))
+            .unwrap();
+        assert_eq!(grandmaster.state.short_string(), "GM");
+    }
+
+    #[tokio::test]
+    async fn test_sync_follow_up_pairing() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Regular two-step pair with 2 ms latency
+            sync_packet(0x00, 1, true, 0),
+            sync_packet(0x08, 1, false, 2),
+            // One-step Sync followed by a Follow_Up, 20 ms late
+            sync_packet(0x00, 2, false, 100),
+            sync_packet(0x08, 2, false, 120),
+            // Follow_Up without Sync
+            sync_packet(0x08, 9, false, 200),
+            // Two-step Sync whose Follow_Up never arrives
+            sync_packet(0x00, 3, true, 300),
+            sync_packet(0x00, 4, true, 1500),
+            sync_packet(0x08, 4, false, 1501),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let host = tracker.get_hosts()[0];
+        let stats = &host.ports.values().next().unwrap().sync_follow_up;
+        assert_eq!(stats.matched_count, 3);
+        assert_eq!(stats.issue_count(SyncFollowUpIssue::MissingFollowUp), 1);
+        assert_eq!(stats.issue_count(SyncFollowUpIssue::LateFollowUp), 1);
+        assert_eq!(stats.issue_count(SyncFollowUpIssue::FollowUpWithoutSync), 1);
+        assert_eq!(stats.issue_count(SyncFollowUpIssue::TwoStepMismatch), 1);
+        assert_eq!(stats.min_latency, Some(Duration::from_millis(1)));
+        assert_eq!(stats.max_latency, Some(Duration::from_millis(20)));
+        assert_eq!(stats.mean_latency(), Some(Duration::from_millis(23) / 3));
+        assert_eq!(
+            stats.latency_percentile(50.0),
+            Some(Duration::from_millis(2))
+        );
+    }
+
+    #[test]
+    fn test_sequence_gaps_duplicates_and_reordering() {
+        let mut stats = SequenceStats::default();
+        // Wraparound, a gap of two, a duplicate and one late packet
+        for sequence_id in [65534, 65535, 0, 3, 3, 2, 4] {
+            stats.record(sequence_id);
+        }
+        assert_eq!(stats.received_count, 7);
+        assert_eq!(stats.duplicate_count, 1);
+        assert_eq!(stats.reordered_count, 1);
+        assert_eq!(stats.lost_count, 1);
+        assert_eq!(stats.expected_count(), 7);
+        assert!((stats.loss_percent() - 100.0 / 7.0).abs() < 1e-9);
+
+        // Sender restarts, jumping forward or back, aren't counted as loss or reordering
+        stats.record(1000);
+        stats.record(10);
+        assert_eq!(stats.lost_count, 1);
+        assert_eq!(stats.restart_count, 2);
+        assert_eq!(stats.reordered_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_message_intervals_against_advertised() {
+        // Sync every 125 ms (logMessageInterval -3) with one 500 ms gap at the end
+        let receiver = crate::source::RawSocketReceiver::from_packets(
+            [0, 125, 250, 375, 500, 1000]
+                .into_iter()
+                .enumerate()
+                .map(|(i, offset_ms)| {
+                    let mut raw = sync_packet(0x00, i as u16, false, offset_ms);
+                    raw.ptp_payload[33] = (-3i8) as u8;
+                    raw
+                })
+                .collect(),
+        );
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let host = tracker.get_hosts()[0];
+        let stats = &host.ports.values().next().unwrap().message_intervals[&PtpMessageType::Sync];
+        assert_eq!(stats.advertised, Some(PtpLogInterval::new(-3)));
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.min, Some(Duration::from_millis(125)));
+        assert_eq!(stats.max, Some(Duration::from_millis(500)));
+        assert_eq!(stats.mean(), Some(Duration::from_millis(200)));
+        assert_eq!(stats.histogram, [0, 0, 0, 4, 0, 0, 1]);
+        assert!((stats.deviation_percent().unwrap() - 60.0).abs() < 1e-9);
+        assert!(stats.deviates(20.0));
+        assert!(!stats.deviates(75.0));
+    }
+
+    #[tokio::test]
+    async fn test_gm_offset_and_time_steps() {
+        // Announce with the PTP timescale flag and a UTC offset of 37 s
+        let mut announce = announce_packet(1, 1, 128);
+        announce.ptp_payload[7] = 0x08;
+        announce.ptp_payload[44..46].copy_from_slice(&37i16.to_be_bytes());
+
+        // Syncs whose origin timestamp (TAI) is 1 ms ahead of the capture time (UTC)
+        let sync = |sequence_id: u16, offset_ms: u64, two_step: bool, origin_ms: i64| {
+            let mut raw = sync_packet(0x00, sequence_id, two_step, offset_ms);
+            let origin = Duration::from_millis((1_000_000 + 37_000 + origin_ms) as u64);
+            raw.ptp_payload[34..40].copy_from_slice(&origin.as_secs().to_be_bytes()[2..]);
+            raw.ptp_payload[40..44].copy_from_slice(&origin.subsec_nanos().to_be_bytes());
+            raw
+        };
+        let mut follow_up = sync(3, 201, false, 201);
+        follow_up.ptp_payload[0] = 0x08;
+        // Correction of 0.5 ms, in 2^-16 ns
+        follow_up.ptp_payload[8..16].copy_from_slice(&(500_000i64 << 16).to_be_bytes());
+
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            announce,
+            sync(1, 0, false, 1),
+            sync(2, 100, false, 101),
+            // Two-step pair, the Follow_Up carries the origin timestamp of the Sync
+            sync(3, 200, true, 0),
+            follow_up,
+            // The grandmaster loses its UTC offset
+            sync(4, 300, false, 301 - 37_000),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let host = tracker.get_hosts()[0];
+        let stats = &host.ports.values().next().unwrap().gm_offset;
+        assert_eq!(stats.timescale, Some(PtpTimescale::Ptp { utc_offset: 37 }));
+        let offsets: Vec<i64> = stats.samples.items.iter().map(|s| s.offset_ns).collect();
+        assert_eq!(offsets, [1_000_000, 1_000_000, 1_500_000, -36_999_000_000]);
+        assert_eq!(stats.step_count, 1);
+        assert_eq!(stats.last_step.unwrap().step_ns, -37_000_500_000);
+        assert_eq!(stats.latest(), Some(-36_999_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_path_delay_from_exchanges() {
+        let base = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        // Message from clock/port 1, captured `capture_us` after `base`, with a timestamp
+        // `timestamp_us` after `base` and an optional requesting port
+        let packet = |message_type: u8,
+                      clock_id: u8,
+                      sequence_id: u16,
+                      capture_us: u64,
+                      timestamp_us: u64,
+                      requesting_clock_id: u8| {
+            let (mut payload, mut raw) = ptp_packet(message_type, clock_id, 1, 64);
+            payload[30..32].copy_from_slice(&sequence_id.to_be_bytes());
+            let timestamp = Duration::from_secs(1_000_000) + Duration::from_micros(timestamp_us);
+            payload[34..40].copy_from_slice(&timestamp.as_secs().to_be_bytes()[2..]);
+            payload[40..44].copy_from_slice(&timestamp.subsec_nanos().to_be_bytes());
+            payload[51] = requesting_clock_id;
+            payload[53] = 1;
+            raw.ptp_payload = payload;
+            raw.timestamp = base + Duration::from_micros(capture_us);
+            raw
+        };
+
+        let mut pdelay_resp = packet(0x03, 3, 5, 1_000, 0, 2);
+        pdelay_resp.ptp_payload[6] = 0x02;
+
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // P2P: 1 ms round trip seen by the capture, 400 µs turnaround of the peer
+            packet(0x02, 2, 5, 0, 0, 0),
+            pdelay_resp,
+            packet(0x0a, 3, 5, 1_100, 400, 2),
+            // E2E: the grandmaster's clock is 5 ms ahead, the path delay is 200 µs
+            packet(0x0b, 1, 1, 0, 0, 0),
+            packet(0x00, 1, 1, 200, 5_000, 0),
+            packet(0x01, 2, 7, 100_000, 0, 0),
+            packet(0x09, 1, 7, 100_500, 105_200, 2),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let requester = tracker
+            .get_host_by_clock_identity(&create_clock_identity(2))
+            .unwrap();
+        let links = &requester.ports.values().next().unwrap().path_delays.links;
+
+        let p2p = links
+            .iter()
+            .find(|(responder, _)| responder.clock_identity == create_clock_identity(3))
+            .unwrap()
+            .1;
+        assert_eq!(p2p.mechanism, DelayMechanism::P2p);
+        assert_eq!(p2p.last_ns, 300_000);
+        assert_eq!(p2p.turnaround_ns, Some(400_000));
+
+        let e2e = links
+            .iter()
+            .find(|(responder, _)| responder.clock_identity == create_clock_identity(1))
+            .unwrap()
+            .1;
+        assert_eq!(e2e.mechanism, DelayMechanism::E2e);
+        assert_eq!(e2e.last_ns, 200_000);
+        assert_eq!(
+            requester.mean_path_delay_to(&create_clock_identity(1)),
+            Some(200_000.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_correction_field_statistics() {
+        let with_correction = |mut raw: crate::source::RawPacket, correction: i64| {
+            raw.ptp_payload[8..16].copy_from_slice(&correction.to_be_bytes());
+            raw
+        };
+
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Residence times of 2 µs and 5 µs, then a negative and a fractional correction
+            with_correction(sync_packet(0x00, 1, false, 0), 2_000 << 16),
+            with_correction(sync_packet(0x00, 2, false, 100), 5_000 << 16),
+            with_correction(sync_packet(0x00, 3, false, 200), -(1_000 << 16)),
+            with_correction(sync_packet(0x00, 4, false, 300), (3_000 << 16) + 0x8000),
+            // Delay_Req of clock 2 passes no TC
+            ptp_packet(0x01, 2, 1, 44).1,
+            delay_resp_packet(1, 1, 2, 1),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let transmitter = tracker
+            .get_host_by_clock_identity(&create_clock_identity(1))
+            .unwrap();
+        let stats = &transmitter.ports.values().next().unwrap().corrections[&PtpMessageType::Sync];
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.nonzero_count, 4);
+        assert_eq!(stats.negative_count, 1);
+        assert_eq!(stats.fractional_count, 1);
+        assert_eq!(stats.huge_count, 0);
+        assert_eq!((stats.min_ns, stats.max_ns), (-1_000, 5_000));
+        assert_eq!(stats.mean_ns(), Some(2_250));
+        assert_eq!(stats.percentile_ns(50.0), Some(3_000));
+        assert!(stats.is_suspicious());
+        assert!(transmitter.has_transparent_clock_on_sync_path());
+
+        let receiver_host = tracker
+            .get_host_by_clock_identity(&create_clock_identity(2))
+            .unwrap();
+        assert!(!receiver_host.has_transparent_clock_on_delay_path());
+    }
+
+    #[tokio::test]
+    async fn test_bmca_election_with_boundary_clock() {
+        // Clock 5 is the grandmaster. Boundary clock 2 relays it with stepsRemoved 1 and
+        // has the lower clock identity, but must not win and is shown as a relay.
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            gm_announce_packet(5, 1, 5, 0),
+            gm_announce_packet(2, 2, 5, 1),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let gm = tracker
+            .get_host_by_clock_identity(&create_clock_identity(5))
+            .unwrap();
+        assert_eq!(gm.state.short_string(), "GM");
+
+        let boundary_clock = tracker
+            .get_host_by_clock_identity(&create_clock_identity(2))
+            .unwrap();
+        assert_eq!(boundary_clock.state.short_string(), "BC");
+    }
+
+    #[tokio::test]
+    async fn test_announce_receipt_timeout_and_qualification() {
+        // Announces every second (logMessageInterval 0). Clock 2 is the better clock but
+        // goes silent, clock 3 is better still but has only announced once when the
+        // capture ends
+        let mut packets = vec![
+            at(announce_packet(2, 1, 100), 0),
+            at(announce_packet(2, 1, 100), 1),
+        ];
+        packets.extend((0..=12).map(|secs| at(announce_packet(1, 1, 200), secs)));
+        packets.push(at(announce_packet(3, 1, 50), 12));
+
+        let receiver = crate::source::RawSocketReceiver::from_packets(packets);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let state = |clock_id: u8| {
+            tracker
+                .get_host_by_clock_identity(&create_clock_identity(clock_id))
+                .unwrap()
+                .state
+                .short_string()
+        };
+        assert_eq!(state(1), "GM");
+        assert_eq!(state(2), "ST");
+        assert_eq!(state(3), "TT");
+    }
+
+    #[tokio::test]
+    async fn test_host_timeout_and_expiry() {
+        // Clock 1 announces every second until 10s, clock 2 is only addressed at the start
+        let mut packets = vec![at(delay_resp_packet(1, 1, 2, 1), 0)];
+        packets.extend((0..=10).map(|secs| at(announce_packet(1, 1, 128), secs)));
+
+        let receiver = crate::source::RawSocketReceiver::from_packets(packets);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let transmitter = create_clock_identity(1);
+        let receiver = create_clock_identity(2);
+        assert_eq!(
+            tracker.hosts[&transmitter].default_timeout(),
+            MIN_HOST_TIMEOUT
+        );
+        assert_eq!(
+            tracker.hosts[&receiver].default_timeout(),
+            DEFAULT_HOST_TIMEOUT
+        );
+
+        // Expiry is measured against the capture time of the last packet
+        assert!(
+            tracker
+                .remove_expired_hosts(Duration::from_secs(20))
+                .is_empty()
+        );
+        assert_eq!(
+            tracker.remove_expired_hosts(Duration::from_secs(5)),
+            vec![receiver]
+        );
+        assert!(tracker.hosts.contains_key(&transmitter));
+        assert!(!tracker.hosts.contains_key(&receiver));
+    }
+
+    #[test]
+    fn test_dataset_changes_are_classified() {
+        let locked = AnnouncedDataset {
+            gm_identity: create_clock_identity(1),
+            priority1: 128,
+            priority2: 128,
+            clock_class: PtpClockClass::new(6),
+            clock_accuracy: PtpClockAccuracy::new(0x21),
+            offset_scaled_log_variance: 0x4e5d,
+            time_source: 0x20,
+            current_utc_offset: PtpUtcOffset::new(37),
+            current_utc_offset_valid: true,
+            leap: None,
+            ptp_timescale: true,
+            time_traceable: true,
+            frequency_traceable: true,
+        };
+        // Lost GPS: holdover with a worse accuracy, no longer traceable
+        let holdover = AnnouncedDataset {
+            clock_class: PtpClockClass::new(7),
+            clock_accuracy: PtpClockAccuracy::new(0x23),
+            time_source: 0xa0,
+            time_traceable: false,
+            ..locked
+        };
+
+        let changes = holdover.changes_from(&locked);
+        let direction = |field| {
+            changes
+                .iter()
+                .find(|change| change.field == field)
+                .map(|change| change.direction)
+        };
+        assert_eq!(changes.len(), 4);
+        assert_eq!(
+            direction(DatasetField::ClockClass),
+            Some(DatasetChangeDirection::Degraded)
+        );
+        assert_eq!(
+            direction(DatasetField::ClockAccuracy),
+            Some(DatasetChangeDirection::Degraded)
+        );
+        assert_eq!(
+            direction(DatasetField::TimeTraceable),
+            Some(DatasetChangeDirection::Degraded)
+        );
+        assert_eq!(
+            direction(DatasetField::TimeSource),
+            Some(DatasetChangeDirection::Changed)
+        );
+        assert_eq!(
+            holdover.quality_change_from(&locked),
+            Some(DatasetChangeDirection::Degraded)
+        );
+        assert_eq!(
+            locked.quality_change_from(&holdover),
+            Some(DatasetChangeDirection::Improved)
+        );
+
+        // Class 52 (degraded A) is better than free-running 248 despite being further from 6
+        let degraded_a = AnnouncedDataset {
+            clock_class: PtpClockClass::new(52),
+            ..locked
+        };
+        let free_running = AnnouncedDataset {
+            clock_class: PtpClockClass::new(248),
+            ..locked
+        };
+        assert_eq!(
+            free_running.quality_change_from(&degraded_a),
+            Some(DatasetChangeDirection::Degraded)
+        );
+        assert_eq!(locked.quality_change_from(&locked), None);
+    }
+
+    #[tokio::test]
+    async fn test_inferred_port_states() {
+        let mut packets = Vec::new();
+        for secs in 0..=5 {
+            // Clock 1 is the active grandmaster, clock 2 a backup announcing without Sync
+            packets.push(at(announce_packet(1, 1, 100), secs));
+            packets.push(at(ptp_packet(0x00, 1, 1, 44).1, secs));
+            packets.push(at(announce_packet(2, 1, 200), secs));
+        }
+        // Clock 3 just started announcing
+        packets.push(at(announce_packet(3, 1, 150), 5));
+        // Clock 4 reports its own port as UNCALIBRATED in a PORT_DATA_SET response
+        let (mut payload, raw) = ptp_packet(0x0d, 4, 1, 66);
+        payload[46] = MANAGEMENT_ACTION_RESPONSE;
+        payload[48..50].copy_from_slice(&0x0001u16.to_be_bytes());
+        payload[52..54].copy_from_slice(&0x2004u16.to_be_bytes());
+        payload[61] = 4;
+        payload[62..64].copy_from_slice(&1u16.to_be_bytes());
+        payload[64] = 8;
+        packets.push(at(
+            crate::source::RawPacket {
+                ptp_payload: payload,
+                ..raw
+            },
+            5,
+        ));
+
+        let receiver = crate::source::RawSocketReceiver::from_packets(packets);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let state = |clock_id: u8| {
+            tracker.hosts[&create_clock_identity(clock_id)]
+                .inferred_state
+                .clone()
+        };
+        assert_eq!(state(1).state, PortState::TimeTransmitter);
+        assert_eq!(state(2).state, PortState::Passive);
+        assert_eq!(state(3).state, PortState::PreTimeTransmitter);
+        assert_eq!(state(4).state, PortState::Uncalibrated);
+        assert!(state(4).from_management);
+        assert!(!state(2).from_management);
+
+        assert_eq!(
+            tracker.hosts[&create_clock_identity(1)].state_short_string(),
+            "GM"
+        );
+        assert_eq!(
+            tracker.hosts[&create_clock_identity(2)].state_short_string(),
+            "PA"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_p2p_receivers_are_inferred_from_link_peers() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Grandmaster 1 and port 1 of the 802.1AS bridge 2 measure the link both ways
+            announce_packet(1, 1, 100),
+            ptp_packet(0x00, 1, 1, 44).1,
+            ptp_packet(0x02, 2, 1, 54).1,
+            pdelay_resp_packet(1, 1, 2, 1),
+            ptp_packet(0x02, 1, 1, 54).1,
+            pdelay_resp_packet(2, 1, 1, 1),
+            // The bridge passes Sync on to end station 3, which only requests
+            ptp_packet(0x00, 2, 2, 44).1,
+            ptp_packet(0x02, 3, 1, 54).1,
+            ptp_packet(0x02, 3, 1, 54).1,
+            pdelay_resp_packet(2, 2, 3, 1),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let selection = |clock_id: u8, port_number: u16| {
+            let port = &tracker.hosts[&create_clock_identity(clock_id)].ports
+                [&port_id(clock_id, port_number)];
+            match &port.state {
+                PtpHostState::TimeReceiver(s) => s
+                    .selected_transmitter_identity
+                    .map(|id| (id, s.selected_transmitter_confidence)),
+                _ => None,
+            }
+        };
+
+        let (transmitter, confidence) = selection(2, 1).unwrap();
+        assert_eq!(transmitter, port_id(1, 1));
+        assert!((confidence - 0.9).abs() < 1e-4);
+        let (transmitter, confidence) = selection(3, 1).unwrap();
+        assert_eq!(transmitter, port_id(2, 2));
+        assert!((confidence - 0.7).abs() < 1e-4);
+
+        // Answered Pdelay requests don't turn the grandmaster into a receiver
+        let gm = &tracker.hosts[&create_clock_identity(1)];
+        assert!(gm.is_transmitter());
+        assert_eq!(
+            tracker.hosts[&create_clock_identity(2)].get_selected_transmitter(),
+            Some(port_id(1, 1))
+        );
+
+        // Each request is counted once, on the port that sent it
+        let end_station = &tracker.hosts[&create_clock_identity(3)];
+        assert_eq!(end_station.pdelay_req_count, 2);
+        assert_eq!(end_station.pdelay_resp_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_bmca_runs_per_segment() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Two separate networks, both running domain 0
+            on(announce_packet(1, 1, 100), "eth0"),
+            on(sync_from(1), "eth0"),
+            on(announce_packet(2, 1, 200), "eth1"),
+            on(sync_from(2), "eth1"),
+            // Clock 3 sends Delay_Req in the second one
+            on(ptp_packet(0x01, 3, 1, 44).1, "eth1"),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let is_gm = |clock_id: u8| {
+            matches!(
+                &tracker.hosts[&create_clock_identity(clock_id)].state,
+                PtpHostState::TimeTransmitter(s) if s.is_bmca_winner
+            )
+        };
+        assert!(is_gm(1));
+        assert!(is_gm(2));
+        assert_eq!(
+            tracker.hosts[&create_clock_identity(3)].get_selected_transmitter(),
+            Some(port_id(2, 1))
+        );
+
+        let segments = tracker.get_segment_statistics();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].segment.interface_name, "eth0");
+        assert_eq!(segments[0].segment.transport, PtpTransport::Ethernet);
+        assert_eq!(segments[0].grandmaster, Some(create_clock_identity(1)));
+        assert_eq!(segments[1].host_count, 2);
+        assert_eq!(segments[1].receiver_count, 1);
+        assert_eq!(segments[1].grandmaster, Some(create_clock_identity(2)));
+    }
+
+    #[test]
+    fn test_profile_inference() {
+        let segment = |domain_number, transport| PtpSegment {
+            interface_name: "eth0".to_string(),
+            vlan_id: None,
+            domain_number,
+            transport,
+        };
+        let l2 = PtpTransport::Ethernet;
+        let udp = PtpTransport::UdpIpv4;
+        let e2e = Some(DelayMechanism::E2e);
+        let p2p = Some(DelayMechanism::P2p);
+
+        assert_eq!(
+            PtpProfile::infer(&segment(0, l2), Some(1), p2p, None),
+            PtpProfile::Gptp
+        );
+        assert_eq!(
+            PtpProfile::infer(&segment(127, udp), Some(0), e2e, None),
+            PtpProfile::Smpte2059
+        );
+        assert_eq!(
+            PtpProfile::infer(&segment(24, l2), Some(0), e2e, None),
+            PtpProfile::TelecomG8275_1
+        );
+        assert_eq!(
+            PtpProfile::infer(&segment(44, udp), Some(0), e2e, None),
+            PtpProfile::TelecomG8275_2
+        );
+        assert_eq!(
+            PtpProfile::infer(
+                &segment(0, udp),
+                Some(0),
+                e2e,
+                Some(PtpLogInterval::new(-3))
+            ),
+            PtpProfile::Aes67
+        );
+        assert_eq!(
+            PtpProfile::infer(&segment(0, udp), Some(0), e2e, Some(PtpLogInterval::new(0))),
+            PtpProfile::DefaultE2e
+        );
+        assert_eq!(
+            PtpProfile::infer(&segment(0, l2), Some(0), p2p, None),
+            PtpProfile::DefaultP2p
+        );
+        assert_eq!(
+            PtpProfile::infer(&segment(0, udp), None, None, None),
+            PtpProfile::Unknown
+        );
+    }
+
+    #[tokio::test]
+    async fn test_domains_are_tracked_per_host() {
+        let start = SystemTime::now();
+        let in_domain = |mut raw: crate::source::RawPacket, domain: u8, secs: u64| {
+            raw.ptp_payload[4] = domain;
+            raw.timestamp = start + Duration::from_secs(secs);
+            raw
+        };
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Clock 1 serves domains 0 and 127 at once
+            in_domain(announce_packet(1, 1, 100), 127, 0),
+            in_domain(announce_packet(1, 1, 100), 0, 0),
+            in_domain(announce_packet(1, 1, 100), 127, 1),
+            // Clock 2 is a receiver in domain 0 and moves to domain 5 later
+            in_domain(ptp_packet(0x01, 2, 1, 44).1, 0, 1),
+            in_domain(ptp_packet(0x01, 2, 1, 44).1, 5, 120),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let transmitter = &tracker.hosts[&create_clock_identity(1)];
+        assert_eq!(transmitter.domain_number, Some(0));
+        assert_eq!(
+            transmitter.domains_string(transmitter.default_timeout()),
+            "0,127"
+        );
+        assert_eq!(transmitter.domains[&127].messages_sent, 2);
+        assert_eq!(transmitter.domains[&0].role(), PortState::TimeTransmitter);
+
+        let receiver = &tracker.hosts[&create_clock_identity(2)];
+        assert_eq!(receiver.domain_number, Some(5));
+        assert_eq!(receiver.domains_string(receiver.default_timeout()), "5");
+        // A longer --host-timeout still counts the old domain as active
+        assert_eq!(receiver.domains_string(Duration::from_secs(300)), "0,5");
+        assert_eq!(receiver.domains.len(), 2);
+        assert_eq!(receiver.domains[&5].role(), PortState::TimeReceiver);
+    }
+
+    #[tokio::test]
+    async fn test_boundary_clock_chain_and_steps_removed() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Grandmaster 1 on eth0, boundary clock 2 relays it into eth1
+            on(gm_announce_packet(1, 1, 1, 0), "eth0"),
+            on(gm_announce_packet(2, 2, 1, 1), "eth1"),
+            // Boundary clock 3 synchronises to 2 and relays into eth2 with a wrong
+            // stepsRemoved
+            on(ptp_packet(0x01, 3, 1, 44).1, "eth1"),
+            on(delay_resp_packet(2, 2, 3, 1), "eth1"),
+            on(gm_announce_packet(3, 2, 1, 3), "eth2"),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let hosts: Vec<PtpHost> = tracker.get_hosts().into_iter().cloned().collect();
+        let host = |clock_id: u8| &tracker.hosts[&create_clock_identity(clock_id)];
+        assert!(!host(1).is_boundary_clock());
+        assert!(host(2).is_boundary_clock());
+
+        // Boundary clock 2's receiving port wasn't seen, the upstream follows from the
+        // grandmaster and stepsRemoved of its Announces
+        assert_eq!(host(2).boundary_clock_upstream(&hosts), Some(port_id(1, 1)));
+        assert_eq!(host(2).steps_removed_mismatch(&hosts), None);
+
+        assert_eq!(host(3).boundary_clock_upstream(&hosts), Some(port_id(2, 2)));
+        assert_eq!(
+            host(3).steps_removed_mismatch(&hosts),
+            Some(StepsRemovedMismatch {
+                port: port_id(3, 2),
+                upstream: port_id(2, 2),
+                upstream_steps: 1,
+                announced: 3,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_competing_grandmasters() {
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Grandmasters 1 and 2 both send Sync on eth0, receivers 3 and 4 follow one each
+            on(gm_announce_packet(1, 1, 1, 0), "eth0"),
+            on(sync_from(1), "eth0"),
+            on(gm_announce_packet(2, 1, 2, 0), "eth0"),
+            on(sync_from(2), "eth0"),
+            on(ptp_packet(0x01, 3, 1, 44).1, "eth0"),
+            on(delay_resp_packet(1, 1, 3, 1), "eth0"),
+            on(ptp_packet(0x01, 4, 1, 44).1, "eth0"),
+            on(delay_resp_packet(2, 1, 4, 1), "eth0"),
+            // On eth1 boundary clock 6 relays grandmaster 5, they don't compete
+            on(gm_announce_packet(5, 1, 5, 0), "eth1"),
+            on(sync_from(5), "eth1"),
+            on(gm_announce_packet(6, 1, 5, 1), "eth1"),
+            on(sync_from(6), "eth1"),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let competing = tracker.get_competing_grandmasters();
+        assert_eq!(competing.len(), 1);
+        assert_eq!(competing[0].segment.interface_name, "eth0");
+        let mut sources = competing[0].sources.clone();
+        sources.sort_by_key(|source| source.port_identity);
+        assert_eq!(
+            sources,
+            vec![
+                SyncSource {
+                    port_identity: port_id(1, 1),
+                    receivers: vec![port_id(3, 1)],
+                },
+                SyncSource {
+                    port_identity: port_id(2, 1),
+                    receivers: vec![port_id(4, 1)],
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clock_identity_conflicts() {
+        const MULTICAST: [u8; 6] = [0x01, 0x1b, 0x19, 0x00, 0x00, 0x00];
+        let from = |clock_id: u8, mac: [u8; 6], interface_name: &str, eui64: bool| {
+            let mut raw = announce_packet(clock_id, 1, 100);
+            if eui64 {
+                raw.ptp_payload[23] = 0xff;
+                raw.ptp_payload[24] = 0xfe;
+            }
+            raw.source_mac = Some(mac);
+            raw.dest_mac = Some(MULTICAST);
+            raw.interface_name = interface_name.to_string();
+            raw
+        };
+        let eui64 = |clock_id: u8| ClockIdentity {
+            clock_id: [0, 0, 0, 0xff, 0xfe, 0, 0, clock_id],
+        };
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Two clones of clock 1 with different MACs
+            from(1, [2, 0, 0, 0, 0, 1], "eth0", false),
+            from(1, [2, 0, 0, 0, 0, 2], "eth0", false),
+            // Clock 2 on two networks, clock 3 only on one of them
+            from(2, [2, 0, 0, 0, 0, 3], "eth0", false),
+            from(2, [2, 0, 0, 0, 0, 3], "eth1", false),
+            from(3, [2, 0, 0, 0, 0, 4], "eth1", false),
+            // EUI-64 identities, clock 4 not derived from its MAC
+            from(4, [2, 0, 0, 0, 0, 9], "eth1", true),
+            from(5, [0, 0, 0, 0, 0, 5], "eth1", true),
+            // Clock 6 moved to another MAC, the old one no longer counts
+            {
+                let mut raw = from(6, [0, 0, 0, 0, 0, 6], "eth1", true);
+                raw.timestamp -= IDENTITY_CONFLICT_WINDOW * 2;
+                raw
+            },
+            from(6, [2, 0, 0, 0, 0, 6], "eth1", true),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let conflicts = |clock_identity: ClockIdentity| {
+            tracker.hosts[&clock_identity].identity_conflicts.clone()
+        };
+        let port_identity = |clock_identity: ClockIdentity| PortIdentity {
+            clock_identity,
+            port_number: 1,
+        };
+        assert_eq!(
+            conflicts(create_clock_identity(1)),
+            vec![ClockIdentityConflict::DuplicateMac {
+                port_identity: port_identity(create_clock_identity(1)),
+                macs: vec![
+                    MacAddress([2, 0, 0, 0, 0, 1]),
+                    MacAddress([2, 0, 0, 0, 0, 2])
+                ],
+            }]
+        );
+        assert_eq!(
+            conflicts(create_clock_identity(2)),
+            vec![ClockIdentityConflict::DuplicateInterface {
+                port_identity: port_identity(create_clock_identity(2)),
+                interfaces: vec!["eth0".to_string(), "eth1".to_string()],
+            }]
+        );
+        assert!(conflicts(create_clock_identity(3)).is_empty());
+        assert_eq!(
+            conflicts(eui64(4)),
+            vec![ClockIdentityConflict::MacMismatch {
+                derived: MacAddress([0, 0, 0, 0, 0, 4]),
+                seen: vec![MacAddress([2, 0, 0, 0, 0, 9])],
+            }]
+        );
+        assert!(conflicts(eui64(5)).is_empty());
+        assert_eq!(
+            conflicts(eui64(6)),
+            vec![ClockIdentityConflict::MacMismatch {
+                derived: MacAddress([0, 0, 0, 0, 0, 6]),
+                seen: vec![MacAddress([2, 0, 0, 0, 0, 6])],
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_properties_and_leap_seconds() {
+        const LEAP61: u8 = 0x01;
+        const UTC_OFFSET_VALID: u8 = 0x04;
+        const PTP_TIMESCALE: u8 = 0x08;
+        let announce = |clock_id: u8, flags: u8, utc_offset: i16| {
+            let mut raw = announce_packet(clock_id, 1, 100);
+            raw.ptp_payload[7] = flags;
+            raw.ptp_payload[44..46].copy_from_slice(&utc_offset.to_be_bytes());
+            raw
+        };
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            // Grandmaster 1 announces a leap second, 2 is a second behind and doesn't
+            on(
+                announce(1, PTP_TIMESCALE | UTC_OFFSET_VALID | LEAP61, 37),
+                "eth0",
+            ),
+            on(announce(2, PTP_TIMESCALE | UTC_OFFSET_VALID, 36), "eth0"),
+            // Grandmaster 3 is on the arbitrary timescale, its offset doesn't matter
+            on(announce(3, 0, 0), "eth1"),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let issues = tracker.get_time_property_issues();
+        let eth0: Vec<&TimePropertiesIssue> = issues
+            .iter()
+            .filter(|(segment, _)| segment.interface_name == "eth0")
+            .map(|(_, issue)| issue)
+            .collect();
+        assert_eq!(
+            eth0,
+            vec![
+                &TimePropertiesIssue::Disagreement {
+                    field: DatasetField::UtcOffset,
+                    values: vec![
+                        (port_id(1, 1), "+37s".to_string()),
+                        (port_id(2, 1), "+36s".to_string())
+                    ],
+                },
+                &TimePropertiesIssue::Disagreement {
+                    field: DatasetField::Leap,
+                    values: vec![
+                        (port_id(1, 1), "+1s".to_string()),
+                        (port_id(2, 1), "none".to_string())
+                    ],
+                },
+                &TimePropertiesIssue::UnexpectedUtcOffset {
+                    port_identity: port_id(2, 1),
+                    announced: 36,
+                    expected: 37,
+                },
+            ]
+        );
+        assert!(issues.iter().any(|(segment, issue)| {
+            segment.interface_name == "eth1"
+                && *issue
+                    == TimePropertiesIssue::UnexpectedTimescale {
+                        port_identity: port_id(3, 1),
+                        ptp_timescale: false,
+                    }
+        }));
+
+        // Expecting the arbitrary timescale flags the PTP grandmasters instead
+        tracker.set_time_expectations(37, ExpectedTimescale::Arbitrary);
+        let issues = tracker.get_time_property_issues();
+        assert!(
+            issues
+                .iter()
+                .all(|(segment, _)| segment.interface_name == "eth0")
+        );
+        assert!(issues.iter().any(|(_, issue)| matches!(
+            issue,
+            TimePropertiesIssue::UnexpectedTimescale { port_identity, .. }
+                if *port_identity == port_id(1, 1)
+        )));
+
+        let leap_seconds = tracker.get_upcoming_leap_seconds();
+        assert_eq!(leap_seconds.len(), 1);
+        assert_eq!(leap_seconds[0].port_identity, port_id(1, 1));
+        assert_eq!(leap_seconds[0].leap, LeapSecond::Insert);
+        assert_eq!(
+            leap_seconds[0].at,
+            next_utc_midnight(tracker.current_time())
         );
-        assert!(host.has_multiple_interfaces());
-        // Primary interface should still be the first one
-        let primary = host.get_primary_interface();
-        assert!(primary.is_some());
 
-        // Add interface directly (gPTP) - should still detect multiple
-        host.add_interface("wlan0".to_string());
-        assert!(host.has_multiple_interfaces());
-        assert_eq!(host.get_interface_count(), 3);
+        let day = Duration::from_secs(86_400);
+        assert_eq!(
+            next_utc_midnight(UNIX_EPOCH + day * 3 + Duration::from_secs(5)),
+            UNIX_EPOCH + day * 4
+        );
+        assert_eq!(next_utc_midnight(UNIX_EPOCH + day), UNIX_EPOCH + day * 2);
+    }
 
-        // Test with only direct interfaces (gPTP scenario)
-        let mut host2 = PtpHost::new(ClockIdentity::default());
-        host2.add_interface("wlan0".to_string());
-        assert_eq!(host2.get_primary_interface(), Some(&"wlan0".to_string()));
-        assert!(!host2.has_multiple_interfaces());
+    #[tokio::test]
+    async fn test_sync_senders_use_capture_time() {
+        // Replayed at once, but clock 1 sent its last Sync two minutes before clock 2
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            at(on(sync_from(1), "eth0"), 0),
+            at(on(sync_from(2), "eth1"), 120),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let senders: Vec<(PortIdentity, SystemTime)> = tracker
+            .recent_sync_senders
+            .values()
+            .flatten()
+            .copied()
+            .collect();
+        assert_eq!(
+            senders,
+            vec![(port_id(2, 1), at(sync_from(2), 120).timestamp)]
+        );
+    }
 
-        host2.add_interface("eth0".to_string());
-        assert!(host2.has_multiple_interfaces());
+    #[tokio::test]
+    async fn test_prp_duplicates_expire_in_capture_time() {
+        let start = SystemTime::now();
+        let at_millis = |mut raw: crate::source::RawPacket, millis: u64| {
+            raw.timestamp = start + Duration::from_millis(millis);
+            raw
+        };
+        // Replayed at once, but the LAN B frame with the same sequence arrives after the
+        // EntryForgetTime in capture time, e.g. after the sequence number wrapped
+        let receiver = crate::source::RawSocketReceiver::from_packets(vec![
+            at_millis(redundant_sync_packet(RedundancyLan::A, 1), 0),
+            at_millis(redundant_sync_packet(RedundancyLan::B, 1), 1000),
+            at_millis(redundant_sync_packet(RedundancyLan::A, 2), 1100),
+            at_millis(redundant_sync_packet(RedundancyLan::B, 2), 1200),
+        ]);
+        let mut tracker = PtpTracker::new(receiver).unwrap();
+        tracker.scan_network().await;
+
+        let host = tracker.get_hosts()[0];
+        assert_eq!(host.sync_count, 3);
+        assert_eq!(host.redundancy.as_ref().unwrap().duplicates_discarded(), 1);
     }
 }
 
@@ -735,10 +4344,26 @@ pub struct PtpTracker {
     hosts: HashMap<ClockIdentity, PtpHost>,
     last_packet: Instant,
     pub raw_socket_receiver: crate::source::RawSocketReceiver,
-    // Track recent sync/follow-up senders per domain for transmitter-receiver correlation
-    recent_sync_senders: HashMap<u8, Vec<(ClockIdentity, Instant)>>,
+    // Track recent sync/follow-up senders per segment for transmitter-receiver correlation,
+    // with the capture time of their last Sync
+    recent_sync_senders: HashMap<PtpSegment, Vec<(PortIdentity, SystemTime)>>,
     // Track interfaces for determining inbound interface of packets
     interfaces: Vec<(String, Option<std::net::Ipv4Addr>, Option<u16>)>,
+    // First copies of PRP/HSR frames by (source MAC, sequence), for duplicate discard
+    recent_redundant_frames: HashMap<([u8; 6], u16), (RedundancyLan, SystemTime)>,
+    filter: PacketFilter,
+    filtered_packet_count: u64,
+    /// Latest capture timestamp, the clock for timeouts in pcap mode
+    capture_time: Option<SystemTime>,
+    /// announceReceiptTimeout in announce intervals
+    announce_receipt_timeout: u8,
+    /// Distinct datasets announced for each grandmaster, with the capture time they appeared
+    gm_datasets: HashMap<ClockIdentity, BoundedVec<(SystemTime, AnnouncedDataset)>>,
+    /// BMCA winner port of each segment and the time it won
+    segment_grandmasters: HashMap<PtpSegment, (PortIdentity, SystemTime)>,
+    /// UTC offset valid transmitters on the PTP timescale should announce
+    expected_utc_offset: i16,
+    expected_timescale: ExpectedTimescale,
 }
 
 impl PtpTracker {
@@ -750,13 +4375,27 @@ impl PtpTracker {
             raw_socket_receiver,
             recent_sync_senders: HashMap::new(),
             interfaces,
+            recent_redundant_frames: HashMap::new(),
+            filter: PacketFilter::default(),
+            filtered_packet_count: 0,
+            capture_time: None,
+            announce_receipt_timeout: DEFAULT_ANNOUNCE_RECEIPT_TIMEOUT,
+            gm_datasets: HashMap::new(),
+            segment_grandmasters: HashMap::new(),
+            expected_utc_offset: DEFAULT_EXPECTED_UTC_OFFSET,
+            expected_timescale: ExpectedTimescale::default(),
         })
     }
 
     pub async fn scan_network(&mut self) -> Vec<Arc<ParsedPacket>> {
         let packets = self.process_ptp_messages().await;
         self.cleanup_old_sync_senders();
+        self.cleanup_old_redundant_frames();
+        self.expire_announce_timeouts();
+        self.infer_link_receivers();
         self.run_bmca_election();
+        self.infer_port_states();
+        self.detect_identity_conflicts();
         packets
     }
 
@@ -768,6 +4407,12 @@ impl PtpTracker {
             // Limit iterations to prevent blocking too long
             match self.raw_socket_receiver.try_recv() {
                 Some(raw_packet) => {
+                    if !self.passes_filter(&raw_packet) {
+                        self.filtered_packet_count += 1;
+                        self.last_packet = Instant::now();
+                        continue;
+                    }
+
                     let raw_packet_arc = std::sync::Arc::new(raw_packet);
                     if let Some(packet) = self.handle_raw_packet(raw_packet_arc).await {
                         processed_packets.push(packet);
@@ -793,16 +4438,35 @@ impl PtpTracker {
             Err(_) => return None, // Invalid message
         };
 
+        self.capture_time = self.capture_time.max(Some(raw_packet.timestamp));
+
+        // Discard the second copy of PRP/HSR frames, only counting it towards LAN statistics
+        if let Some(tag) = &raw_packet.redundancy {
+            let duplicate = self.is_redundant_duplicate(&raw_packet, tag);
+            let clock_identity = msg.header().source_port_identity.clock_identity;
+            if duplicate {
+                if let Some(host) = self.hosts.get_mut(&clock_identity) {
+                    host.record_redundancy(tag, true);
+                }
+                return None;
+            }
+            self.hosts
+                .entry(clock_identity)
+                .or_insert_with(|| PtpHost::new(clock_identity))
+                .record_redundancy(tag, false);
+        }
+
         // Create packet info for recording
         let packet = Arc::new(ParsedPacket {
             ptp: msg,
             raw: raw_packet.clone(),
         });
 
+        let source_port = msg.header().source_port_identity;
         let sending_host = self
             .hosts
-            .entry(msg.header().source_port_identity.clock_identity)
-            .or_insert_with(|| PtpHost::new(msg.header().source_port_identity.clock_identity));
+            .entry(source_port.clock_identity)
+            .or_insert_with(|| PtpHost::new(source_port.clock_identity));
 
         // Add IP address or interface depending on packet type
         if let Some(source_addr) = raw_packet.source_addr {
@@ -817,130 +4481,206 @@ impl PtpTracker {
             sending_host.add_interface(packet.raw.interface_name.clone());
         }
 
-        sending_host.total_messages_sent_count += 1;
+        if let Some(tunnel) = &raw_packet.tunnel {
+            sending_host.tunnels.insert(tunnel.clone());
+        }
+
         sending_host.update_from_ptp_header(msg.header());
-        // Update last_seen with packet timestamp
-        sending_host.last_seen = raw_packet.timestamp;
+        let sending_port = sending_host.record_packet(source_port, &packet, true);
+
+        // Responses also change the state of the port that sent the request
+        let mut requesting_port = None;
 
         match msg {
             PtpMessage::Announce(msg) => {
-                sending_host.announce_count += 1;
-                sending_host.state.update_from_announce(&msg);
-                sending_host.add_packet(packet.clone());
+                sending_port
+                    .state
+                    .update_from_announce(&msg, raw_packet.timestamp);
+
+                let dataset = AnnouncedDataset::from_announce(&msg);
+                let history = self
+                    .gm_datasets
+                    .entry(dataset.gm_identity)
+                    .or_insert_with(|| BoundedVec::new(GM_DATASET_HISTORY));
+                if history
+                    .items
+                    .back()
+                    .is_none_or(|(_, last)| *last != dataset)
+                {
+                    history.push((raw_packet.timestamp, dataset));
+                }
+                sending_port.gm_offset.timescale = Some(if msg.header.flags.ptp_timescale() {
+                    PtpTimescale::Ptp {
+                        utc_offset: msg.current_utc_offset.offset,
+                    }
+                } else {
+                    PtpTimescale::Arbitrary
+                });
             }
             PtpMessage::Sync(msg) => {
-                sending_host.sync_count += 1;
-                sending_host.state.update_from_sync(&msg);
+                sending_port
+                    .state
+                    .update_from_sync(&msg, raw_packet.timestamp);
+                sending_port.sync_interface_name = Some(raw_packet.interface_name.clone());
+                let two_step = msg.header.flags.two_step();
+                sending_port.sync_follow_up.record_sync(
+                    msg.header.sequence_id,
+                    two_step,
+                    msg.header.correction_field,
+                    raw_packet.timestamp,
+                );
+                if !two_step {
+                    sending_port.gm_offset.record(
+                        raw_packet.timestamp,
+                        msg.origin_timestamp,
+                        msg.header.correction_field,
+                    );
+                }
 
-                // Record this as a recent sync sender for this domain
+                // Record this as a recent sync sender for this segment
                 let domain_senders = self
                     .recent_sync_senders
-                    .entry(msg.header.domain_number)
+                    .entry(PtpSegment::from_packet(
+                        &raw_packet,
+                        msg.header.domain_number,
+                    ))
                     .or_default();
 
-                let now = std::time::Instant::now();
-                if let Some(existing) = domain_senders
-                    .iter_mut()
-                    .find(|(id, _)| id == &msg.header.source_port_identity.clock_identity)
+                if let Some(existing) = domain_senders.iter_mut().find(|(id, _)| id == &source_port)
                 {
-                    existing.1 = now;
+                    existing.1 = raw_packet.timestamp;
                 } else {
-                    domain_senders.push((msg.header.source_port_identity.clock_identity, now));
+                    domain_senders.push((source_port, raw_packet.timestamp));
                 }
-                sending_host.add_packet(packet.clone());
             }
             PtpMessage::DelayReq(msg) => {
-                sending_host.delay_req_count += 1;
+                sending_port.path_delays.record_request(
+                    DelayMechanism::E2e,
+                    msg.header.sequence_id,
+                    raw_packet.timestamp,
+                );
 
-                let now = std::time::Instant::now();
-                if let Some(domain_senders) =
-                    self.recent_sync_senders.get(&msg.header.domain_number)
-                {
+                if let Some(domain_senders) = self.recent_sync_senders.get(
+                    &PtpSegment::from_packet(&raw_packet, msg.header.domain_number),
+                ) {
                     // Find the most recent sync sender and determine the age of the last sync
-                    if let Some((clock_identity, sync_time)) = domain_senders
+                    if let Some((port_identity, sync_time)) = domain_senders
                         .iter()
                         .max_by_key(|(_, timestamp)| *timestamp)
                     {
-                        let age = now.duration_since(*sync_time);
+                        let age = raw_packet
+                            .timestamp
+                            .duration_since(*sync_time)
+                            .unwrap_or_default();
 
-                        sending_host
+                        sending_port
                             .state
-                            .update_from_recent_sync_sender(*clock_identity, age);
+                            .update_from_recent_sync_sender(*port_identity, age);
                     }
                 }
-                sending_host.add_packet(packet.clone());
             }
             PtpMessage::DelayResp(msg) => {
-                sending_host.delay_resp_count += 1;
-                sending_host.add_packet(packet.clone());
-
-                // Handle receiving host separately to avoid borrow checker issues
-                let receiving_clock_id = msg.requesting_port_identity.clock_identity;
-                let receiving_host = self
-                    .hosts
-                    .entry(receiving_clock_id)
-                    .or_insert_with(|| PtpHost::new(receiving_clock_id));
-
-                receiving_host.delay_resp_count += 1;
-                receiving_host.total_messages_received_count += 1;
-                receiving_host.state.update_from_delay_resp(&msg);
-                receiving_host.last_seen = raw_packet.timestamp;
-                receiving_host.add_packet(packet.clone());
-            }
-            PtpMessage::PDelayReq(_) => {
-                sending_host.pdelay_req_count += 1;
+                // t4 relative to the capture clock needs the transmitter's offset against it
+                let receive_ns = sending_port
+                    .gm_offset
+                    .utc_nanos(msg.receive_timestamp)
+                    .map(|t4| t4 - msg.header.correction_field.nanoseconds() as i128);
+                let transmitter_offset_ns = sending_port.gm_offset.latest();
+
+                let requester = self.record_requesting_port(msg.requesting_port_identity, &packet);
+                requester.state.update_from_delay_resp(&msg);
+                if let (Some(receive_ns), Some(offset_ns)) = (receive_ns, transmitter_offset_ns) {
+                    requester.path_delays.record_delay_resp(
+                        source_port,
+                        msg.header.sequence_id,
+                        receive_ns,
+                        offset_ns,
+                    );
+                }
+                requesting_port = Some(msg.requesting_port_identity);
+            }
+            PtpMessage::PDelayReq(msg) => {
                 // PDelay requests are used for peer-to-peer delay measurement
                 // In P2P mode, each node measures delay with its neighbors directly
-                // Could extract timing information if needed for analysis
-                sending_host.pdelay_req_count += 1;
+                sending_port.path_delays.record_request(
+                    DelayMechanism::P2p,
+                    msg.header.sequence_id,
+                    raw_packet.timestamp,
+                );
             }
             PtpMessage::PDelayResp(msg) => {
                 // PDelay responses are sent in response to PDelay requests
                 // These contain receive and transmit timestamps for delay calculation
-                // Like PDelayReq, they don't indicate transmitter-receiver relationship
-
-                sending_host.pdelay_resp_count += 1;
-
-                let receiving_host = self
-                    .hosts
-                    .entry(msg.requesting_port_identity.clock_identity)
-                    .or_insert_with(|| PtpHost::new(msg.requesting_port_identity.clock_identity));
-
-                receiving_host.pdelay_resp_count += 1;
-                receiving_host.total_messages_received_count += 1;
-                receiving_host.state.update_from_pdelay_resp(&msg);
-                receiving_host.last_seen = raw_packet.timestamp;
-
-                receiving_host.add_packet(packet.clone());
+                // Like PDelayReq, they don't indicate transmitter-receiver relationship,
+                // but they tell which ports share a link
+                sending_port.record_link_peer(
+                    msg.requesting_port_identity,
+                    false,
+                    &raw_packet.interface_name,
+                    raw_packet.timestamp,
+                );
+                let requester = self.record_requesting_port(msg.requesting_port_identity, &packet);
+                requester.record_link_peer(
+                    source_port,
+                    true,
+                    &raw_packet.interface_name,
+                    raw_packet.timestamp,
+                );
+                requester.state.update_from_pdelay_resp(&msg);
+                requester
+                    .path_delays
+                    .record_pdelay_resp(&msg, raw_packet.timestamp);
+                requesting_port = Some(msg.requesting_port_identity);
             }
             PtpMessage::PDelayRespFollowup(msg) => {
-                sending_host.pdelay_resp_follow_up_count += 1;
                 // PDelay response follow-up messages provide precise transmit timestamps
                 // for peer delay measurements in two-step mode. This completes the
                 // peer delay measurement cycle: PDelayReq -> PDelayResp -> PDelayRespFollowUp
-                let receiving_host = self
-                    .hosts
-                    .entry(msg.requesting_port_identity.clock_identity)
-                    .or_insert_with(|| PtpHost::new(msg.requesting_port_identity.clock_identity));
-
-                receiving_host.pdelay_resp_follow_up_count += 1;
-                receiving_host.total_messages_received_count += 1;
-                receiving_host.state.update_from_pdelay_resp_follow_up(&msg);
-                receiving_host.last_seen = raw_packet.timestamp;
-                receiving_host.add_packet(packet.clone());
+                let requester = self.record_requesting_port(msg.requesting_port_identity, &packet);
+                requester.state.update_from_pdelay_resp_follow_up(&msg);
+                requester.path_delays.record_pdelay_resp_follow_up(&msg);
+                requesting_port = Some(msg.requesting_port_identity);
             }
             PtpMessage::FollowUp(msg) => {
-                sending_host.follow_up_count += 1;
-                sending_host.add_packet(packet.clone());
-                sending_host.state.update_from_follow_up(&msg);
+                sending_port.state.update_from_follow_up(&msg);
+                if let Some((sync_timestamp, sync_correction)) = sending_port
+                    .sync_follow_up
+                    .record_follow_up(msg.header.sequence_id, raw_packet.timestamp)
+                {
+                    // The corrections of both messages add up (IEEE 1588-2019 11.3.2)
+                    let correction = PtpCorrectionField::new(
+                        sync_correction
+                            .value
+                            .saturating_add(msg.header.correction_field.value),
+                    );
+                    sending_port.gm_offset.record(
+                        sync_timestamp,
+                        msg.precise_origin_timestamp,
+                        correction,
+                    );
+                }
             }
-            PtpMessage::Signaling(_) => {
-                sending_host.signaling_message_count += 1;
-                sending_host.add_packet(packet.clone());
+            PtpMessage::Management(msg) => {
+                if msg.action_field == MANAGEMENT_ACTION_RESPONSE
+                    && let Some((port_identity, state)) = msg.port_data_set
+                    && let Some(state) = PortState::from_port_data_set(state)
+                {
+                    let max_history = sending_host.packet_history.max_size;
+                    self.hosts
+                        .entry(port_identity.clock_identity)
+                        .or_insert_with(|| PtpHost::new(port_identity.clock_identity))
+                        .ports
+                        .entry(port_identity)
+                        .or_insert_with(|| PtpPort::new(port_identity, max_history))
+                        .management_state = Some((state, raw_packet.timestamp));
+                }
             }
-            PtpMessage::Management(_) => {
-                sending_host.management_message_count += 1;
-                sending_host.add_packet(packet.clone());
+            PtpMessage::Signaling(_) => {}
+        }
+
+        for port_identity in std::iter::once(source_port).chain(requesting_port) {
+            if let Some(host) = self.hosts.get_mut(&port_identity.clock_identity) {
+                host.refresh_from_ports();
             }
         }
 
@@ -948,15 +4688,279 @@ impl PtpTracker {
         Some(packet)
     }
 
+    /// Get the port a response was addressed to, counting the response as received
+    fn record_requesting_port(
+        &mut self,
+        port_identity: PortIdentity,
+        packet: &Arc<ParsedPacket>,
+    ) -> &mut PtpPort {
+        self.hosts
+            .entry(port_identity.clock_identity)
+            .or_insert_with(|| PtpHost::new(port_identity.clock_identity))
+            .record_packet(port_identity, packet, false)
+    }
+
+    /// Packets that can't be parsed are passed on and dropped by `handle_raw_packet`
+    fn passes_filter(&self, raw_packet: &crate::source::RawPacket) -> bool {
+        if self.filter.is_empty() {
+            return true;
+        }
+
+        match PtpHeader::try_from(raw_packet.ptp_payload.as_slice()) {
+            Ok(header) => self.filter.matches(raw_packet, &header),
+            Err(_) => true,
+        }
+    }
+
+    pub fn get_filter(&self) -> &PacketFilter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: PacketFilter) {
+        self.filter = filter;
+    }
+
+    pub fn get_filtered_packet_count(&self) -> u64 {
+        self.filtered_packet_count
+    }
+
+    /// Check whether the copy of this frame from the other LAN was already processed
+    fn is_redundant_duplicate(
+        &mut self,
+        raw_packet: &crate::source::RawPacket,
+        tag: &RedundancyTag,
+    ) -> bool {
+        let key = (raw_packet.source_mac.unwrap_or_default(), tag.sequence);
+        let now = self.current_time();
+
+        if let Some((lan, seen)) = self.recent_redundant_frames.get(&key)
+            && *lan != tag.lan
+            && now.duration_since(*seen).unwrap_or_default() < REDUNDANCY_ENTRY_FORGET_TIME
+        {
+            self.recent_redundant_frames.remove(&key);
+            return true;
+        }
+
+        self.recent_redundant_frames
+            .insert(key, (tag.lan, raw_packet.timestamp));
+        false
+    }
+
+    fn cleanup_old_redundant_frames(&mut self) {
+        let now = self.current_time();
+        self.recent_redundant_frames.retain(|_, (_, seen)| {
+            now.duration_since(*seen).unwrap_or_default() < REDUNDANCY_ENTRY_FORGET_TIME
+        });
+    }
+
+    /// Current time for timeouts: the latest packet time in pcap mode, wall time otherwise
+    pub fn current_time(&self) -> SystemTime {
+        match self.raw_socket_receiver.capture_mode() {
+            CaptureMode::Pcap => self.capture_time.unwrap_or_else(SystemTime::now),
+            _ => SystemTime::now(),
+        }
+    }
+
+    pub fn set_announce_receipt_timeout(&mut self, receipt_timeout: u8) {
+        self.announce_receipt_timeout = receipt_timeout;
+    }
+
+    pub fn set_time_expectations(&mut self, utc_offset: i16, timescale: ExpectedTimescale) {
+        self.expected_utc_offset = utc_offset;
+        self.expected_timescale = timescale;
+    }
+
+    /// Infer the 1588 state of every port, after the BMCA has picked the winners
+    fn infer_port_states(&mut self) {
+        let now = self.current_time();
+        for host in self.hosts.values_mut() {
+            for port in host.ports.values_mut() {
+                port.inferred_state = port.infer_state(now);
+            }
+            host.refresh_from_ports();
+        }
+    }
+
+    /// Look for clock identities used by several devices: a port sending from several MACs
+    /// or on separate networks, or an EUI-64 identity that doesn't match the sender's MAC.
+    /// Multi-port clocks legitimately use a MAC per port, so MACs are compared per port, and
+    /// port MACs usually count up from the one the identity was derived from.
+    fn detect_identity_conflicts(&mut self) {
+        let now = self.current_time();
+        let recent = |time: &SystemTime| {
+            now.duration_since(*time).unwrap_or_default() <= IDENTITY_CONFLICT_WINDOW
+        };
+
+        // Interfaces connected to the same network see the same ports, count the ports
+        // seen on each pair of interfaces
+        let mut shared_interfaces: HashMap<(String, String), usize> = HashMap::new();
+        let mut port_interfaces: HashMap<PortIdentity, Vec<String>> = HashMap::new();
+        for port in self.hosts.values().flat_map(|host| host.ports.values()) {
+            let interfaces: Vec<String> = port
+                .capture_interfaces
+                .iter()
+                .filter(|(_, time)| recent(time))
+                .map(|(interface, _)| interface.clone())
+                .collect();
+            for (i, a) in interfaces.iter().enumerate() {
+                for b in &interfaces[i + 1..] {
+                    *shared_interfaces.entry((a.clone(), b.clone())).or_default() += 1;
+                }
+            }
+            port_interfaces.insert(port.port_identity, interfaces);
+        }
+
+        for host in self.hosts.values_mut() {
+            let mut conflicts = Vec::new();
+            for port in host.ports.values() {
+                let macs: Vec<MacAddress> = port
+                    .source_macs
+                    .iter()
+                    .filter(|(_, time)| recent(time))
+                    .map(|(mac, _)| *mac)
+                    .collect();
+                if macs.len() > 1 {
+                    conflicts.push(ClockIdentityConflict::DuplicateMac {
+                        port_identity: port.port_identity,
+                        macs,
+                    });
+                }
+
+                // Separate unless another port was seen on both as well. PRP and HSR send
+                // every frame on both LANs.
+                let interfaces = &port_interfaces[&port.port_identity];
+                let separate = interfaces.iter().enumerate().any(|(i, a)| {
+                    interfaces[i + 1..]
+                        .iter()
+                        .any(|b| shared_interfaces[&(a.clone(), b.clone())] < 2)
+                });
+                if separate && host.redundancy.is_none() {
+                    conflicts.push(ClockIdentityConflict::DuplicateInterface {
+                        port_identity: port.port_identity,
+                        interfaces: interfaces.clone(),
+                    });
+                }
+            }
+
+            if let Some(derived) = host.clock_identity.derived_mac_address() {
+                let seen: BTreeSet<MacAddress> = host
+                    .ports
+                    .values()
+                    .flat_map(|port| port.source_macs.iter())
+                    .filter(|(_, time)| recent(time))
+                    .map(|(mac, _)| *mac)
+                    .collect();
+                let matches = |mac: &MacAddress| mac.0[..5] == derived.0[..5];
+                if !seen.is_empty() && !seen.iter().any(matches) {
+                    conflicts.push(ClockIdentityConflict::MacMismatch {
+                        derived,
+                        seen: seen.into_iter().collect(),
+                    });
+                }
+            }
+            host.identity_conflicts = conflicts;
+        }
+    }
+
+    /// Move transmitters without an Announce within the announceReceiptTimeout to Stale
+    fn expire_announce_timeouts(&mut self) {
+        let now = self.current_time();
+        for host in self.hosts.values_mut() {
+            for port in host.ports.values_mut() {
+                port.state
+                    .expire_announce(now, self.announce_receipt_timeout);
+            }
+            host.refresh_from_ports();
+        }
+    }
+
+    /// Infer the receivers of peer-to-peer domains, which send no Delay_Req. A port that
+    /// isn't transmitting and shares a link with a Sync sender is synchronized by it:
+    /// in P2P (and 802.1AS) networks Sync only travels one link, from the transmitter
+    /// port to the receiver port. The confidence grows with the evidence for the link:
+    /// - 0.6 for Pdelay peering in one direction
+    /// - +0.2 when both ports measured the peer delay to each other
+    /// - +0.1 when the Sync was captured on the interface of the Pdelay exchange
+    /// - -0.3 when several peers send Sync, e.g. behind a switch without PTP support
+    fn infer_link_receivers(&mut self) {
+        let now = self.current_time();
+
+        // Transmitter ports currently sending Sync, with the interface it is captured on
+        let sync_senders: HashMap<PortIdentity, (Option<String>, bool)> = self
+            .hosts
+            .values()
+            .flat_map(|h| h.ports.values())
+            .filter(|port| port.is_sending_sync(now))
+            .map(|port| {
+                let preferred = matches!(&port.state,
+                    PtpHostState::TimeTransmitter(s) if s.is_bmca_winner || s.is_relay);
+                (
+                    port.port_identity,
+                    (port.sync_interface_name.clone(), preferred),
+                )
+            })
+            .collect();
+
+        let mut selections = Vec::new();
+        for port in self.hosts.values_mut().flat_map(|h| h.ports.values_mut()) {
+            port.link_peers.retain(|_, peer| {
+                now.duration_since(peer.last_seen).unwrap_or_default() < LINK_PEER_MAX_AGE
+            });
+            if !matches!(
+                port.state,
+                PtpHostState::Listening | PtpHostState::TimeReceiver(_)
+            ) {
+                continue;
+            }
+
+            let transmitting_peers: Vec<_> = port
+                .link_peers
+                .iter()
+                .filter_map(|(id, peer)| sync_senders.get(id).map(|sender| (id, peer, sender)))
+                .collect();
+            // Prefer the peer that relays the grandmaster, otherwise the lowest identity
+            let Some((transmitter, peer, (sync_interface, _))) = transmitting_peers
+                .iter()
+                .rev()
+                .max_by_key(|(_, _, (_, preferred))| *preferred)
+            else {
+                continue;
+            };
+
+            let mut confidence = 0.6;
+            if peer.requested && peer.responded {
+                confidence += 0.2;
+            }
+            if sync_interface.as_deref() == Some(peer.interface_name.as_str()) {
+                confidence += 0.1;
+            }
+            if transmitting_peers.len() > 1 {
+                confidence -= 0.3;
+            }
+            selections.push((port.port_identity, **transmitter, confidence));
+        }
+
+        for (port_identity, transmitter, confidence) in selections {
+            if let Some(port) = self.get_port_mut(&port_identity) {
+                port.state.update_from_link_peer(transmitter, confidence);
+            }
+            if let Some(host) = self.hosts.get_mut(&port_identity.clock_identity) {
+                host.refresh_from_ports();
+            }
+        }
+    }
+
     fn cleanup_old_sync_senders(&mut self) {
-        let now = std::time::Instant::now();
+        let now = self.current_time();
         let timeout = Duration::from_secs(60); // Keep sync senders for 60 seconds
 
         for (_, senders) in self.recent_sync_senders.iter_mut() {
-            senders.retain(|(_, timestamp)| now.duration_since(*timestamp) < timeout);
+            senders.retain(|(_, timestamp)| {
+                now.duration_since(*timestamp).unwrap_or_default() < timeout
+            });
         }
 
-        // Remove domains with no recent senders
+        // Remove segments with no recent senders
         self.recent_sync_senders
             .retain(|_, senders| !senders.is_empty());
     }
@@ -983,6 +4987,34 @@ impl PtpTracker {
 
     pub fn clear_hosts(&mut self) {
         self.hosts.clear();
+        self.gm_datasets.clear();
+        self.segment_grandmasters.clear();
+    }
+
+    /// The distinct datasets announced for a grandmaster, oldest first
+    pub fn get_gm_dataset_history(
+        &self,
+        gm_identity: &ClockIdentity,
+    ) -> Vec<(SystemTime, AnnouncedDataset)> {
+        self.gm_datasets
+            .get(gm_identity)
+            .map(|history| history.items.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove hosts not seen for longer than `expiry`, returning their clock identities
+    pub fn remove_expired_hosts(&mut self, expiry: Duration) -> Vec<ClockIdentity> {
+        let now = self.current_time();
+        let expired: Vec<ClockIdentity> = self
+            .hosts
+            .values()
+            .filter(|host| host.time_since_last_seen(Some(now)) > expiry)
+            .map(|host| host.clock_identity)
+            .collect();
+        for clock_identity in &expired {
+            self.hosts.remove(clock_identity);
+        }
+        expired
     }
 
     pub fn get_transmitter_count(&self) -> usize {
@@ -993,6 +5025,241 @@ impl PtpTracker {
         self.hosts.values().filter(|h| h.is_receiver()).count()
     }
 
+    /// Host and message counts of every segment, with hosts counted by the role of their
+    /// ports in that segment
+    pub fn get_segment_statistics(&self) -> Vec<SegmentStatistics> {
+        let mut statistics: BTreeMap<PtpSegment, SegmentStatistics> = BTreeMap::new();
+        for host in self.hosts.values() {
+            for segment in host.segments() {
+                let view = host.segment_view(&segment);
+                let stats =
+                    statistics
+                        .entry(segment.clone())
+                        .or_insert_with(|| SegmentStatistics {
+                            segment,
+                            host_count: 0,
+                            transmitter_count: 0,
+                            receiver_count: 0,
+                            listening_count: 0,
+                            grandmaster: None,
+                            message_count: 0,
+                        });
+                stats.host_count += 1;
+                stats.message_count += view.total_messages_sent_count as u64;
+                match &view.state {
+                    PtpHostState::TimeTransmitter(s) => {
+                        stats.transmitter_count += 1;
+                        if s.is_bmca_winner {
+                            stats.grandmaster = Some(host.clock_identity);
+                        }
+                    }
+                    PtpHostState::TimeReceiver(_) => stats.receiver_count += 1,
+                    PtpHostState::Listening | PtpHostState::Stale(_) => stats.listening_count += 1,
+                }
+            }
+        }
+        statistics.into_values().collect()
+    }
+
+    /// Segments in which unrelated clocks send Sync at the same time, e.g. a grandmaster
+    /// with the BMCA disabled next to the elected one. Sources distributing the same
+    /// grandmaster's time, like a boundary clock and its upstream, don't compete.
+    pub fn get_competing_grandmasters(&self) -> Vec<CompetingGrandmasters> {
+        let now = self.current_time();
+        let mut sources: BTreeMap<PtpSegment, Vec<(&PtpHost, &PtpPort)>> = BTreeMap::new();
+        for host in self.hosts.values() {
+            for port in host.ports.values() {
+                if let Some(segment) = &port.segment
+                    && port.is_sending_sync(now)
+                {
+                    sources
+                        .entry(segment.clone())
+                        .or_default()
+                        .push((host, port));
+                }
+            }
+        }
+
+        sources
+            .into_iter()
+            .filter_map(|(segment, sources)| {
+                let clock_ids: HashSet<ClockIdentity> = sources
+                    .iter()
+                    .map(|(host, _)| host.clock_identity)
+                    .collect();
+                // The clock whose time a source distributes
+                let time_source = |host: &PtpHost, port: &PtpPort| {
+                    let announced = match &port.state {
+                        PtpHostState::TimeTransmitter(s) => s.gm_identifier,
+                        _ => None,
+                    };
+                    announced
+                        .or_else(|| {
+                            host.get_selected_transmitter()
+                                .map(|id| id.clock_identity)
+                                .filter(|clock_id| clock_ids.contains(clock_id))
+                        })
+                        .unwrap_or(host.clock_identity)
+                };
+                let time_sources: HashSet<ClockIdentity> = sources
+                    .iter()
+                    .map(|(host, port)| time_source(host, port))
+                    .collect();
+                (time_sources.len() > 1).then(|| CompetingGrandmasters {
+                    sources: sources
+                        .iter()
+                        .map(|(_, port)| SyncSource {
+                            port_identity: port.port_identity,
+                            receivers: self.get_delay_resp_receivers(port.port_identity),
+                        })
+                        .collect(),
+                    segment,
+                })
+            })
+            .collect()
+    }
+
+    /// Announcing transmitter ports with their datasets, by segment
+    fn get_announced_datasets(&self) -> BTreeMap<PtpSegment, Vec<(&PtpPort, &AnnouncedDataset)>> {
+        let mut datasets: BTreeMap<PtpSegment, Vec<(&PtpPort, &AnnouncedDataset)>> =
+            BTreeMap::new();
+        for port in self.hosts.values().flat_map(|host| host.ports.values()) {
+            if let (Some(segment), PtpHostState::TimeTransmitter(s)) = (&port.segment, &port.state)
+                && let Some(dataset) = &s.dataset
+            {
+                datasets
+                    .entry(segment.clone())
+                    .or_default()
+                    .push((port, dataset));
+            }
+        }
+        for ports in datasets.values_mut() {
+            ports.sort_by_key(|(port, _)| port.port_identity);
+        }
+        datasets
+    }
+
+    /// Time properties that transmitters of a segment disagree on, or that don't match the
+    /// expected UTC offset and timescale
+    pub fn get_time_property_issues(&self) -> Vec<(PtpSegment, TimePropertiesIssue)> {
+        let mut issues = Vec::new();
+        for (segment, ports) in self.get_announced_datasets() {
+            for field in AnnouncedDataset::TIME_PROPERTIES {
+                let values: Vec<(PortIdentity, String)> = ports
+                    .iter()
+                    .filter_map(|(port, dataset)| {
+                        Some((port.port_identity, dataset.time_property(field)?))
+                    })
+                    .collect();
+                if values.iter().any(|(_, v)| *v != values[0].1) {
+                    issues.push((
+                        segment.clone(),
+                        TimePropertiesIssue::Disagreement { field, values },
+                    ));
+                }
+            }
+
+            for (port, dataset) in &ports {
+                if !self.expected_timescale.matches(dataset.ptp_timescale) {
+                    issues.push((
+                        segment.clone(),
+                        TimePropertiesIssue::UnexpectedTimescale {
+                            port_identity: port.port_identity,
+                            ptp_timescale: dataset.ptp_timescale,
+                        },
+                    ));
+                }
+                // The offset only means something on the PTP timescale and when marked valid
+                if dataset.ptp_timescale
+                    && dataset.current_utc_offset_valid
+                    && dataset.current_utc_offset.offset != self.expected_utc_offset
+                {
+                    issues.push((
+                        segment.clone(),
+                        TimePropertiesIssue::UnexpectedUtcOffset {
+                            port_identity: port.port_identity,
+                            announced: dataset.current_utc_offset.offset,
+                            expected: self.expected_utc_offset,
+                        },
+                    ));
+                }
+            }
+        }
+        issues
+    }
+
+    /// Leap seconds announced by grandmaster ports, soonest first. Boundary clocks relaying
+    /// the announcement are left out.
+    pub fn get_upcoming_leap_seconds(&self) -> Vec<UpcomingLeapSecond> {
+        let at = next_utc_midnight(self.current_time());
+        let mut leap_seconds: Vec<UpcomingLeapSecond> = self
+            .get_announced_datasets()
+            .into_iter()
+            .flat_map(|(segment, ports)| {
+                ports
+                    .into_iter()
+                    .filter(|(port, _)| {
+                        !matches!(&port.state, PtpHostState::TimeTransmitter(s) if s.is_relay)
+                    })
+                    .filter_map(move |(port, dataset)| {
+                        dataset.leap.map(|leap| UpcomingLeapSecond {
+                            port_identity: port.port_identity,
+                            segment: segment.clone(),
+                            leap,
+                            current_utc_offset: dataset.current_utc_offset.offset,
+                            at,
+                        })
+                    })
+            })
+            .collect();
+        leap_seconds.sort_by_key(|leap_second| (leap_second.at, leap_second.port_identity));
+        leap_seconds
+    }
+
+    /// Receiver ports that got a Delay_Resp from the given transmitter port
+    fn get_delay_resp_receivers(&self, transmitter: PortIdentity) -> Vec<PortIdentity> {
+        let mut receivers: Vec<PortIdentity> = self
+            .hosts
+            .values()
+            .flat_map(|host| host.ports.values())
+            .filter(|port| match &port.state {
+                PtpHostState::TimeReceiver(s) => {
+                    s.selected_transmitter_identity == Some(transmitter)
+                        && s.last_delay_response_origin_timestamp.is_some()
+                }
+                _ => false,
+            })
+            .map(|port| port.port_identity)
+            .collect();
+        receivers.sort();
+        receivers
+    }
+
+    /// The transmitter ports of a segment, best first by the BMCA dataset comparison
+    pub fn get_ranked_transmitters(&self, segment: &PtpSegment) -> Vec<&PtpPort> {
+        let mut transmitters: Vec<(&PtpPort, &PtpHostStateTimeTransmitter)> = self
+            .hosts
+            .values()
+            .flat_map(|h| h.ports.values())
+            .filter(|port| port.segment.as_ref() == Some(segment))
+            .filter_map(|port| match &port.state {
+                PtpHostState::TimeTransmitter(state) => Some((port, state)),
+                _ => None,
+            })
+            .collect();
+        transmitters.sort_by(|(a, a_state), (b, b_state)| {
+            a_state.compare_for_bmca(b_state, a.port_identity, b.port_identity)
+        });
+        transmitters.into_iter().map(|(port, _)| port).collect()
+    }
+
+    /// When the current BMCA winner of a segment won, in capture time in pcap mode
+    pub fn get_gm_change_time(&self, segment: &PtpSegment) -> Option<SystemTime> {
+        self.segment_grandmasters
+            .get(segment)
+            .map(|(_, time)| *time)
+    }
+
     pub fn get_last_packet_age(&self) -> Duration {
         Instant::now().duration_since(self.last_packet)
     }
@@ -1031,110 +5298,178 @@ impl PtpTracker {
             .collect()
     }
 
-    /// Run the Best Master Clock Algorithm (BMCA) election to determine the best transmitter in each domain
+    /// Run the Best Master Clock Algorithm (BMCA) election to determine the grandmaster of each
+    /// segment (interface, VLAN, domain and transport, see `PtpSegment`)
     ///
-    /// This implements IEEE 1588 BMCA which compares transmitters using the following criteria (in order):
-    /// 1. Priority1 (lower is better)
-    /// 2. Clock Class (lower is better)
-    /// 3. Clock Accuracy (lower is better)
-    /// 4. Offset Scaled Log Variance (lower is better)
-    /// 5. Priority2 (lower is better)
-    /// 6. Clock Identity (lower is better, used as tiebreaker)
+    /// This implements the IEEE 1588 dataset comparison (see `BmcaDataset::compare`): part 1
+    /// compares the announced grandmasters by priority1, clock class, accuracy, variance,
+    /// priority2 and grandmaster identity; part 2 picks the shortest path (stepsRemoved,
+    /// then sender identity) when several ports announce the same grandmaster.
     ///
     /// The algorithm:
-    /// - Groups all transmitters by domain number
-    /// - For each domain with multiple transmitters, runs pairwise comparisons
-    /// - Marks the best transmitter as the BMCA winner (shown as "PT" in UI)
-    /// - Updates all receivers in the domain to select the BMCA winner
+    /// - Groups all transmitter ports by segment
+    /// - Keeps the ports qualified as foreign masters (see `is_qualified`), or all of them while
+    ///   none in the segment has qualified yet, e.g. right after the capture started
+    /// - Finds the best Announce in each segment by pairwise comparisons
+    /// - Marks the ports of the announced grandmaster (gm_identity) as the BMCA winner (shown as
+    ///   "GM" in the UI); boundary clock ports relaying it are not grandmaster candidates
+    /// - Lets receivers without a Delay_Resp from their transmitter select the best port
     ///
     /// Transmitters missing announce message data are considered inferior to those with complete data.
     pub fn run_bmca_election(&mut self) {
         use std::collections::HashMap;
 
-        // Group transmitters by domain
-        let mut domain_transmitters: HashMap<u8, Vec<ClockIdentity>> = HashMap::new();
+        let now = self.current_time();
+
+        // Group transmitter ports by segment, noting whether they are qualified
+        let mut segment_transmitters: HashMap<PtpSegment, Vec<(PortIdentity, bool)>> =
+            HashMap::new();
 
-        for (clock_id, host) in &self.hosts {
-            if let (Some(domain), PtpHostState::TimeTransmitter(_)) =
-                (host.domain_number, &host.state)
+        for port in self.hosts.values().flat_map(|h| h.ports.values()) {
+            if let (Some(segment), PtpHostState::TimeTransmitter(state)) =
+                (&port.segment, &port.state)
             {
-                domain_transmitters
-                    .entry(domain)
+                segment_transmitters
+                    .entry(segment.clone())
                     .or_default()
-                    .push(*clock_id);
+                    .push((port.port_identity, state.is_qualified(now)));
             }
         }
 
-        // For each domain, find the best transmitter using BMCA
-        for (domain, transmitters) in domain_transmitters {
+        // For each segment, find the best transmitter using BMCA
+        for (segment, transmitters) in segment_transmitters {
             if transmitters.is_empty() {
                 continue;
             }
 
-            // Reset all winners in this domain first
-            for clock_id in &transmitters {
-                if let Some(host) = self.hosts.get_mut(clock_id)
-                    && let PtpHostState::TimeTransmitter(ref mut state) = host.state
+            // Reset all winners in this segment first
+            for (port_identity, _) in &transmitters {
+                if let Some(port) = self.get_port_mut(port_identity)
+                    && let PtpHostState::TimeTransmitter(ref mut state) = port.state
                 {
                     state.is_bmca_winner = false;
                 }
             }
 
-            // Find the best transmitter by comparing all pairs
-            let mut best_clock_id = transmitters[0];
-
-            for &candidate_clock_id in &transmitters[1..] {
-                if let (Some(best_host), Some(candidate_host)) = (
-                    self.hosts.get(&best_clock_id),
-                    self.hosts.get(&candidate_clock_id),
-                ) && let (
-                    PtpHostState::TimeTransmitter(best_state),
-                    PtpHostState::TimeTransmitter(candidate_state),
-                ) = (&best_host.state, &candidate_host.state)
-                {
-                    let comparison_result = candidate_state.compare_for_bmca(
-                        best_state,
-                        candidate_clock_id,
-                        best_clock_id,
-                    );
+            let any_qualified = transmitters.iter().any(|(_, qualified)| *qualified);
+            let transmitters: Vec<PortIdentity> = transmitters
+                .into_iter()
+                .filter(|(_, qualified)| *qualified || !any_qualified)
+                .map(|(port_identity, _)| port_identity)
+                .collect();
 
-                    if comparison_result == std::cmp::Ordering::Less {
-                        best_clock_id = candidate_clock_id;
-                    }
-                }
-            }
+            let Some(best_port_id) = self.best_transmitter(transmitters.iter().copied()) else {
+                continue;
+            };
+
+            // The grandmaster is the announced one, which may sit behind boundary clocks
+            let gm_identity = self
+                .get_port(&best_port_id)
+                .and_then(|port| match &port.state {
+                    PtpHostState::TimeTransmitter(state) => state.gm_identifier,
+                    _ => None,
+                })
+                .unwrap_or(best_port_id.clock_identity);
+            let gm_port_id = self.best_transmitter(
+                transmitters
+                    .iter()
+                    .copied()
+                    .filter(|port_id| port_id.clock_identity == gm_identity),
+            );
 
             // Mark the winner
-            if let Some(winner_host) = self.hosts.get_mut(&best_clock_id)
-                && let PtpHostState::TimeTransmitter(ref mut state) = winner_host.state
+            if let Some(gm_port_id) = gm_port_id
+                && let Some(winner_port) = self.get_port_mut(&gm_port_id)
+                && let PtpHostState::TimeTransmitter(ref mut state) = winner_port.state
             {
                 state.is_bmca_winner = true;
+                if self
+                    .segment_grandmasters
+                    .get(&segment)
+                    .is_none_or(|(port_id, _)| *port_id != gm_port_id)
+                {
+                    self.segment_grandmasters
+                        .insert(segment.clone(), (gm_port_id, now));
+                }
             }
 
-            // Update receivers in this domain to select the BMCA winner as their transmitter
-            self.update_receivers_for_domain(domain, best_clock_id);
+            // Update receivers in this segment to select the best transmitter port
+            self.update_receivers_for_segment(&segment, best_port_id);
         }
-    }
 
-    /// Update all receivers in a domain to select the BMCA winner as their transmitter
-    fn update_receivers_for_domain(&mut self, domain: u8, winner_clock_id: ClockIdentity) {
         for host in self.hosts.values_mut() {
-            if host.domain_number == Some(domain)
-                && let PtpHostState::TimeReceiver(ref mut receiver_state) = host.state
+            host.refresh_from_ports();
+        }
+    }
+
+    /// The best of the given transmitter ports by the BMCA dataset comparison
+    fn best_transmitter(
+        &self,
+        mut port_ids: impl Iterator<Item = PortIdentity>,
+    ) -> Option<PortIdentity> {
+        let mut best_port_id = port_ids.next()?;
+
+        for candidate_port_id in port_ids {
+            if let (Some(best_port), Some(candidate_port)) = (
+                self.get_port(&best_port_id),
+                self.get_port(&candidate_port_id),
+            ) && let (
+                PtpHostState::TimeTransmitter(best_state),
+                PtpHostState::TimeTransmitter(candidate_state),
+            ) = (&best_port.state, &candidate_port.state)
+            {
+                let comparison_result =
+                    candidate_state.compare_for_bmca(best_state, candidate_port_id, best_port_id);
+
+                if comparison_result == std::cmp::Ordering::Less {
+                    best_port_id = candidate_port_id;
+                }
+            }
+        }
+
+        Some(best_port_id)
+    }
+
+    /// Update receiving ports in a segment to select the best transmitter port. Receivers that
+    /// got a Delay_Resp know their transmitter, e.g. a boundary clock, and keep it. So do
+    /// receivers synchronized by the peer on their P2P link.
+    fn update_receivers_for_segment(&mut self, segment: &PtpSegment, winner_port_id: PortIdentity) {
+        for port in self.hosts.values_mut().flat_map(|h| h.ports.values_mut()) {
+            if port.segment.as_ref() == Some(segment)
+                && let PtpHostState::TimeReceiver(ref mut receiver_state) = port.state
+                && receiver_state
+                    .last_delay_response_origin_timestamp
+                    .is_none()
+                && !receiver_state
+                    .selected_transmitter_identity
+                    .is_some_and(|id| port.link_peers.contains_key(&id))
             {
-                receiver_state.selected_transmitter_identity = Some(winner_clock_id);
+                receiver_state.selected_transmitter_identity = Some(winner_port_id);
                 receiver_state.selected_transmitter_confidence = 1.0; // High confidence from BMCA
             }
         }
     }
+
+    fn get_port(&self, port_identity: &PortIdentity) -> Option<&PtpPort> {
+        self.hosts
+            .get(&port_identity.clock_identity)
+            .and_then(|h| h.ports.get(port_identity))
+    }
+
+    fn get_port_mut(&mut self, port_identity: &PortIdentity) -> Option<&mut PtpPort> {
+        self.hosts
+            .get_mut(&port_identity.clock_identity)
+            .and_then(|h| h.ports.get_mut(port_identity))
+    }
 }
 
 #[cfg(test)]
 mod bmca_tests {
     use super::*;
     use crate::types::{PtpClockAccuracy, PtpClockClass};
+    use std::cmp::Ordering;
 
-    fn create_test_transmitter_state() -> PtpHostStateTimeTransmitter {
+    fn create_test_transmitter_state(gm: ClockIdentity) -> PtpHostStateTimeTransmitter {
         PtpHostStateTimeTransmitter {
             priority1: Some(128),
             priority2: Some(128),
@@ -1143,117 +5478,185 @@ mod bmca_tests {
             offset_scaled_log_variance: Some(0x4E5D),
             steps_removed: Some(0),
             time_source: Some(0x20),
+            gm_identifier: Some(gm),
             ..Default::default()
         }
     }
 
     fn create_test_clock_identity(id: u64) -> ClockIdentity {
         ClockIdentity {
-            clock_id: [
-                (id >> 56) as u8,
-                (id >> 48) as u8,
-                (id >> 40) as u8,
-                (id >> 32) as u8,
-                (id >> 24) as u8,
-                (id >> 16) as u8,
-                (id >> 8) as u8,
-                id as u8,
-            ],
+            clock_id: id.to_be_bytes(),
+        }
+    }
+
+    fn create_test_port_identity(id: u64, port_number: u16) -> PortIdentity {
+        PortIdentity {
+            clock_identity: create_test_clock_identity(id),
+            port_number,
         }
     }
 
+    fn create_test_dataset(gm: u64, steps_removed: u16, sender: PortIdentity) -> BmcaDataset {
+        create_test_transmitter_state(create_test_clock_identity(gm))
+            .bmca_dataset(sender)
+            .map(|d| BmcaDataset { steps_removed, ..d })
+            .unwrap()
+    }
+
     #[test]
     fn test_bmca_priority1_comparison() {
-        let mut state1 = create_test_transmitter_state();
-        let mut state2 = create_test_transmitter_state();
+        let port1 = create_test_port_identity(1, 1);
+        let port2 = create_test_port_identity(2, 1);
+        let mut state1 = create_test_transmitter_state(port1.clock_identity);
+        let mut state2 = create_test_transmitter_state(port2.clock_identity);
 
         state1.priority1 = Some(64);
         state2.priority1 = Some(128);
 
-        let clock1 = create_test_clock_identity(1);
-        let clock2 = create_test_clock_identity(2);
-
         // Lower priority1 should win
         assert_eq!(
-            state1.compare_for_bmca(&state2, clock1, clock2),
-            std::cmp::Ordering::Less
+            state1.compare_for_bmca(&state2, port1, port2),
+            Ordering::Less
         );
         assert_eq!(
-            state2.compare_for_bmca(&state1, clock2, clock1),
-            std::cmp::Ordering::Greater
+            state2.compare_for_bmca(&state1, port2, port1),
+            Ordering::Greater
         );
     }
 
     #[test]
     fn test_bmca_clock_class_comparison() {
-        let mut state1 = create_test_transmitter_state();
-        let mut state2 = create_test_transmitter_state();
+        let port1 = create_test_port_identity(1, 1);
+        let port2 = create_test_port_identity(2, 1);
+        let mut state1 = create_test_transmitter_state(port1.clock_identity);
+        let mut state2 = create_test_transmitter_state(port2.clock_identity);
 
         // Same priority1, different clock class
-        state1.priority1 = Some(128);
-        state2.priority1 = Some(128);
         state1.clock_class = Some(PtpClockClass::new(6)); // Better (lower)
         state2.clock_class = Some(PtpClockClass::new(7)); // Worse (higher)
 
-        let clock1 = create_test_clock_identity(1);
-        let clock2 = create_test_clock_identity(2);
-
         // Lower clock class should win
         assert_eq!(
-            state1.compare_for_bmca(&state2, clock1, clock2),
-            std::cmp::Ordering::Less
+            state1.compare_for_bmca(&state2, port1, port2),
+            Ordering::Less
         );
     }
 
     #[test]
     fn test_bmca_clock_identity_tiebreaker() {
-        let state1 = create_test_transmitter_state();
-        let state2 = create_test_transmitter_state();
-
-        // Identical parameters, should use clock identity as tiebreaker
-        let clock1 = create_test_clock_identity(0x0000000000000001);
-        let clock2 = create_test_clock_identity(0x0000000000000002);
+        // Identical parameters, should use the grandmaster identity as tiebreaker
+        let port1 = create_test_port_identity(0x0000000000000001, 1);
+        let port2 = create_test_port_identity(0x0000000000000002, 1);
+        let state1 = create_test_transmitter_state(port1.clock_identity);
+        let state2 = create_test_transmitter_state(port2.clock_identity);
 
         // Lower clock identity should win
         assert_eq!(
-            state1.compare_for_bmca(&state2, clock1, clock2),
-            std::cmp::Ordering::Less
+            state1.compare_for_bmca(&state2, port1, port2),
+            Ordering::Less
         );
         assert_eq!(
-            state2.compare_for_bmca(&state1, clock2, clock1),
-            std::cmp::Ordering::Greater
+            state2.compare_for_bmca(&state1, port2, port1),
+            Ordering::Greater
         );
     }
 
     #[test]
     fn test_bmca_missing_data_handling() {
-        let mut state1 = create_test_transmitter_state();
-        let mut state2 = create_test_transmitter_state();
+        let port1 = create_test_port_identity(1, 1);
+        let port2 = create_test_port_identity(2, 1);
+        let state1 = create_test_transmitter_state(port1.clock_identity);
+        let mut state2 = create_test_transmitter_state(port2.clock_identity);
 
         // State1 has priority1, state2 doesn't
-        state1.priority1 = Some(128);
         state2.priority1 = None;
 
-        let clock1 = create_test_clock_identity(1);
-        let clock2 = create_test_clock_identity(2);
-
         // Having data should be better than not having data
         assert_eq!(
-            state1.compare_for_bmca(&state2, clock1, clock2),
-            std::cmp::Ordering::Less
+            state1.compare_for_bmca(&state2, port1, port2),
+            Ordering::Less
         );
         assert_eq!(
-            state2.compare_for_bmca(&state1, clock2, clock1),
-            std::cmp::Ordering::Greater
+            state2.compare_for_bmca(&state1, port2, port1),
+            Ordering::Greater
         );
     }
 
     #[test]
     fn test_bmca_winner_flag() {
-        let mut state = create_test_transmitter_state();
+        let mut state = create_test_transmitter_state(create_test_clock_identity(1));
         assert!(!state.is_bmca_winner);
 
         state.is_bmca_winner = true;
         assert!(state.is_bmca_winner);
     }
+
+    #[test]
+    fn test_bmca_grandmaster_quality_ignores_sender() {
+        // A boundary clock with a low clock identity relaying a worse grandmaster loses
+        // against a better grandmaster, regardless of the sender identities
+        let relay = create_test_port_identity(1, 1);
+        let gm = create_test_port_identity(9, 1);
+        let mut relayed = create_test_dataset(5, 1, relay);
+        relayed.gm_clock_class = 7;
+        let direct = create_test_dataset(9, 0, gm);
+
+        assert_eq!(relayed.compare(&direct), BmcaComparison::BBetter);
+        assert_eq!(direct.compare(&relayed), BmcaComparison::ABetter);
+    }
+
+    #[test]
+    fn test_bmca_same_grandmaster_steps_removed() {
+        let gm = create_test_port_identity(9, 1);
+        let relay = create_test_port_identity(1, 2);
+        let direct = create_test_dataset(9, 0, gm);
+        let one_step = create_test_dataset(9, 1, relay);
+        let three_steps = create_test_dataset(9, 3, relay);
+
+        // More than one step apart: the shorter path is plainly better
+        assert_eq!(direct.compare(&three_steps), BmcaComparison::ABetter);
+        assert_eq!(three_steps.compare(&direct), BmcaComparison::BBetter);
+
+        // One step apart without a known receiver: better by topology
+        assert_eq!(direct.compare(&one_step), BmcaComparison::ABetterByTopology);
+        assert_eq!(one_step.compare(&direct), BmcaComparison::BBetterByTopology);
+
+        // One step apart with the receiver below the sender
+        let received = BmcaDataset {
+            receiver: Some(create_test_port_identity(0, 1)),
+            ..one_step
+        };
+        assert_eq!(received.compare(&direct), BmcaComparison::BBetter);
+
+        // An Announce received by the clock that sent it
+        let returned = BmcaDataset {
+            receiver: Some(create_test_port_identity(1, 1)),
+            ..one_step
+        };
+        assert_eq!(returned.compare(&direct), BmcaComparison::Error1);
+    }
+
+    #[test]
+    fn test_bmca_same_grandmaster_equal_steps() {
+        let relay1 = create_test_port_identity(1, 1);
+        let relay2 = create_test_port_identity(2, 1);
+        let a = create_test_dataset(9, 1, relay1);
+        let b = create_test_dataset(9, 1, relay2);
+
+        // Lower sender identity wins by topology
+        assert_eq!(a.compare(&b), BmcaComparison::ABetterByTopology);
+        assert_eq!(b.compare(&a), BmcaComparison::BBetterByTopology);
+
+        // Same sender: the lower receiving port number wins, identical paths are an error
+        let on_port = |port_number| BmcaDataset {
+            receiver: Some(create_test_port_identity(3, port_number)),
+            ..a
+        };
+        assert_eq!(
+            on_port(1).compare(&on_port(2)),
+            BmcaComparison::ABetterByTopology
+        );
+        assert_eq!(on_port(1).compare(&on_port(1)), BmcaComparison::Error2);
+        assert_eq!(a.compare(&a), BmcaComparison::Error2);
+    }
 }
@@ -1,6 +1,13 @@
-use crate::ptp::PtpHost;
-use crate::types::{ClockIdentity, ParsedPacket, PtpClockAccuracy, PtpClockClass};
+use crate::ptp::{
+    ClockIdentityConflict, DatasetChange, LeapSecond, PtpHost, PtpSegment, SyncFollowUpIssue,
+    SyncSource, TimePropertiesIssue,
+};
+use crate::types::{
+    ClockIdentity, ParsedPacket, PortIdentity, PtpClockAccuracy, PtpClockClass, PtpLogInterval,
+    PtpMessageType,
+};
 use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
 
 /// Events emitted by the PTP service for real-time monitoring
 #[derive(Debug, Clone)]
@@ -16,28 +23,83 @@ pub enum PtpEvent {
     },
 
     /// A host has timed out (no packets received for timeout threshold)
-    #[allow(dead_code)]
     HostTimeout {
         clock_identity: ClockIdentity,
         last_seen_ago_secs: u64,
     },
 
-    /// A host changed PTP domain
+    /// A timed out host sent packets again
+    HostRecovered {
+        clock_identity: ClockIdentity,
+        /// Time between the last packet before and the first packet after the outage
+        outage_secs: u64,
+    },
+
+    /// A host was silent for longer than the host expiry and was removed
+    HostExpired { clock_identity: ClockIdentity },
+
+    /// A transmitter port sent no Announce within the announceReceiptTimeout and no longer
+    /// takes part in the BMCA
+    AnnounceTimeout {
+        port_identity: PortIdentity,
+        domain: Option<u8>,
+        /// Time since the last Announce
+        silent_for: Duration,
+    },
+
+    /// A host left a PTP domain for one it joined after its last message in the old one
     DomainChange {
         clock_identity: ClockIdentity,
         old_domain: u8,
         new_domain: u8,
     },
 
-    /// The grandmaster changed in a domain (BMCA election result changed)
+    /// The grandmaster changed in a segment (BMCA election result changed)
     GrandmasterChange {
-        domain: u8,
+        segment: PtpSegment,
         old_gm: Option<ClockIdentity>,
         new_gm: ClockIdentity,
     },
 
-    /// Clock quality degraded
-    #[allow(dead_code)]
+    /// Unrelated clocks send Sync in the same segment, splitting its receivers between
+    /// them. Emitted again when the set of sources changes.
+    CompetingGrandmasters {
+        segment: PtpSegment,
+        /// The Sync sources with the receivers that got a Delay_Resp from each
+        sources: Vec<SyncSource>,
+    },
+
+    /// A clock identity seems to be used by several devices, or doesn't match the MAC
+    /// address of its sender
+    ClockIdentityConflict {
+        clock_identity: ClockIdentity,
+        conflict: ClockIdentityConflict,
+    },
+
+    /// Transmitters of a segment disagree on their time properties, or announce an
+    /// unexpected UTC offset or timescale. Emitted once while the issue lasts.
+    TimePropertiesIssue {
+        segment: PtpSegment,
+        issue: TimePropertiesIssue,
+    },
+
+    /// A grandmaster port set the leap61 or leap59 flag
+    LeapSecondAnnounced {
+        port_identity: PortIdentity,
+        leap: LeapSecond,
+        /// The UTC midnight the leap second takes effect at
+        at: SystemTime,
+    },
+
+    /// A grandmaster port cleared its leap second flag, after the leap second or instead of it
+    LeapSecondEnded {
+        port_identity: PortIdentity,
+        leap: LeapSecond,
+        old_utc_offset: i16,
+        new_utc_offset: i16,
+    },
+
+    /// The announced class or accuracy of a grandmaster got worse
     ClockQualityDegraded {
         clock_identity: ClockIdentity,
         old_class: PtpClockClass,
@@ -46,6 +108,61 @@ pub enum PtpEvent {
         new_accuracy: PtpClockAccuracy,
     },
 
+    /// The announced class or accuracy of a grandmaster got better
+    ClockQualityImproved {
+        clock_identity: ClockIdentity,
+        old_class: PtpClockClass,
+        new_class: PtpClockClass,
+        old_accuracy: PtpClockAccuracy,
+        new_accuracy: PtpClockAccuracy,
+    },
+
+    /// A field of the dataset announced by a transmitter port changed
+    DatasetChanged {
+        port_identity: PortIdentity,
+        gm_identity: ClockIdentity,
+        change: DatasetChange,
+    },
+
+    /// Sync and Follow_Up messages of a transmitter port didn't pair up
+    SyncFollowUpIssue {
+        port_identity: PortIdentity,
+        issue: SyncFollowUpIssue,
+        /// Occurrences since the previous event for this port and issue
+        count: u32,
+    },
+
+    /// The time distributed by a transmitter port jumped against the local capture clock
+    GrandmasterTimeStep {
+        port_identity: PortIdentity,
+        /// Size of the jump in nanoseconds
+        step_ns: i64,
+        /// Offset from the local clock after the jump, in nanoseconds
+        offset_ns: i64,
+    },
+
+    /// The measured interval of a message stream deviates from the advertised logMessageInterval
+    IntervalDeviation {
+        port_identity: PortIdentity,
+        message_type: PtpMessageType,
+        advertised: PtpLogInterval,
+        /// Mean measured interval
+        measured: Duration,
+        std_dev: Duration,
+        deviation_percent: f64,
+    },
+
+    /// Sequence id gaps of a message stream exceeded the configured loss threshold
+    SequenceLoss {
+        port_identity: PortIdentity,
+        message_type: PtpMessageType,
+        domain: u8,
+        /// Loss since the previous check of this stream
+        loss_percent: f64,
+        lost: u32,
+        expected: u32,
+    },
+
     /// Network interface link state changed
     InterfaceLinkChange { interface: String, link_up: bool },
 
@@ -77,13 +194,16 @@ pub enum ChangeType {
         old: Option<u16>,
         new: Option<u16>,
     },
-    DomainNumber {
-        old: Option<u8>,
-        new: Option<u8>,
+    /// The host joined or left domains
+    Domains {
+        added: Vec<u8>,
+        removed: Vec<u8>,
     },
     State {
         old: String,
         new: String,
+        /// Observations the new port state was inferred from
+        evidence: String,
     },
     ClockQuality {
         description: String,
@@ -102,7 +222,7 @@ impl ChangeType {
             ChangeType::IpAddress { .. } => "ip_address",
             ChangeType::Interface { .. } => "interface",
             ChangeType::VlanId { .. } => "vlan_id",
-            ChangeType::DomainNumber { .. } => "domain_number",
+            ChangeType::Domains { .. } => "domains",
             ChangeType::State { .. } => "state",
             ChangeType::ClockQuality { .. } => "clock_quality",
             ChangeType::MessageCounts => "message_counts",
@@ -142,17 +262,25 @@ impl ChangeType {
                         .unwrap_or_else(|| "none".to_string())
                 )
             }
-            ChangeType::DomainNumber { old, new } => {
-                format!(
-                    "{} -> {}",
-                    old.map(|v| v.to_string())
-                        .unwrap_or_else(|| "none".to_string()),
-                    new.map(|v| v.to_string())
-                        .unwrap_or_else(|| "none".to_string())
-                )
+            ChangeType::Domains { added, removed } => {
+                let join = |domains: &[u8]| {
+                    domains
+                        .iter()
+                        .map(|d| d.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let mut parts = Vec::new();
+                if !added.is_empty() {
+                    parts.push(format!("added=[{}]", join(added)));
+                }
+                if !removed.is_empty() {
+                    parts.push(format!("removed=[{}]", join(removed)));
+                }
+                parts.join(" ")
             }
-            ChangeType::State { old, new } => {
-                format!("{} -> {}", old, new)
+            ChangeType::State { old, new, evidence } => {
+                format!("{} -> {} ({})", old, new, evidence)
             }
             ChangeType::ClockQuality { description } => description.clone(),
             ChangeType::MessageCounts => "updated".to_string(),
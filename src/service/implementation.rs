@@ -3,29 +3,61 @@
 //! This module provides the concrete implementation of the PtpService trait,
 //! managing packet capture, host tracking, and event emission.
 
-use crate::ptp::PtpTracker;
+use crate::filter::PacketFilter;
+use crate::ptp::{
+    AnnouncedDataset, ClockIdentityConflict, DatasetChangeDirection, DelayMechanism,
+    DomainParticipation, PtpHost, PtpHostState, PtpPort, PtpProfile, PtpSegment, PtpTracker,
+    SyncFollowUpIssue, TimePropertiesIssue, next_utc_midnight,
+};
 use crate::service::events::{ChangeType, PtpEvent};
 use crate::service::interface_monitor::InterfaceMonitor;
-use crate::service::{PtpService, PtpStatistics};
+use crate::service::{PtpDomainSummary, PtpService, PtpStatistics, ServiceConfig};
 use crate::source::RawSocketReceiver;
-use crate::types::{ClockIdentity, ParsedPacket};
+use crate::types::{ClockIdentity, ParsedPacket, PortIdentity, PtpMessageType};
 use anyhow::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, mpsc};
 
 /// Snapshot of host state for change detection
 #[derive(Debug, Clone)]
 struct HostSnapshot {
-    domain_number: Option<u8>,
+    /// Domains the host is active in, see `PtpHost::active_domains`
+    domains: BTreeMap<u8, DomainParticipation>,
     ip_addresses: std::collections::HashSet<std::net::IpAddr>,
     interfaces: std::collections::HashSet<String>,
-    state_type: String, // "Listening", "TimeTransmitter", "TimeReceiver"
-    is_bmca_winner: bool,
-    clock_class: Option<u8>,
-    selected_transmitter: Option<ClockIdentity>,
+    state_type: String, // Short state, e.g. "TT", "GM" or "PA", see `PtpHost::state_short_string`
+    /// Segments in which a port of this host is the BMCA winner
+    gm_segments: Vec<PtpSegment>,
+    datasets: HashMap<PortIdentity, AnnouncedDataset>,
+    selected_transmitter: Option<PortIdentity>,
+    sync_follow_up_issues: HashMap<(PortIdentity, SyncFollowUpIssue), u32>,
+    deviating_intervals: HashSet<(PortIdentity, PtpMessageType)>,
+    gm_time_steps: HashMap<PortIdentity, u32>,
+    stale_ports: HashSet<PortIdentity>,
+    identity_conflicts: HashSet<ClockIdentityConflict>,
+    last_seen: SystemTime,
+    timed_out: bool,
+}
+
+/// Minimum number of expected packets before the loss of a stream is evaluated
+const LOSS_WINDOW_MIN_PACKETS: u32 = 20;
+
+/// Minimum time between two `SequenceLoss` events for the same stream
+const LOSS_EVENT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sequence stream of a port: (port, message type, domain)
+type StreamKey = (PortIdentity, PtpMessageType, u8);
+
+/// Loss counters of a stream at the start of the current evaluation window
+#[derive(Debug, Clone, Default)]
+struct LossWindow {
+    lost: u32,
+    expected: u32,
+    /// Capture time of the last event, in pcap mode
+    last_event: Option<SystemTime>,
 }
 
 /// Implementation of PtpService
@@ -39,24 +71,34 @@ pub struct PtpServiceImpl {
     /// Previous state snapshots for change detection
     previous_states: Arc<RwLock<HashMap<ClockIdentity, HostSnapshot>>>,
 
-    /// Grandmaster tracking for domain change detection
-    domain_grandmasters: Arc<RwLock<HashMap<u8, ClockIdentity>>>,
+    /// Grandmaster tracking per segment for change detection
+    segment_grandmasters: Arc<RwLock<HashMap<PtpSegment, ClockIdentity>>>,
+
+    /// Competing Sync sources per segment, for `CompetingGrandmasters` events
+    competing_sources: Arc<RwLock<HashMap<PtpSegment, BTreeSet<PortIdentity>>>>,
+
+    /// Time property issues already reported, for `TimePropertiesIssue` events
+    time_property_issues: Arc<RwLock<HashSet<(PtpSegment, TimePropertiesIssue)>>>,
+
+    /// Per-stream loss windows for `SequenceLoss` events
+    loss_windows: Arc<RwLock<HashMap<StreamKey, LossWindow>>>,
+
+    loss_threshold_percent: f64,
+
+    interval_tolerance_percent: f64,
+
+    host_timeout: Option<Duration>,
+
+    host_expiry: Option<Duration>,
 }
 
 impl PtpServiceImpl {
     /// Create a new service instance and start background processing
-    pub async fn new(raw_socket_receiver: RawSocketReceiver) -> Result<Arc<Self>> {
-        let tracker = Arc::new(RwLock::new(PtpTracker::new(raw_socket_receiver)?));
-        let event_subscribers = Arc::new(RwLock::new(Vec::new()));
-        let previous_states = Arc::new(RwLock::new(HashMap::new()));
-        let domain_grandmasters = Arc::new(RwLock::new(HashMap::new()));
-
-        let service = Arc::new(Self {
-            tracker,
-            event_subscribers,
-            previous_states,
-            domain_grandmasters,
-        });
+    pub async fn new(
+        raw_socket_receiver: RawSocketReceiver,
+        config: ServiceConfig,
+    ) -> Result<Arc<Self>> {
+        let service = Arc::new(Self::without_tasks(raw_socket_receiver, config)?);
 
         // Start background packet processing
         Self::start_packet_processing(service.clone()).await;
@@ -70,16 +112,62 @@ impl PtpServiceImpl {
         Ok(service)
     }
 
+    /// The service state, without the background tasks that drive it
+    fn without_tasks(
+        raw_socket_receiver: RawSocketReceiver,
+        config: ServiceConfig,
+    ) -> Result<Self> {
+        let mut tracker = PtpTracker::new(raw_socket_receiver)?;
+        tracker.set_filter(config.filter);
+        tracker.set_announce_receipt_timeout(config.announce_receipt_timeout);
+        tracker.set_time_expectations(config.expected_utc_offset, config.expected_timescale);
+        let tracker = Arc::new(RwLock::new(tracker));
+        let event_subscribers = Arc::new(RwLock::new(Vec::new()));
+        let previous_states = Arc::new(RwLock::new(HashMap::new()));
+        let segment_grandmasters = Arc::new(RwLock::new(HashMap::new()));
+
+        Ok(Self {
+            tracker,
+            event_subscribers,
+            previous_states,
+            segment_grandmasters,
+            competing_sources: Arc::new(RwLock::new(HashMap::new())),
+            time_property_issues: Arc::new(RwLock::new(HashSet::new())),
+            loss_windows: Arc::new(RwLock::new(HashMap::new())),
+            loss_threshold_percent: config.loss_threshold_percent,
+            interval_tolerance_percent: config.interval_tolerance_percent,
+            host_timeout: config.host_timeout,
+            host_expiry: config.host_expiry,
+        })
+    }
+
     /// Start background task for processing packets
     async fn start_packet_processing(service: Arc<Self>) {
         tokio::spawn(async move {
             loop {
                 // Process packets and get the list of processed packets
-                let packets = {
+                let (packets, expired) = {
                     let mut tracker = service.tracker.write().await;
-                    tracker.scan_network().await
+                    let packets = tracker.scan_network().await;
+                    let expired = service
+                        .host_expiry
+                        .map(|expiry| tracker.remove_expired_hosts(expiry))
+                        .unwrap_or_default();
+                    (packets, expired)
                 };
 
+                if !expired.is_empty() {
+                    let mut previous_states = service.previous_states.write().await;
+                    for clock_identity in &expired {
+                        previous_states.remove(clock_identity);
+                    }
+                }
+                for clock_identity in expired {
+                    service
+                        .emit_event(PtpEvent::HostExpired { clock_identity })
+                        .await;
+                }
+
                 // Emit PacketReceived events for each packet
                 for packet in packets {
                     service
@@ -143,160 +231,596 @@ impl PtpServiceImpl {
         });
     }
 
+    /// Snapshot of the host state the events are detected from
+    fn snapshot_host(&self, host: &PtpHost, now: SystemTime) -> HostSnapshot {
+        let host_timeout = self.host_timeout.unwrap_or_else(|| host.default_timeout());
+        HostSnapshot {
+            domains: host
+                .active_domains(host_timeout)
+                .filter_map(|domain| {
+                    host.domains
+                        .get(&domain)
+                        .map(|participation| (domain, participation.clone()))
+                })
+                .collect(),
+            ip_addresses: host.ip_addresses.keys().copied().collect(),
+            interfaces: host.interfaces.clone(),
+            state_type: host.state_short_string().to_string(),
+            gm_segments: host
+                .ports
+                .values()
+                .filter(|port| {
+                    matches!(port.state, PtpHostState::TimeTransmitter(ref s) if s.is_bmca_winner)
+                })
+                .filter_map(|port| port.segment.clone())
+                .collect(),
+            datasets: host
+                .ports
+                .values()
+                .filter_map(|port| match &port.state {
+                    PtpHostState::TimeTransmitter(s) | PtpHostState::Stale(s) => {
+                        s.dataset.map(|dataset| (port.port_identity, dataset))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            selected_transmitter: if let PtpHostState::TimeReceiver(ref s) = host.state {
+                s.selected_transmitter_identity
+            } else {
+                None
+            },
+            sync_follow_up_issues: host
+                .ports
+                .values()
+                .flat_map(|port| {
+                    SyncFollowUpIssue::ALL.into_iter().map(|issue| {
+                        (
+                            (port.port_identity, issue),
+                            port.sync_follow_up.issue_count(issue),
+                        )
+                    })
+                })
+                .collect(),
+            deviating_intervals: host
+                .ports
+                .values()
+                .flat_map(|port| {
+                    port.message_intervals
+                        .iter()
+                        .filter(|(_, stats)| stats.deviates(self.interval_tolerance_percent))
+                        .map(|(&message_type, _)| (port.port_identity, message_type))
+                })
+                .collect(),
+            gm_time_steps: host
+                .ports
+                .values()
+                .map(|port| (port.port_identity, port.gm_offset.step_count))
+                .collect(),
+            stale_ports: host
+                .ports
+                .values()
+                .filter(|port| matches!(port.state, PtpHostState::Stale(_)))
+                .map(|port| port.port_identity)
+                .collect(),
+            identity_conflicts: host.identity_conflicts.iter().cloned().collect(),
+            last_seen: host.last_seen,
+            timed_out: host.time_since_last_seen(Some(now)) > host_timeout,
+        }
+    }
+
     /// Detect changes and emit events
     async fn detect_and_emit_changes(&self) -> Result<()> {
         let tracker = self.tracker.read().await;
         let hosts = tracker.get_hosts();
+        let now = tracker.current_time();
         let mut previous_states = self.previous_states.write().await;
-        let mut domain_gms = self.domain_grandmasters.write().await;
+        let mut segment_gms = self.segment_grandmasters.write().await;
+        let mut loss_windows = self.loss_windows.write().await;
+        let mut reported_gms = HashSet::new();
 
         for host in hosts {
             let clock_id = host.clock_identity;
+            let current = self.snapshot_host(host, now);
+            let prev = previous_states.get(&clock_id);
 
-            // Create current snapshot
-            let current_snapshot = HostSnapshot {
-                domain_number: host.domain_number,
-                ip_addresses: host.ip_addresses.keys().copied().collect(),
-                interfaces: host.interfaces.clone(),
-                state_type: host.state.short_string().to_string(),
-                is_bmca_winner: matches!(host.state, crate::ptp::PtpHostState::TimeTransmitter(ref s) if s.is_bmca_winner),
-                clock_class: if let crate::ptp::PtpHostState::TimeTransmitter(ref s) = host.state {
-                    s.clock_class.map(|c| c.class())
-                } else {
-                    None
-                },
-                selected_transmitter: if let crate::ptp::PtpHostState::TimeReceiver(ref s) =
-                    host.state
-                {
-                    s.selected_transmitter_identity
-                } else {
-                    None
-                },
-            };
+            self.emit_identity_conflicts(clock_id, prev, &current).await;
+            self.emit_leap_second_events(clock_id, prev, &current, now)
+                .await;
+            self.emit_timeout_events(host, prev, &current, now).await;
+            self.emit_announce_timeouts(host, prev, &current, now).await;
+            self.emit_sequence_loss(host, &mut loss_windows, now).await;
+            self.emit_sync_follow_up_issues(prev, &current).await;
+            self.emit_gm_time_steps(host, prev, &current).await;
+            self.emit_interval_deviations(host, prev, &current).await;
 
-            if let Some(prev) = previous_states.get(&clock_id) {
-                // Detect changes
-                let mut changes = Vec::new();
-
-                if prev.domain_number != current_snapshot.domain_number {
-                    changes.push(ChangeType::DomainNumber {
-                        old: prev.domain_number,
-                        new: current_snapshot.domain_number,
-                    });
-
-                    // Emit domain change event
-                    if let (Some(old), Some(new)) =
-                        (prev.domain_number, current_snapshot.domain_number)
-                    {
-                        self.emit_event(PtpEvent::DomainChange {
-                            clock_identity: clock_id,
-                            old_domain: old,
-                            new_domain: new,
-                        })
-                        .await;
-                    }
-                }
+            if let Some(prev) = prev {
+                self.emit_domain_changes(clock_id, prev, &current).await;
+                self.emit_dataset_changes(prev, &current, &mut reported_gms)
+                    .await;
+                self.emit_grandmaster_changes(clock_id, &current, &mut segment_gms)
+                    .await;
+                self.emit_host_updated(host, prev, &current).await;
+            } else {
+                // New host discovered
+                self.emit_event(PtpEvent::HostDiscovered(host.clone()))
+                    .await;
 
-                if prev.ip_addresses != current_snapshot.ip_addresses {
-                    let added: Vec<String> = current_snapshot
-                        .ip_addresses
-                        .difference(&prev.ip_addresses)
-                        .map(|ip| ip.to_string())
-                        .collect();
-                    let removed: Vec<String> = prev
-                        .ip_addresses
-                        .difference(&current_snapshot.ip_addresses)
-                        .map(|ip| ip.to_string())
-                        .collect();
-
-                    changes.push(ChangeType::IpAddress { added, removed });
+                // Track if it's a GM
+                for segment in &current.gm_segments {
+                    segment_gms.insert(segment.clone(), clock_id);
                 }
+            }
 
-                if prev.interfaces != current_snapshot.interfaces {
-                    let added: Vec<String> = current_snapshot
-                        .interfaces
-                        .difference(&prev.interfaces)
-                        .cloned()
-                        .collect();
-                    let removed: Vec<String> = prev
-                        .interfaces
-                        .difference(&current_snapshot.interfaces)
-                        .cloned()
-                        .collect();
-
-                    changes.push(ChangeType::Interface { added, removed });
-                }
+            previous_states.insert(clock_id, current);
+        }
 
-                if prev.state_type != current_snapshot.state_type {
-                    changes.push(ChangeType::State {
-                        old: prev.state_type.clone(),
-                        new: current_snapshot.state_type.clone(),
-                    });
-                }
+        self.emit_competing_grandmasters(&tracker).await;
+        self.emit_time_property_issues(&tracker).await;
+
+        Ok(())
+    }
+
+    /// New hosts can already show a conflict, e.g. two MACs within one capture batch
+    async fn emit_identity_conflicts(
+        &self,
+        clock_id: ClockIdentity,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+    ) {
+        for conflict in &current.identity_conflicts {
+            if prev.is_none_or(|prev| !prev.identity_conflicts.contains(conflict)) {
+                self.emit_event(PtpEvent::ClockIdentityConflict {
+                    clock_identity: clock_id,
+                    conflict: conflict.clone(),
+                })
+                .await;
+            }
+        }
+    }
 
-                if prev.clock_class != current_snapshot.clock_class {
-                    let old_str = prev
-                        .clock_class
-                        .map(|c| c.to_string())
-                        .unwrap_or_else(|| "none".to_string());
-                    let new_str = current_snapshot
-                        .clock_class
-                        .map(|c| c.to_string())
-                        .unwrap_or_else(|| "none".to_string());
-
-                    changes.push(ChangeType::ClockQuality {
-                        description: format!("{} -> {}", old_str, new_str),
-                    });
+    /// Leap second flags of the grandmaster's own ports, relays repeat them
+    async fn emit_leap_second_events(
+        &self,
+        clock_id: ClockIdentity,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+        now: SystemTime,
+    ) {
+        for (port_identity, dataset) in &current.datasets {
+            if dataset.gm_identity != clock_id {
+                continue;
+            }
+            let old = prev.and_then(|prev| prev.datasets.get(port_identity));
+            match (old.and_then(|old| old.leap), dataset.leap) {
+                (old_leap, Some(leap)) if old_leap != Some(leap) => {
+                    self.emit_event(PtpEvent::LeapSecondAnnounced {
+                        port_identity: *port_identity,
+                        leap,
+                        at: next_utc_midnight(now),
+                    })
+                    .await;
+                }
+                (Some(leap), None) => {
+                    self.emit_event(PtpEvent::LeapSecondEnded {
+                        port_identity: *port_identity,
+                        leap,
+                        old_utc_offset: old.map_or(0, |old| old.current_utc_offset.offset),
+                        new_utc_offset: dataset.current_utc_offset.offset,
+                    })
+                    .await;
                 }
+                _ => {}
+            }
+        }
+    }
+
+    /// Report each outage once, and the host's return
+    async fn emit_timeout_events(
+        &self,
+        host: &PtpHost,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+        now: SystemTime,
+    ) {
+        let Some(prev) = prev else {
+            return;
+        };
+        if current.timed_out && !prev.timed_out {
+            self.emit_event(PtpEvent::HostTimeout {
+                clock_identity: host.clock_identity,
+                last_seen_ago_secs: host.time_since_last_seen(Some(now)).as_secs(),
+            })
+            .await;
+        } else if prev.timed_out && !current.timed_out {
+            self.emit_event(PtpEvent::HostRecovered {
+                clock_identity: host.clock_identity,
+                outage_secs: current
+                    .last_seen
+                    .duration_since(prev.last_seen)
+                    .unwrap_or_default()
+                    .as_secs(),
+            })
+            .await;
+        }
+    }
 
-                if prev.selected_transmitter != current_snapshot.selected_transmitter {
-                    changes.push(ChangeType::SelectedTransmitter {
-                        old: prev.selected_transmitter.map(|id| id.to_string()),
-                        new: current_snapshot
-                            .selected_transmitter
-                            .map(|id| id.to_string()),
-                    });
+    /// Ports that went stale since the previous snapshot
+    async fn emit_announce_timeouts(
+        &self,
+        host: &PtpHost,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+        now: SystemTime,
+    ) {
+        for port_identity in &current.stale_ports {
+            if prev.is_some_and(|prev| prev.stale_ports.contains(port_identity)) {
+                continue;
+            }
+            let Some(port) = host.ports.get(port_identity) else {
+                continue;
+            };
+            if let PtpHostState::Stale(ref s) = port.state {
+                self.emit_event(PtpEvent::AnnounceTimeout {
+                    port_identity: *port_identity,
+                    domain: port.domain_number,
+                    silent_for: s
+                        .last_announce_time
+                        .and_then(|t| now.duration_since(t).ok())
+                        .unwrap_or_default(),
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Loss of each sequence stream since its last evaluation window, rate-limited per stream
+    async fn emit_sequence_loss(
+        &self,
+        host: &PtpHost,
+        loss_windows: &mut HashMap<StreamKey, LossWindow>,
+        now: SystemTime,
+    ) {
+        for port in host.ports.values() {
+            for (&(message_type, domain), stats) in &port.sequence_streams {
+                let window = loss_windows
+                    .entry((port.port_identity, message_type, domain))
+                    .or_default();
+                let expected = stats.expected_count().saturating_sub(window.expected);
+                if expected < LOSS_WINDOW_MIN_PACKETS {
+                    continue;
                 }
 
-                // Check for grandmaster changes
-                if current_snapshot.is_bmca_winner
-                    && let Some(domain) = current_snapshot.domain_number
-                {
-                    let old_gm = domain_gms.insert(domain, clock_id);
-                    if old_gm.is_some() && old_gm != Some(clock_id) {
-                        self.emit_event(PtpEvent::GrandmasterChange {
-                            domain,
-                            old_gm,
-                            new_gm: clock_id,
-                        })
-                        .await;
-                    }
+                let lost = stats.lost_count.saturating_sub(window.lost);
+                let loss_percent = lost as f64 * 100.0 / expected as f64;
+                window.lost = stats.lost_count;
+                window.expected = stats.expected_count();
+
+                let rate_limited = window.last_event.is_some_and(|t| {
+                    now.duration_since(t).unwrap_or_default() < LOSS_EVENT_INTERVAL
+                });
+                if lost > 0 && loss_percent >= self.loss_threshold_percent && !rate_limited {
+                    window.last_event = Some(now);
+                    self.emit_event(PtpEvent::SequenceLoss {
+                        port_identity: port.port_identity,
+                        message_type,
+                        domain,
+                        loss_percent,
+                        lost,
+                        expected,
+                    })
+                    .await;
                 }
+            }
+        }
+    }
+
+    /// Sync/Follow_Up pairing issues that occurred since the previous snapshot
+    async fn emit_sync_follow_up_issues(
+        &self,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+    ) {
+        for (&(port_identity, issue), &count) in &current.sync_follow_up_issues {
+            let previous = prev
+                .and_then(|prev| prev.sync_follow_up_issues.get(&(port_identity, issue)))
+                .copied()
+                .unwrap_or(0);
+            if count > previous {
+                self.emit_event(PtpEvent::SyncFollowUpIssue {
+                    port_identity,
+                    issue,
+                    count: count - previous,
+                })
+                .await;
+            }
+        }
+    }
 
-                if !changes.is_empty() {
-                    self.emit_event(PtpEvent::HostUpdated {
-                        host: host.clone(),
-                        changes,
+    /// Time steps of transmitter ports since the previous snapshot
+    async fn emit_gm_time_steps(
+        &self,
+        host: &PtpHost,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+    ) {
+        for (port_identity, &step_count) in &current.gm_time_steps {
+            let previous = prev
+                .and_then(|prev| prev.gm_time_steps.get(port_identity))
+                .copied()
+                .unwrap_or(0);
+            if step_count <= previous {
+                continue;
+            }
+            let Some(port) = host.ports.get(port_identity) else {
+                continue;
+            };
+            if let (Some(step), Some(offset_ns)) =
+                (port.gm_offset.last_step, port.gm_offset.latest())
+            {
+                self.emit_event(PtpEvent::GrandmasterTimeStep {
+                    port_identity: *port_identity,
+                    step_ns: step.step_ns,
+                    offset_ns,
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Message streams that started deviating from their advertised interval
+    async fn emit_interval_deviations(
+        &self,
+        host: &PtpHost,
+        prev: Option<&HostSnapshot>,
+        current: &HostSnapshot,
+    ) {
+        for &(port_identity, message_type) in &current.deviating_intervals {
+            if prev.is_some_and(|prev| {
+                prev.deviating_intervals
+                    .contains(&(port_identity, message_type))
+            }) {
+                continue;
+            }
+            let Some(stats) = host
+                .ports
+                .get(&port_identity)
+                .and_then(|port| port.message_intervals.get(&message_type))
+            else {
+                continue;
+            };
+            if let (Some(advertised), Some(mean), Some(deviation_percent)) =
+                (stats.advertised, stats.mean(), stats.deviation_percent())
+            {
+                self.emit_event(PtpEvent::IntervalDeviation {
+                    port_identity,
+                    message_type,
+                    advertised,
+                    measured: mean,
+                    std_dev: stats.std_dev().unwrap_or_default(),
+                    deviation_percent,
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Leaving a domain is only a move if the host never sent in both at once, otherwise it
+    /// just stopped taking part in one of several domains
+    async fn emit_domain_changes(
+        &self,
+        clock_id: ClockIdentity,
+        prev: &HostSnapshot,
+        current: &HostSnapshot,
+    ) {
+        for (old, old_participation) in &prev.domains {
+            if current.domains.contains_key(old) {
+                continue;
+            }
+            let new = current
+                .domains
+                .iter()
+                .filter(|(_, participation)| {
+                    participation.first_seen >= old_participation.last_seen
+                })
+                .min_by_key(|(_, participation)| participation.first_seen);
+            if let Some((new, _)) = new {
+                self.emit_event(PtpEvent::DomainChange {
+                    clock_identity: clock_id,
+                    old_domain: *old,
+                    new_domain: *new,
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Changed fields of announced datasets, and the grandmaster quality they imply
+    async fn emit_dataset_changes(
+        &self,
+        prev: &HostSnapshot,
+        current: &HostSnapshot,
+        reported_gms: &mut HashSet<ClockIdentity>,
+    ) {
+        for (port_identity, dataset) in &current.datasets {
+            let Some(old) = prev.datasets.get(port_identity) else {
+                continue;
+            };
+            for change in dataset.changes_from(old) {
+                self.emit_event(PtpEvent::DatasetChanged {
+                    port_identity: *port_identity,
+                    gm_identity: dataset.gm_identity,
+                    change,
+                })
+                .await;
+            }
+
+            // Relays announce the same grandmaster quality, report it once
+            if dataset.gm_identity != old.gm_identity || !reported_gms.insert(dataset.gm_identity) {
+                continue;
+            }
+            let (old_class, new_class, old_accuracy, new_accuracy) = (
+                old.clock_class,
+                dataset.clock_class,
+                old.clock_accuracy,
+                dataset.clock_accuracy,
+            );
+            match dataset.quality_change_from(old) {
+                Some(DatasetChangeDirection::Degraded) => {
+                    self.emit_event(PtpEvent::ClockQualityDegraded {
+                        clock_identity: dataset.gm_identity,
+                        old_class,
+                        new_class,
+                        old_accuracy,
+                        new_accuracy,
                     })
                     .await;
                 }
-            } else {
-                // New host discovered
-                self.emit_event(PtpEvent::HostDiscovered(host.clone()))
+                Some(DatasetChangeDirection::Improved) => {
+                    self.emit_event(PtpEvent::ClockQualityImproved {
+                        clock_identity: dataset.gm_identity,
+                        old_class,
+                        new_class,
+                        old_accuracy,
+                        new_accuracy,
+                    })
                     .await;
-
-                // Track if it's a GM
-                if current_snapshot.is_bmca_winner
-                    && let Some(domain) = current_snapshot.domain_number
-                {
-                    domain_gms.insert(domain, clock_id);
                 }
+                _ => {}
+            }
+        }
+    }
+
+    /// Segments this host newly won the BMCA in
+    async fn emit_grandmaster_changes(
+        &self,
+        clock_id: ClockIdentity,
+        current: &HostSnapshot,
+        segment_gms: &mut HashMap<PtpSegment, ClockIdentity>,
+    ) {
+        for segment in &current.gm_segments {
+            let old_gm = segment_gms.insert(segment.clone(), clock_id);
+            if old_gm.is_some() && old_gm != Some(clock_id) {
+                self.emit_event(PtpEvent::GrandmasterChange {
+                    segment: segment.clone(),
+                    old_gm,
+                    new_gm: clock_id,
+                })
+                .await;
             }
+        }
+    }
+
+    /// Changes of the host's addresses, interfaces, domains, state and transmitter
+    async fn emit_host_updated(&self, host: &PtpHost, prev: &HostSnapshot, current: &HostSnapshot) {
+        let mut changes = Vec::new();
+
+        let added: Vec<u8> = current
+            .domains
+            .keys()
+            .filter(|domain| !prev.domains.contains_key(domain))
+            .copied()
+            .collect();
+        let removed: Vec<u8> = prev
+            .domains
+            .keys()
+            .filter(|domain| !current.domains.contains_key(domain))
+            .copied()
+            .collect();
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(ChangeType::Domains { added, removed });
+        }
+
+        if prev.ip_addresses != current.ip_addresses {
+            let added: Vec<String> = current
+                .ip_addresses
+                .difference(&prev.ip_addresses)
+                .map(|ip| ip.to_string())
+                .collect();
+            let removed: Vec<String> = prev
+                .ip_addresses
+                .difference(&current.ip_addresses)
+                .map(|ip| ip.to_string())
+                .collect();
 
-            previous_states.insert(clock_id, current_snapshot);
+            changes.push(ChangeType::IpAddress { added, removed });
         }
 
-        Ok(())
+        if prev.interfaces != current.interfaces {
+            let added: Vec<String> = current
+                .interfaces
+                .difference(&prev.interfaces)
+                .cloned()
+                .collect();
+            let removed: Vec<String> = prev
+                .interfaces
+                .difference(&current.interfaces)
+                .cloned()
+                .collect();
+
+            changes.push(ChangeType::Interface { added, removed });
+        }
+
+        if prev.state_type != current.state_type {
+            changes.push(ChangeType::State {
+                old: prev.state_type.clone(),
+                new: current.state_type.clone(),
+                evidence: host.inferred_state.evidence.clone(),
+            });
+        }
+
+        if prev.selected_transmitter != current.selected_transmitter {
+            changes.push(ChangeType::SelectedTransmitter {
+                old: prev.selected_transmitter.map(|id| id.to_string()),
+                new: current.selected_transmitter.map(|id| id.to_string()),
+            });
+        }
+
+        if !changes.is_empty() {
+            self.emit_event(PtpEvent::HostUpdated {
+                host: host.clone(),
+                changes,
+            })
+            .await;
+        }
+    }
+
+    /// Report a split segment once, and again when its Sync sources change
+    async fn emit_competing_grandmasters(&self, tracker: &PtpTracker) {
+        let competing = tracker.get_competing_grandmasters();
+        let mut competing_sources = self.competing_sources.write().await;
+        competing_sources.retain(|segment, _| competing.iter().any(|c| c.segment == *segment));
+        for competition in competing {
+            let sources: BTreeSet<PortIdentity> = competition
+                .sources
+                .iter()
+                .map(|source| source.port_identity)
+                .collect();
+            if competing_sources.get(&competition.segment) != Some(&sources) {
+                competing_sources.insert(competition.segment.clone(), sources);
+                self.emit_event(PtpEvent::CompetingGrandmasters {
+                    segment: competition.segment,
+                    sources: competition.sources,
+                })
+                .await;
+            }
+        }
+    }
+
+    /// Report each time property issue once, while it lasts
+    async fn emit_time_property_issues(&self, tracker: &PtpTracker) {
+        let issues: HashSet<(PtpSegment, TimePropertiesIssue)> =
+            tracker.get_time_property_issues().into_iter().collect();
+        let mut reported_issues = self.time_property_issues.write().await;
+        for (segment, issue) in &issues {
+            if !reported_issues.contains(&(segment.clone(), issue.clone())) {
+                self.emit_event(PtpEvent::TimePropertiesIssue {
+                    segment: segment.clone(),
+                    issue: issue.clone(),
+                })
+                .await;
+            }
+        }
+        *reported_issues = issues;
     }
 
     /// Emit an event to all subscribers
@@ -346,6 +870,14 @@ impl PtpService for PtpServiceImpl {
             .unwrap_or_default())
     }
 
+    async fn get_gm_dataset_history(
+        &self,
+        gm_identity: &ClockIdentity,
+    ) -> Result<Vec<(SystemTime, AnnouncedDataset)>> {
+        let tracker = self.tracker.read().await;
+        Ok(tracker.get_gm_dataset_history(gm_identity))
+    }
+
     async fn get_statistics(&self) -> Result<PtpStatistics> {
         let tracker = self.tracker.read().await;
         let hosts = tracker.get_hosts();
@@ -385,9 +917,119 @@ impl PtpService for PtpServiceImpl {
             version,
             local_ips: tracker.get_local_ips(),
             last_packet_timestamp: tracker.raw_socket_receiver.get_last_timestamp(),
+            capture_mode: tracker.raw_socket_receiver.capture_mode(),
+            filtered_packets: tracker.get_filtered_packet_count(),
+            filter: tracker.get_filter().to_string(),
+            interval_tolerance_percent: self.interval_tolerance_percent,
+            host_timeout: self.host_timeout,
+            segments: tracker.get_segment_statistics(),
+            upcoming_leap_second: tracker.get_upcoming_leap_seconds().into_iter().next(),
         })
     }
 
+    async fn get_domains(&self) -> Result<Vec<PtpDomainSummary>> {
+        let tracker = self.tracker.read().await;
+        let now = tracker.current_time();
+        let hosts = tracker.get_hosts();
+        let mut competing = tracker.get_competing_grandmasters();
+        let mut issues = tracker.get_time_property_issues();
+
+        let summaries = tracker
+            .get_segment_statistics()
+            .into_iter()
+            .map(|statistics| {
+                let segment = statistics.segment.clone();
+                let ports: Vec<&PtpPort> = hosts
+                    .iter()
+                    .flat_map(|h| h.ports.values())
+                    .filter(|port| port.segment.as_ref() == Some(&segment))
+                    .collect();
+                let dataset = |port: &PtpPort| match &port.state {
+                    PtpHostState::TimeTransmitter(s) => s.dataset,
+                    _ => None,
+                };
+
+                let ranked = tracker.get_ranked_transmitters(&segment);
+                let gm_port = ranked.iter().copied().find(|port| {
+                    matches!(&port.state, PtpHostState::TimeTransmitter(s) if s.is_bmca_winner)
+                });
+                let gm_dataset = gm_port.and_then(dataset);
+                // Relays of the grandmaster, e.g. boundary clocks, are no backups
+                let backup_candidates = ranked
+                    .iter()
+                    .filter(|port| {
+                        gm_dataset.is_none_or(|gm| {
+                            dataset(port).is_none_or(|d| d.gm_identity != gm.gm_identity)
+                        })
+                    })
+                    .map(|port| (port.port_identity, dataset(port)))
+                    .collect();
+
+                let advertised = |port: Option<&PtpPort>, message_type| {
+                    port.and_then(|p| p.message_intervals.get(&message_type))
+                        .and_then(|stats| stats.advertised)
+                };
+                let delay_mechanism = if ports.iter().any(|p| p.pdelay_req_count > 0) {
+                    Some(DelayMechanism::P2p)
+                } else if ports.iter().any(|p| p.delay_req_count > 0) {
+                    Some(DelayMechanism::E2e)
+                } else {
+                    None
+                };
+                let delay_req_interval = ports.iter().find_map(|port| {
+                    advertised(Some(port), PtpMessageType::DelayReq)
+                        .or_else(|| advertised(Some(port), PtpMessageType::PDelayReq))
+                });
+                let sync_interval = advertised(gm_port, PtpMessageType::Sync);
+                let major_sdo_id = gm_port
+                    .or_else(|| ports.first().copied())
+                    .and_then(|port| port.major_sdo_id);
+
+                let mut packet_rates = BTreeMap::new();
+                for port in &ports {
+                    for (message_type, stats) in &port.message_intervals {
+                        if let Some(rate) = stats.rate() {
+                            *packet_rates.entry(*message_type).or_insert(0.0) += rate;
+                        }
+                    }
+                }
+
+                PtpDomainSummary {
+                    grandmaster: gm_port.map(|port| port.port_identity),
+                    gm_dataset,
+                    backup_candidates,
+                    profile: PtpProfile::infer(
+                        &segment,
+                        major_sdo_id,
+                        delay_mechanism,
+                        sync_interval,
+                    ),
+                    delay_mechanism,
+                    announce_interval: advertised(gm_port, PtpMessageType::Announce),
+                    sync_interval,
+                    delay_req_interval,
+                    timescale: gm_port.and_then(|port| port.gm_offset.timescale),
+                    packet_rates,
+                    since_gm_change: tracker
+                        .get_gm_change_time(&segment)
+                        .map(|time| now.duration_since(time).unwrap_or_default()),
+                    competing_sources: competing
+                        .iter_mut()
+                        .find(|c| c.segment == segment)
+                        .map(|c| std::mem::take(&mut c.sources))
+                        .unwrap_or_default(),
+                    time_property_issues: issues
+                        .extract_if(.., |(issue_segment, _)| *issue_segment == segment)
+                        .map(|(_, issue)| issue)
+                        .collect(),
+                    segment,
+                    statistics,
+                }
+            })
+            .collect();
+        Ok(summaries)
+    }
+
     async fn subscribe_to_events(&self) -> Result<mpsc::Receiver<PtpEvent>> {
         let (tx, rx) = mpsc::channel(1000);
         let mut subscribers = self.event_subscribers.write().await;
@@ -400,8 +1042,11 @@ impl PtpService for PtpServiceImpl {
         tracker.clear_hosts();
         let mut previous_states = self.previous_states.write().await;
         previous_states.clear();
-        let mut domain_gms = self.domain_grandmasters.write().await;
-        domain_gms.clear();
+        let mut segment_gms = self.segment_grandmasters.write().await;
+        segment_gms.clear();
+        self.competing_sources.write().await.clear();
+        self.time_property_issues.write().await.clear();
+        self.loss_windows.write().await.clear();
         Ok(())
     }
 
@@ -422,4 +1067,148 @@ impl PtpService for PtpServiceImpl {
         tracker.set_max_packet_history(max_history);
         Ok(())
     }
+
+    async fn get_filter(&self) -> Result<PacketFilter> {
+        let tracker = self.tracker.read().await;
+        Ok(tracker.get_filter().clone())
+    }
+
+    async fn set_filter(&self, filter: PacketFilter) -> Result<()> {
+        let mut tracker = self.tracker.write().await;
+        tracker.set_filter(filter);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::RawPacket;
+
+    /// A Delay_Req of port 1 of the given clock, captured `secs` into the capture
+    fn delay_req(clock_id: u8, domain: u8, secs: u64) -> RawPacket {
+        let mut payload = vec![0u8; 44];
+        payload[0] = 0x01;
+        payload[1] = 0x02;
+        payload[2..4].copy_from_slice(&44u16.to_be_bytes());
+        payload[4] = domain;
+        payload[27] = clock_id;
+        payload[28..30].copy_from_slice(&1u16.to_be_bytes());
+        RawPacket {
+            timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + secs),
+            data: payload.clone(),
+            source_addr: None,
+            source_mac: None,
+            dest_addr: None,
+            dest_mac: None,
+            vlan_id: None,
+            ttl: None,
+            interface_name: "eth0".to_string(),
+            ptp_payload: payload,
+            tunnel: None,
+            redundancy: None,
+        }
+    }
+
+    fn clock_identity(clock_id: u8) -> ClockIdentity {
+        ClockIdentity {
+            clock_id: [0, 0, 0, 0, 0, 0, 0, clock_id],
+        }
+    }
+
+    /// A service with a 10s host timeout, fed by `capture`
+    async fn service() -> (PtpServiceImpl, mpsc::Receiver<PtpEvent>) {
+        let config = ServiceConfig {
+            host_timeout: Some(Duration::from_secs(10)),
+            ..ServiceConfig::default()
+        };
+        let service =
+            PtpServiceImpl::without_tasks(RawSocketReceiver::from_packets(Vec::new()), config)
+                .unwrap();
+        let events = service.subscribe_to_events().await.unwrap();
+        (service, events)
+    }
+
+    /// Process the packets as one capture batch and return the events it caused
+    async fn capture(
+        service: &PtpServiceImpl,
+        events: &mut mpsc::Receiver<PtpEvent>,
+        packets: Vec<RawPacket>,
+    ) -> Vec<PtpEvent> {
+        {
+            let mut tracker = service.tracker.write().await;
+            tracker.raw_socket_receiver = RawSocketReceiver::from_packets(packets);
+            tracker.scan_network().await;
+        }
+        service.detect_and_emit_changes().await.unwrap();
+        std::iter::from_fn(|| events.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_host_timeout_is_reported_once() {
+        let (service, mut events) = service().await;
+        let mut timeouts = Vec::new();
+        // Clock 2 keeps the capture time going while clock 1 is silent for 25s
+        for batch in [
+            vec![delay_req(1, 0, 0), delay_req(2, 0, 0)],
+            vec![delay_req(2, 0, 15)],
+            vec![delay_req(2, 0, 20)],
+            vec![delay_req(1, 0, 25), delay_req(2, 0, 25)],
+        ] {
+            timeouts.extend(
+                capture(&service, &mut events, batch)
+                    .await
+                    .into_iter()
+                    .filter(|event| {
+                        matches!(
+                            event,
+                            PtpEvent::HostTimeout { .. } | PtpEvent::HostRecovered { .. }
+                        )
+                    }),
+            );
+        }
+
+        assert!(matches!(
+            timeouts.as_slice(),
+            [
+                PtpEvent::HostTimeout {
+                    clock_identity: timed_out,
+                    last_seen_ago_secs: 15,
+                },
+                PtpEvent::HostRecovered {
+                    clock_identity: recovered,
+                    outage_secs: 25,
+                },
+            ] if *timed_out == clock_identity(1) && *recovered == clock_identity(1)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_domain_change_only_for_real_moves() {
+        let (service, mut events) = service().await;
+        let mut changes = Vec::new();
+        // Clock 1 takes part in domains 0 and 127 and stops sending in 127, clock 3 moves
+        // from domain 0 to 5
+        for batch in [
+            vec![delay_req(1, 0, 0), delay_req(1, 127, 0), delay_req(3, 0, 0)],
+            vec![delay_req(1, 0, 5), delay_req(1, 127, 5)],
+            vec![delay_req(1, 0, 20), delay_req(3, 5, 20)],
+        ] {
+            changes.extend(
+                capture(&service, &mut events, batch)
+                    .await
+                    .into_iter()
+                    .filter(|event| matches!(event, PtpEvent::DomainChange { .. })),
+            );
+        }
+
+        assert!(matches!(
+            changes.as_slice(),
+            [PtpEvent::DomainChange {
+                clock_identity: moved,
+                old_domain: 0,
+                new_domain: 5,
+            }] if *moved == clock_identity(3)
+        ));
+    }
 }
@@ -10,13 +10,19 @@ pub mod interface_monitor;
 pub use events::PtpEvent;
 pub use implementation::PtpServiceImpl;
 
-use crate::ptp::PtpHost;
-use crate::types::{ClockIdentity, ParsedPacket};
+use crate::filter::PacketFilter;
+use crate::ptp::{
+    AnnouncedDataset, DelayMechanism, ExpectedTimescale, PtpHost, PtpProfile, PtpSegment,
+    PtpTimescale, SegmentStatistics, SyncSource, TimePropertiesIssue, UpcomingLeapSecond,
+};
+use crate::source::CaptureMode;
+use crate::types::{ClockIdentity, ParsedPacket, PortIdentity, PtpLogInterval, PtpMessageType};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::collections::BTreeMap;
 use std::net::IpAddr;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::sync::mpsc;
 
 /// Statistics about the PTP monitoring service
@@ -34,6 +40,82 @@ pub struct PtpStatistics {
     pub version: String,
     pub local_ips: Vec<IpAddr>,
     pub last_packet_timestamp: Option<SystemTime>,
+    pub capture_mode: CaptureMode,
+    /// Packets dropped by the packet filter
+    pub filtered_packets: u64,
+    /// Active packet filter expression, empty if none
+    pub filter: String,
+    /// Allowed deviation of measured message intervals from the advertised ones, in percent
+    pub interval_tolerance_percent: f64,
+    /// Silence after which a host times out, None for the message-type-aware default
+    pub host_timeout: Option<Duration>,
+    /// Host and message counts per segment (interface, VLAN, domain and transport)
+    pub segments: Vec<SegmentStatistics>,
+    /// The soonest leap second announced by a grandmaster
+    pub upcoming_leap_second: Option<UpcomingLeapSecond>,
+}
+
+/// Overview of one PTP network (domain on an interface, VLAN and transport)
+#[derive(Debug, Clone)]
+pub struct PtpDomainSummary {
+    pub segment: PtpSegment,
+    /// Port of the BMCA winner and the dataset it announces
+    pub grandmaster: Option<PortIdentity>,
+    pub gm_dataset: Option<AnnouncedDataset>,
+    /// Transmitters announcing another grandmaster, best first by the BMCA
+    pub backup_candidates: Vec<(PortIdentity, Option<AnnouncedDataset>)>,
+    pub statistics: SegmentStatistics,
+    pub profile: PtpProfile,
+    pub delay_mechanism: Option<DelayMechanism>,
+    /// Advertised by the grandmaster port
+    pub announce_interval: Option<PtpLogInterval>,
+    pub sync_interval: Option<PtpLogInterval>,
+    /// Advertised by the first receiver sending delay requests
+    pub delay_req_interval: Option<PtpLogInterval>,
+    pub timescale: Option<PtpTimescale>,
+    /// Measured messages per second of all ports in the segment, per message type
+    pub packet_rates: BTreeMap<PtpMessageType, f64>,
+    /// Time since the current BMCA winner won
+    pub since_gm_change: Option<Duration>,
+    /// Unrelated Sync sources splitting the segment, empty if there is at most one
+    pub competing_sources: Vec<SyncSource>,
+    /// Disagreeing or unexpected UTC offset, leap flags and timescale
+    pub time_property_issues: Vec<TimePropertiesIssue>,
+}
+
+/// Settings for packet processing and the analysis events
+#[derive(Debug, Clone)]
+pub struct ServiceConfig {
+    pub filter: PacketFilter,
+    /// Sequence id loss, in percent, above which a `SequenceLoss` event is emitted
+    pub loss_threshold_percent: f64,
+    /// Allowed deviation of measured message intervals from the advertised ones, in percent
+    pub interval_tolerance_percent: f64,
+    /// Announce intervals without an Announce after which a transmitter is considered stale
+    pub announce_receipt_timeout: u8,
+    /// Silence after which a `HostTimeout` is emitted, None for the message-type-aware default
+    pub host_timeout: Option<Duration>,
+    /// Silence after which a host is removed, None to keep hosts forever
+    pub host_expiry: Option<Duration>,
+    /// UTC offset grandmasters on the PTP timescale should announce
+    pub expected_utc_offset: i16,
+    /// Timescale grandmasters should announce
+    pub expected_timescale: ExpectedTimescale,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            filter: PacketFilter::default(),
+            loss_threshold_percent: 1.0,
+            interval_tolerance_percent: 20.0,
+            announce_receipt_timeout: crate::ptp::DEFAULT_ANNOUNCE_RECEIPT_TIMEOUT,
+            host_timeout: None,
+            host_expiry: None,
+            expected_utc_offset: crate::ptp::DEFAULT_EXPECTED_UTC_OFFSET,
+            expected_timescale: ExpectedTimescale::default(),
+        }
+    }
 }
 
 /// Main service trait for PTP monitoring
@@ -50,9 +132,18 @@ pub trait PtpService: Send + Sync {
     async fn get_packet_history(&self, clock_identity: &ClockIdentity)
     -> Result<Vec<ParsedPacket>>;
 
+    /// Get the distinct datasets announced for a grandmaster, oldest first
+    async fn get_gm_dataset_history(
+        &self,
+        gm_identity: &ClockIdentity,
+    ) -> Result<Vec<(SystemTime, AnnouncedDataset)>>;
+
     /// Get current statistics
     async fn get_statistics(&self) -> Result<PtpStatistics>;
 
+    /// Get an overview of every domain, per segment
+    async fn get_domains(&self) -> Result<Vec<PtpDomainSummary>>;
+
     /// Subscribe to real-time events
     async fn subscribe_to_events(&self) -> Result<mpsc::Receiver<PtpEvent>>;
 
@@ -67,6 +158,13 @@ pub trait PtpService: Send + Sync {
     #[allow(dead_code)]
     async fn clear_all_packet_histories(&self) -> Result<()>;
 
+    /// Get the active packet filter
+    #[allow(dead_code)]
+    async fn get_filter(&self) -> Result<PacketFilter>;
+
+    /// Replace the packet filter; applies to packets received from now on
+    async fn set_filter(&self, filter: PacketFilter) -> Result<()>;
+
     /// Set maximum packet history size per host
     #[allow(dead_code)]
     async fn set_max_packet_history(&self, max_history: usize) -> Result<()>;
@@ -3,49 +3,197 @@
 //! This module implements packet capture using pnet for cross-platform
 //! promiscuous mode support. Works on Linux, macOS, and Windows.
 //! Supports both PTP over UDP (Layer 3) and gPTP over Ethernet (Layer 2).
+//!
+//! An unprivileged alternative binds ordinary UDP sockets to the PTP ports
+//! instead. It only sees PTP over UDP and cannot report link-layer fields.
 
+use crate::types::PtpTimestamp;
 use anyhow::Result;
-use pnet::datalink::{self, Channel, Config};
+use pnet::datalink::{self, Channel, Config, DataLinkReceiver};
 use pnet::packet::Packet;
-use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
+use pnet::packet::ethernet::{EtherType, EtherTypes, EthernetPacket};
 use pnet::packet::ip::IpNextHeaderProtocols;
 use pnet::packet::ipv4::Ipv4Packet;
 use pnet::packet::udp::UdpPacket;
 use socket2::{Domain, Protocol, Socket, Type};
 use std::io;
 use std::net::{IpAddr, Ipv4Addr};
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 
 const PTP_EVENT_PORT: u16 = 319;
 const PTP_GENERAL_PORT: u16 = 320;
 const PTP_MULTICAST_ADDR: &str = "224.0.1.129";
+/// Multicast group used for peer delay messages (IEEE 1588 Annex D)
+const PTP_PDELAY_MULTICAST_ADDR: &str = "224.0.0.107";
 /// gPTP (generalized Precision Time Protocol) EtherType for Layer 2 transport
 const GPTP_ETHERTYPE: u16 = 0x88f7;
 /// gPTP multicast MAC address (IEEE 802.1AS)
 const GPTP_MULTICAST_MAC: [u8; 6] = [0x01, 0x80, 0xc2, 0x00, 0x00, 0x0e];
+/// HSR tag EtherType (IEC 62439-3)
+const HSR_ETHERTYPE: u16 = 0x892f;
+/// Suffix of the PRP redundancy control trailer (IEC 62439-3)
+const PRP_SUFFIX: u16 = 0x88fb;
+/// VXLAN UDP destination port (RFC 7348)
+const VXLAN_PORT: u16 = 4789;
+/// GRE protocol type for transparent Ethernet bridging
+const GRE_PROTO_TEB: u16 = 0x6558;
+/// GRE protocol type for ERSPAN type II
+const GRE_PROTO_ERSPAN_II: u16 = 0x88be;
+/// GRE protocol type for ERSPAN type III
+const GRE_PROTO_ERSPAN_III: u16 = 0x22eb;
+/// Maximum number of nested tunnel headers that are decapsulated
+const MAX_TUNNEL_DEPTH: u8 = 4;
 
 type InterfaceSourceType = (String, Option<Ipv4Addr>, Option<u16>);
 
+/// How packets are obtained, which determines which `RawPacket` fields are available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureMode {
+    /// Promiscuous capture of complete Ethernet frames (requires root)
+    #[default]
+    Raw,
+    /// Ordinary UDP sockets bound to the PTP ports (no link-layer information)
+    Udp,
+    /// Frames read from a pcap file
+    Pcap,
+}
+
+impl CaptureMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureMode::Raw => "raw",
+            CaptureMode::Udp => "udp",
+            CaptureMode::Pcap => "pcap",
+        }
+    }
+
+    /// Packet fields that can never be populated in this mode
+    pub fn unavailable_fields(&self) -> &'static [&'static str] {
+        match self {
+            CaptureMode::Udp => &["Source MAC", "Dest MAC", "VLAN ID", "gPTP (Layer 2)"],
+            CaptureMode::Raw | CaptureMode::Pcap => &[],
+        }
+    }
+}
+
+/// Encapsulation a mirrored packet was received in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TunnelType {
+    Gre,
+    ErspanII,
+    ErspanIII,
+    Vxlan,
+}
+
+impl std::fmt::Display for TunnelType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TunnelType::Gre => write!(f, "GRE"),
+            TunnelType::ErspanII => write!(f, "ERSPAN II"),
+            TunnelType::ErspanIII => write!(f, "ERSPAN III"),
+            TunnelType::Vxlan => write!(f, "VXLAN"),
+        }
+    }
+}
+
+/// Outermost tunnel a packet was decapsulated from, identifying the mirror session
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TunnelInfo {
+    pub tunnel_type: TunnelType,
+    /// Outer IP source address, i.e. the switch sending the mirrored traffic
+    pub source: IpAddr,
+    /// ERSPAN session ID, VXLAN VNI or GRE key
+    pub session_id: Option<u32>,
+}
+
+impl std::fmt::Display for TunnelInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.session_id {
+            Some(id) => write!(
+                f,
+                "{} from {} (session {})",
+                self.tunnel_type, self.source, id
+            ),
+            None => write!(f, "{} from {}", self.tunnel_type, self.source),
+        }
+    }
+}
+
+/// Redundancy protocol a frame was tagged with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RedundancyProtocol {
+    /// Parallel Redundancy Protocol, identified by a trailer
+    Prp,
+    /// High-availability Seamless Redundancy, identified by a tag after the MAC header
+    Hsr,
+}
+
+impl std::fmt::Display for RedundancyProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedundancyProtocol::Prp => write!(f, "PRP"),
+            RedundancyProtocol::Hsr => write!(f, "HSR"),
+        }
+    }
+}
+
+/// Which of the two redundant LANs (or HSR ring directions) a frame travelled over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RedundancyLan {
+    A,
+    B,
+}
+
+impl std::fmt::Display for RedundancyLan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedundancyLan::A => write!(f, "A"),
+            RedundancyLan::B => write!(f, "B"),
+        }
+    }
+}
+
+/// PRP trailer or HSR tag of a frame. Both copies of a frame share the sequence number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedundancyTag {
+    pub protocol: RedundancyProtocol,
+    pub lan: RedundancyLan,
+    pub sequence: u16,
+}
+
+impl std::fmt::Display for RedundancyTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} LAN {} (seq {})",
+            self.protocol, self.lan, self.sequence
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RawPacket {
     pub timestamp: std::time::SystemTime,
     pub data: Vec<u8>,
     pub source_addr: Option<std::net::SocketAddr>,
-    pub source_mac: [u8; 6],
+    pub source_mac: Option<[u8; 6]>,
     pub dest_addr: Option<std::net::SocketAddr>,
-    pub dest_mac: [u8; 6],
+    pub dest_mac: Option<[u8; 6]>,
     pub vlan_id: Option<u16>,
     pub ttl: Option<u8>,
     pub interface_name: String,
     pub ptp_payload: Vec<u8>,
+    pub tunnel: Option<TunnelInfo>,
+    pub redundancy: Option<RedundancyTag>,
 }
 
 pub enum PacketSource {
     Socket {
         receiver: mpsc::UnboundedReceiver<RawPacket>,
         interfaces: Vec<InterfaceSourceType>,
+        mode: CaptureMode,
         _multicast_sockets: Vec<Socket>,
     },
     Pcap {
@@ -86,6 +234,26 @@ impl RawSocketReceiver {
         }
     }
 
+    pub fn capture_mode(&self) -> CaptureMode {
+        match &self.source {
+            PacketSource::Socket { mode, .. } => *mode,
+            PacketSource::Pcap { .. } => CaptureMode::Pcap,
+        }
+    }
+
+    /// Replay the given packets, as if they had been read from a pcap file
+    #[cfg(test)]
+    pub fn from_packets(packets: Vec<RawPacket>) -> Self {
+        let last_timestamp = packets.iter().map(|p| p.timestamp).max();
+        Self {
+            source: PacketSource::Pcap {
+                packets,
+                current_index: 0,
+                last_timestamp,
+            },
+        }
+    }
+
     pub fn get_last_timestamp(&self) -> Option<SystemTime> {
         match &self.source {
             PacketSource::Socket { .. } => None,
@@ -188,12 +356,228 @@ fn join_multicast_group(interface_name: &str, interface_addr: Ipv4Addr) -> Resul
     Ok(socket)
 }
 
+/// Inner Ethernet frame extracted from a tunnel header
+struct Decapsulated<'a> {
+    frame: &'a [u8],
+    tunnel_type: TunnelType,
+    session_id: Option<u32>,
+    /// VLAN of the original frame as reported by the ERSPAN header
+    vlan_id: Option<u16>,
+    hardware_timestamp: Option<SystemTime>,
+}
+
+/// Parse an ERSPAN type II header (8 bytes)
+fn decapsulate_erspan_ii(data: &[u8]) -> Option<Decapsulated<'_>> {
+    let header = data.get(..8)?;
+    if header[0] >> 4 != 1 {
+        return None;
+    }
+
+    let vlan = u16::from_be_bytes([header[0], header[1]]) & 0x0fff;
+    let session_id = u16::from_be_bytes([header[2], header[3]]) & 0x03ff;
+
+    Some(Decapsulated {
+        frame: &data[8..],
+        tunnel_type: TunnelType::ErspanII,
+        session_id: Some(session_id as u32),
+        vlan_id: (vlan != 0).then_some(vlan),
+        hardware_timestamp: None,
+    })
+}
+
+/// Parse an ERSPAN type III header (12 bytes plus optional 8-byte platform subheader)
+///
+/// The header only carries the lower 32 bits of the timestamp. It is only used when
+/// the granularity is IEEE 1588 (seconds in the platform subheader, nanoseconds in
+/// the header), which platform ID 0x03 provides.
+fn decapsulate_erspan_iii(data: &[u8]) -> Option<Decapsulated<'_>> {
+    let header = data.get(..12)?;
+    if header[0] >> 4 != 2 {
+        return None;
+    }
+
+    let vlan = u16::from_be_bytes([header[0], header[1]]) & 0x0fff;
+    let session_id = u16::from_be_bytes([header[2], header[3]]) & 0x03ff;
+    let timestamp_low = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let flags = u16::from_be_bytes([header[10], header[11]]);
+    let granularity = (flags >> 1) & 0x03;
+    let has_subheader = flags & 0x01 != 0;
+
+    let mut offset = 12;
+    let mut hardware_timestamp = None;
+    if has_subheader {
+        let subheader = data.get(12..20)?;
+        let platform_id = subheader[0] >> 2;
+        if granularity == 0b11 && platform_id == 0x03 {
+            let seconds =
+                u32::from_be_bytes([subheader[4], subheader[5], subheader[6], subheader[7]]);
+            hardware_timestamp = Some(
+                PtpTimestamp {
+                    seconds: seconds as u64,
+                    nanoseconds: timestamp_low,
+                }
+                .to_system_time(),
+            );
+        }
+        offset = 20;
+    }
+
+    Some(Decapsulated {
+        frame: &data[offset..],
+        tunnel_type: TunnelType::ErspanIII,
+        session_id: Some(session_id as u32),
+        vlan_id: (vlan != 0).then_some(vlan),
+        hardware_timestamp,
+    })
+}
+
+/// Parse a GRE header and any ERSPAN header carried inside it
+fn decapsulate_gre(data: &[u8]) -> Option<Decapsulated<'_>> {
+    let header = data.get(..4)?;
+    let flags = u16::from_be_bytes([header[0], header[1]]);
+    let protocol = u16::from_be_bytes([header[2], header[3]]);
+
+    let mut offset = 4;
+    if flags & 0x8000 != 0 {
+        // Checksum and reserved
+        offset += 4;
+    }
+    let key = if flags & 0x2000 != 0 {
+        let key = data.get(offset..offset + 4)?;
+        offset += 4;
+        Some(u32::from_be_bytes([key[0], key[1], key[2], key[3]]))
+    } else {
+        None
+    };
+    if flags & 0x1000 != 0 {
+        // Sequence number
+        offset += 4;
+    }
+
+    let inner = data.get(offset..)?;
+    match protocol {
+        GRE_PROTO_TEB => Some(Decapsulated {
+            frame: inner,
+            tunnel_type: TunnelType::Gre,
+            session_id: key,
+            vlan_id: None,
+            hardware_timestamp: None,
+        }),
+        GRE_PROTO_ERSPAN_II => decapsulate_erspan_ii(inner),
+        GRE_PROTO_ERSPAN_III => decapsulate_erspan_iii(inner),
+        _ => None,
+    }
+}
+
+/// Parse a VXLAN header (8 bytes)
+fn decapsulate_vxlan(data: &[u8]) -> Option<Decapsulated<'_>> {
+    let header = data.get(..8)?;
+    // The I flag indicates a valid VNI
+    if header[0] & 0x08 == 0 {
+        return None;
+    }
+    let vni = u32::from_be_bytes([0, header[4], header[5], header[6]]);
+
+    Some(Decapsulated {
+        frame: &data[8..],
+        tunnel_type: TunnelType::Vxlan,
+        session_id: Some(vni),
+        vlan_id: None,
+        hardware_timestamp: None,
+    })
+}
+
+/// Process the Ethernet frame found inside a tunnel, recording where it came from.
+fn process_tunneled_packet(
+    outer_data: &[u8],
+    outer_source: Ipv4Addr,
+    decapsulated: Decapsulated<'_>,
+    interface_name: &str,
+    timestamp: SystemTime,
+    depth: u8,
+) -> Option<RawPacket> {
+    if depth >= MAX_TUNNEL_DEPTH {
+        return None;
+    }
+
+    // The native VLAN of the capture interface doesn't apply to the mirrored frame
+    let mut packet = process_frame(
+        decapsulated.frame,
+        interface_name,
+        None,
+        timestamp,
+        depth + 1,
+    )?;
+
+    packet.data = outer_data.to_vec();
+    packet.vlan_id = packet.vlan_id.or(decapsulated.vlan_id);
+    packet.tunnel = Some(TunnelInfo {
+        tunnel_type: decapsulated.tunnel_type,
+        source: IpAddr::V4(outer_source),
+        session_id: decapsulated.session_id,
+    });
+    if let Some(timestamp) = decapsulated.hardware_timestamp {
+        packet.timestamp = timestamp;
+    }
+
+    Some(packet)
+}
+
+/// `timestamp` is the capture time of the frame, used unless the frame carries a
+/// more precise hardware timestamp (ERSPAN III)
 fn process_ethernet_packet(
     packet_data: &[u8],
     interface_name: &str,
     native_vlan_id: Option<u16>,
+    timestamp: SystemTime,
 ) -> Option<RawPacket> {
-    let ethernet = EthernetPacket::new(packet_data)?;
+    process_frame(packet_data, interface_name, native_vlan_id, timestamp, 0)
+}
+
+/// Split off a PRP redundancy control trailer (sequence, LAN id and LSDU size, suffix)
+fn split_prp_trailer(frame: &[u8]) -> (&[u8], Option<RedundancyTag>) {
+    // Ethernet header plus the 6-byte trailer
+    if frame.len() < 14 + 6 {
+        return (frame, None);
+    }
+
+    let (body, trailer) = frame.split_at(frame.len() - 6);
+    if u16::from_be_bytes([trailer[4], trailer[5]]) != PRP_SUFFIX {
+        return (frame, None);
+    }
+
+    let lan = match trailer[2] >> 4 {
+        0xa => RedundancyLan::A,
+        0xb => RedundancyLan::B,
+        _ => return (frame, None),
+    };
+
+    // The LSDU size covers everything after the Ethernet header, the trailer included.
+    // A payload that merely ends in the suffix bytes doesn't match it.
+    let lsdu_size = u16::from_be_bytes([trailer[2], trailer[3]]) & 0x0fff;
+    if usize::from(lsdu_size) != frame.len() - 14 {
+        return (frame, None);
+    }
+
+    (
+        body,
+        Some(RedundancyTag {
+            protocol: RedundancyProtocol::Prp,
+            lan,
+            sequence: u16::from_be_bytes([trailer[0], trailer[1]]),
+        }),
+    )
+}
+
+fn process_frame(
+    packet_data: &[u8],
+    interface_name: &str,
+    native_vlan_id: Option<u16>,
+    timestamp: SystemTime,
+    depth: u8,
+) -> Option<RawPacket> {
+    let (frame, mut redundancy) = split_prp_trailer(packet_data);
+    let ethernet = EthernetPacket::new(frame)?;
 
     let mut vlan_id: Option<u16> = None;
     let mut payload_data = ethernet.payload();
@@ -213,6 +597,8 @@ fn process_ethernet_packet(
         } else if inner_ethertype_val == 0x8100 {
             // Double VLAN tag (QinQ) - skip inner VLAN tag
             EtherTypes::Vlan
+        } else if inner_ethertype_val == HSR_ETHERTYPE {
+            EtherType(HSR_ETHERTYPE)
         } else {
             return None; // Only handle IPv4 for now
         };
@@ -229,6 +615,8 @@ fn process_ethernet_packet(
             let inner_inner_ethertype_val = u16::from_be_bytes([payload_data[2], payload_data[3]]);
             ethertype = if inner_inner_ethertype_val == 0x0800 {
                 EtherTypes::Ipv4
+            } else if inner_inner_ethertype_val == HSR_ETHERTYPE {
+                EtherType(HSR_ETHERTYPE)
             } else {
                 return None; // Only handle IPv4 for now
             };
@@ -237,6 +625,22 @@ fn process_ethernet_packet(
         }
     }
 
+    // Handle the HSR tag (path id and LSDU size, sequence number, encapsulated EtherType)
+    if ethertype.0 == HSR_ETHERTYPE {
+        let tag = payload_data.get(..6)?;
+        redundancy = Some(RedundancyTag {
+            protocol: RedundancyProtocol::Hsr,
+            lan: if (tag[0] >> 4) & 0x01 == 0 {
+                RedundancyLan::A
+            } else {
+                RedundancyLan::B
+            },
+            sequence: u16::from_be_bytes([tag[2], tag[3]]),
+        });
+        ethertype = EtherType(u16::from_be_bytes([tag[4], tag[5]]));
+        payload_data = &payload_data[6..];
+    }
+
     // Check if this is gPTP (Layer 2) or PTP over UDP (Layer 3)
     if ethertype.0 == GPTP_ETHERTYPE {
         // Handle gPTP (IEEE 802.1AS - Layer 2 transport)
@@ -259,21 +663,36 @@ fn process_ethernet_packet(
         let ptp_payload = payload_data.to_vec();
 
         Some(RawPacket {
-            timestamp: SystemTime::now(),
+            timestamp,
             data: packet_data.to_vec(),
             source_addr,
-            source_mac,
+            source_mac: Some(source_mac),
             dest_addr,
-            dest_mac,
+            dest_mac: Some(dest_mac),
             vlan_id: vlan_id.or(native_vlan_id),
             ttl: None, // No TTL in Layer 2
             interface_name: interface_name.to_string(),
             ptp_payload,
+            tunnel: None,
+            redundancy,
         })
     } else if ethertype == EtherTypes::Ipv4 {
         // Handle PTP over UDP (existing code)
         let ipv4_packet = Ipv4Packet::new(payload_data)?;
 
+        // Mirrored traffic is decapsulated recursively
+        if ipv4_packet.get_next_level_protocol() == IpNextHeaderProtocols::Gre {
+            let decapsulated = decapsulate_gre(ipv4_packet.payload())?;
+            return process_tunneled_packet(
+                packet_data,
+                ipv4_packet.get_source(),
+                decapsulated,
+                interface_name,
+                timestamp,
+                depth,
+            );
+        }
+
         // Check if this is UDP
         if ipv4_packet.get_next_level_protocol() != IpNextHeaderProtocols::Udp {
             return None;
@@ -281,6 +700,18 @@ fn process_ethernet_packet(
 
         let udp_packet = UdpPacket::new(ipv4_packet.payload())?;
 
+        if udp_packet.get_destination() == VXLAN_PORT {
+            let decapsulated = decapsulate_vxlan(udp_packet.payload())?;
+            return process_tunneled_packet(
+                packet_data,
+                ipv4_packet.get_source(),
+                decapsulated,
+                interface_name,
+                timestamp,
+                depth,
+            );
+        }
+
         // Filter for PTP ports
         let dest_port = udp_packet.get_destination();
         if dest_port != PTP_EVENT_PORT && dest_port != PTP_GENERAL_PORT {
@@ -307,16 +738,18 @@ fn process_ethernet_packet(
         let ptp_payload = udp_packet.payload().to_vec();
 
         Some(RawPacket {
-            timestamp: SystemTime::now(),
+            timestamp,
             data: packet_data.to_vec(),
             source_addr,
-            source_mac,
+            source_mac: Some(source_mac),
             dest_addr,
-            dest_mac,
+            dest_mac: Some(dest_mac),
             vlan_id: vlan_id.or(native_vlan_id),
             ttl,
             interface_name: interface_name.to_string(),
             ptp_payload,
+            tunnel: None,
+            redundancy,
         })
     } else {
         // Not PTP or gPTP
@@ -324,12 +757,11 @@ fn process_ethernet_packet(
     }
 }
 
-async fn capture_on_interface(
-    interface_name: String,
-    native_vlan_id: Option<u16>,
-    sender: mpsc::UnboundedSender<RawPacket>,
-    _multicast_socket: Socket,
-) -> Result<()> {
+/// Open a datalink channel on the given interface.
+///
+/// This is the step that requires elevated privileges, so it is done before
+/// `create_raw_socket_receiver` returns and privileges can be dropped.
+fn open_datalink_channel(interface_name: &str) -> Result<Box<dyn DataLinkReceiver>> {
     // Find the interface
     let interface = datalink::interfaces()
         .into_iter()
@@ -338,33 +770,36 @@ async fn capture_on_interface(
 
     // Create datalink channel
     let config = Config::default();
-    let (_, mut rx) = match datalink::channel(&interface, config) {
-        Ok(Channel::Ethernet(tx, rx)) => (tx, rx),
-        Ok(_) => {
-            return Err(anyhow::anyhow!(
-                "Unsupported channel type for interface {}",
-                interface_name
-            ));
-        }
-        Err(e) => {
-            eprintln!(
-                "Failed to open datalink channel on interface {}: {}",
-                interface_name, e
-            );
-            return Err(anyhow::anyhow!(
-                "Failed to open datalink channel on {}: {}",
-                interface_name,
-                e
-            ));
-        }
-    };
+    match datalink::channel(&interface, config) {
+        Ok(Channel::Ethernet(_, rx)) => Ok(rx),
+        Ok(_) => Err(anyhow::anyhow!(
+            "Unsupported channel type for interface {}",
+            interface_name
+        )),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to open datalink channel on {}: {}",
+            interface_name,
+            e
+        )),
+    }
+}
 
+async fn capture_on_interface(
+    interface_name: String,
+    native_vlan_id: Option<u16>,
+    mut rx: Box<dyn DataLinkReceiver>,
+    sender: mpsc::UnboundedSender<RawPacket>,
+    _multicast_socket: Socket,
+) -> Result<()> {
     loop {
         match rx.next() {
             Ok(packet_data) => {
-                if let Some(raw_packet) =
-                    process_ethernet_packet(packet_data, &interface_name, native_vlan_id)
-                    && sender.send(raw_packet).is_err()
+                if let Some(raw_packet) = process_ethernet_packet(
+                    packet_data,
+                    &interface_name,
+                    native_vlan_id,
+                    SystemTime::now(),
+                ) && sender.send(raw_packet).is_err()
                 {
                     // Receiver has been dropped, exit the loop
                     break;
@@ -383,7 +818,7 @@ async fn capture_on_interface(
     Ok(())
 }
 
-pub async fn create_raw_socket_receiver(ifnames: &[String]) -> Result<RawSocketReceiver> {
+fn resolve_target_interfaces(ifnames: &[String]) -> Result<Vec<InterfaceSourceType>> {
     // Get interfaces to monitor
     let target_interfaces = if ifnames.is_empty() {
         // Default to all available interfaces
@@ -414,6 +849,12 @@ pub async fn create_raw_socket_receiver(ifnames: &[String]) -> Result<RawSocketR
         ));
     }
 
+    Ok(target_interfaces)
+}
+
+pub async fn create_raw_socket_receiver(ifnames: &[String]) -> Result<RawSocketReceiver> {
+    let target_interfaces = resolve_target_interfaces(ifnames)?;
+
     println!(
         "Starting live capture on: {}",
         target_interfaces
@@ -469,6 +910,14 @@ pub async fn create_raw_socket_receiver(ifnames: &[String]) -> Result<RawSocketR
             .unwrap()
         };
 
+        let rx = match open_datalink_channel(interface_name) {
+            Ok(rx) => rx,
+            Err(e) => {
+                eprintln!("Packet capture error on {}: {}", interface_name, e);
+                continue;
+            }
+        };
+
         tokio::spawn(async move {
             // Stagger startup to reduce resource contention
             tokio::time::sleep(Duration::from_millis(200)).await;
@@ -476,6 +925,7 @@ pub async fn create_raw_socket_receiver(ifnames: &[String]) -> Result<RawSocketR
             if let Err(e) = capture_on_interface(
                 interface_name_clone.clone(),
                 native_vlan_id_clone,
+                rx,
                 sender_clone,
                 multicast_socket,
             )
@@ -490,11 +940,314 @@ pub async fn create_raw_socket_receiver(ifnames: &[String]) -> Result<RawSocketR
         source: PacketSource::Socket {
             receiver,
             interfaces: target_interfaces,
+            mode: CaptureMode::Raw,
             _multicast_sockets: multicast_sockets,
         },
     })
 }
 
+/// Bind an unprivileged UDP socket to one of the PTP ports on all addresses.
+///
+/// Both SO_REUSEADDR and SO_REUSEPORT are set so that a PTP daemon running on
+/// the same host keeps receiving its traffic.
+fn bind_ptp_udp_socket(port: u16) -> Result<Socket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsRawFd;
+
+        let enable: libc::c_int = 1;
+        // SAFETY: the fd is valid for the lifetime of `socket` and the option value
+        // is a properly sized c_int
+        let ret = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_REUSEPORT,
+                &enable as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if ret != 0 {
+            return Err(anyhow::anyhow!(
+                "Failed to set SO_REUSEPORT on UDP port {}: {}",
+                port,
+                io::Error::last_os_error()
+            ));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::fd::AsRawFd;
+
+        // Ask the kernel to report the receiving interface, destination address and TTL
+        let enable: libc::c_int = 1;
+        for option in [libc::IP_PKTINFO, libc::IP_RECVTTL] {
+            // SAFETY: see above
+            let ret = unsafe {
+                libc::setsockopt(
+                    socket.as_raw_fd(),
+                    libc::IPPROTO_IP,
+                    option,
+                    &enable as *const libc::c_int as *const libc::c_void,
+                    std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+                )
+            };
+            if ret != 0 {
+                return Err(anyhow::anyhow!(
+                    "Failed to enable packet info on UDP port {}: {}",
+                    port,
+                    io::Error::last_os_error()
+                ));
+            }
+        }
+    }
+
+    let bind_addr =
+        std::net::SocketAddr::V4(std::net::SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port));
+    socket.bind(&bind_addr.into()).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to bind UDP port {} (needs CAP_NET_BIND_SERVICE or \
+             net.ipv4.ip_unprivileged_port_start <= {}): {}",
+            port,
+            PTP_EVENT_PORT,
+            e
+        )
+    })?;
+
+    Ok(socket)
+}
+
+/// Metadata for a datagram received on a UDP capture socket
+struct UdpDatagramInfo {
+    len: usize,
+    source_addr: Option<std::net::SocketAddr>,
+    dest_ip: Option<Ipv4Addr>,
+    interface_name: Option<String>,
+    ttl: Option<u8>,
+}
+
+#[cfg(target_os = "linux")]
+fn recv_udp_datagram(socket: &Socket, buf: &mut [u8]) -> io::Result<UdpDatagramInfo> {
+    use std::os::fd::AsRawFd;
+
+    let mut src: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    // Room for IP_PKTINFO and IP_TTL control messages
+    let mut control = [0u64; 16];
+
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_name = &mut src as *mut libc::sockaddr_in as *mut libc::c_void;
+    msg.msg_namelen = std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = std::mem::size_of_val(&control) as _;
+
+    // SAFETY: all pointers in `msg` reference live buffers of the advertised sizes
+    let len = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, 0) };
+    if len < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let source_addr = (src.sin_family == libc::AF_INET as libc::sa_family_t).then(|| {
+        std::net::SocketAddr::V4(std::net::SocketAddrV4::new(
+            Ipv4Addr::from(u32::from_be(src.sin_addr.s_addr)),
+            u16::from_be(src.sin_port),
+        ))
+    });
+
+    let mut info = UdpDatagramInfo {
+        len: len as usize,
+        source_addr,
+        dest_ip: None,
+        interface_name: None,
+        ttl: None,
+    };
+
+    // SAFETY: the CMSG_* helpers only walk the control buffer filled in by recvmsg
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            let header = &*cmsg;
+            if header.cmsg_level == libc::IPPROTO_IP && header.cmsg_type == libc::IP_PKTINFO {
+                let pktinfo =
+                    std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::in_pktinfo);
+                info.dest_ip = Some(Ipv4Addr::from(u32::from_be(pktinfo.ipi_addr.s_addr)));
+
+                let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+                if !libc::if_indextoname(pktinfo.ipi_ifindex as libc::c_uint, name.as_mut_ptr())
+                    .is_null()
+                {
+                    info.interface_name = Some(
+                        std::ffi::CStr::from_ptr(name.as_ptr())
+                            .to_string_lossy()
+                            .into_owned(),
+                    );
+                }
+            } else if header.cmsg_level == libc::IPPROTO_IP && header.cmsg_type == libc::IP_TTL {
+                let ttl = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::c_int);
+                info.ttl = u8::try_from(ttl).ok();
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    Ok(info)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn recv_udp_datagram(socket: &Socket, buf: &mut [u8]) -> io::Result<UdpDatagramInfo> {
+    // SAFETY: MaybeUninit<u8> has the same layout as u8 and recv_from only writes to it
+    let uninit = unsafe {
+        std::slice::from_raw_parts_mut(
+            buf.as_mut_ptr() as *mut std::mem::MaybeUninit<u8>,
+            buf.len(),
+        )
+    };
+    let (len, addr) = socket.recv_from(uninit)?;
+
+    Ok(UdpDatagramInfo {
+        len,
+        source_addr: addr.as_socket(),
+        dest_ip: None,
+        interface_name: None,
+        ttl: None,
+    })
+}
+
+fn capture_on_udp_socket(
+    socket: Socket,
+    port: u16,
+    interfaces: Vec<InterfaceSourceType>,
+    sender: mpsc::UnboundedSender<RawPacket>,
+) {
+    let mut buf = [0u8; 2048];
+
+    loop {
+        let info = match recv_udp_datagram(&socket, &mut buf) {
+            Ok(info) => info,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                eprintln!("Error receiving on UDP port {}: {}", port, e);
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                continue;
+            }
+        };
+
+        // The socket is bound to all addresses, so drop traffic from interfaces
+        // that were not selected. Without IP_PKTINFO everything is accepted.
+        let native_vlan_id = match &info.interface_name {
+            Some(name) => match interfaces.iter().find(|(ifname, _, _)| ifname == name) {
+                Some((_, _, vlan_id)) => *vlan_id,
+                None => continue,
+            },
+            None => None,
+        };
+
+        let payload = buf[..info.len].to_vec();
+        let raw_packet = RawPacket {
+            timestamp: SystemTime::now(),
+            data: payload.clone(),
+            source_addr: info.source_addr,
+            source_mac: None,
+            dest_addr: info
+                .dest_ip
+                .map(|ip| std::net::SocketAddr::V4(std::net::SocketAddrV4::new(ip, port))),
+            dest_mac: None,
+            vlan_id: native_vlan_id,
+            ttl: info.ttl,
+            interface_name: info.interface_name.unwrap_or_else(|| "udp".to_string()),
+            ptp_payload: payload,
+            tunnel: None,
+            redundancy: None,
+        };
+
+        if sender.send(raw_packet).is_err() {
+            // Receiver has been dropped, exit the loop
+            break;
+        }
+    }
+}
+
+/// Create a receiver that uses ordinary UDP sockets instead of a raw capture.
+///
+/// This does not require root, but only sees PTP over UDP addressed to this host
+/// (multicast or unicast), and cannot report MAC addresses or VLAN tags.
+pub async fn create_udp_socket_receiver(ifnames: &[String]) -> Result<RawSocketReceiver> {
+    let target_interfaces = resolve_target_interfaces(ifnames)?;
+
+    println!(
+        "Starting UDP socket capture on: {}",
+        target_interfaces
+            .iter()
+            .map(|(name, _, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    let multicast_groups: [Ipv4Addr; 2] = [
+        PTP_MULTICAST_ADDR.parse()?,
+        PTP_PDELAY_MULTICAST_ADDR.parse()?,
+    ];
+
+    let mut sockets = Vec::new();
+    for port in [PTP_EVENT_PORT, PTP_GENERAL_PORT] {
+        let socket = bind_ptp_udp_socket(port)?;
+
+        // Memberships are per socket, so join on both ports
+        for (interface_name, interface_addr, _) in &target_interfaces {
+            let Some(interface_addr) = interface_addr else {
+                eprintln!(
+                    "Warning: {} has no IPv4 address, cannot join multicast groups",
+                    interface_name
+                );
+                continue;
+            };
+
+            for group in &multicast_groups {
+                if let Err(e) = socket.join_multicast_v4(group, interface_addr) {
+                    eprintln!(
+                        "Warning: Could not join multicast group {} on {}: {}",
+                        group, interface_name, e
+                    );
+                }
+            }
+        }
+
+        let socket_clone = socket.try_clone()?;
+        let interfaces = target_interfaces.clone();
+        let sender_clone = sender.clone();
+        tokio::task::spawn_blocking(move || {
+            capture_on_udp_socket(socket_clone, port, interfaces, sender_clone);
+        });
+
+        sockets.push(socket);
+    }
+
+    println!(
+        "Joined PTP multicast groups {} and {} on UDP ports {} and {}",
+        PTP_MULTICAST_ADDR, PTP_PDELAY_MULTICAST_ADDR, PTP_EVENT_PORT, PTP_GENERAL_PORT
+    );
+
+    Ok(RawSocketReceiver {
+        source: PacketSource::Socket {
+            receiver,
+            interfaces: target_interfaces,
+            mode: CaptureMode::Udp,
+            _multicast_sockets: sockets,
+        },
+    })
+}
+
 pub async fn create_pcap_receiver(pcap_path: &str) -> Result<RawSocketReceiver> {
     use pcap_file::pcap::PcapReader;
     use pcap_file::pcapng::PcapNgReader;
@@ -513,7 +1266,10 @@ pub async fn create_pcap_receiver(pcap_path: &str) -> Result<RawSocketReceiver>
             match block {
                 Ok(pcap_file::pcapng::Block::EnhancedPacket(epb)) => {
                     let packet_data = epb.data;
-                    if let Some(raw_packet) = process_ethernet_packet(&packet_data, "pcap", None) {
+                    let timestamp = UNIX_EPOCH + epb.timestamp;
+                    if let Some(raw_packet) =
+                        process_ethernet_packet(&packet_data, "pcap", None, timestamp)
+                    {
                         if last_timestamp.is_none()
                             || raw_packet.timestamp > last_timestamp.unwrap()
                         {
@@ -523,8 +1279,12 @@ pub async fn create_pcap_receiver(pcap_path: &str) -> Result<RawSocketReceiver>
                     }
                 }
                 Ok(pcap_file::pcapng::Block::SimplePacket(spb)) => {
+                    // Simple packet blocks carry no timestamp, reuse the previous one
                     let packet_data = spb.data;
-                    if let Some(raw_packet) = process_ethernet_packet(&packet_data, "pcap", None) {
+                    let timestamp = last_timestamp.unwrap_or(UNIX_EPOCH);
+                    if let Some(raw_packet) =
+                        process_ethernet_packet(&packet_data, "pcap", None, timestamp)
+                    {
                         if last_timestamp.is_none()
                             || raw_packet.timestamp > last_timestamp.unwrap()
                         {
@@ -554,7 +1314,10 @@ pub async fn create_pcap_receiver(pcap_path: &str) -> Result<RawSocketReceiver>
             match pkt {
                 Ok(packet) => {
                     let packet_data = packet.data;
-                    if let Some(raw_packet) = process_ethernet_packet(&packet_data, "pcap", None) {
+                    let timestamp = UNIX_EPOCH + packet.timestamp;
+                    if let Some(raw_packet) =
+                        process_ethernet_packet(&packet_data, "pcap", None, timestamp)
+                    {
                         if last_timestamp.is_none()
                             || raw_packet.timestamp > last_timestamp.unwrap()
                         {
@@ -585,3 +1348,185 @@ pub async fn create_pcap_receiver(pcap_path: &str) -> Result<RawSocketReceiver>
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INNER_SOURCE: [u8; 4] = [192, 168, 1, 10];
+    const OUTER_SOURCE: [u8; 4] = [10, 0, 0, 1];
+
+    fn ptp_sync_payload() -> Vec<u8> {
+        let mut payload = vec![0u8; 44];
+        payload[0] = 0x00; // Sync
+        payload[1] = 0x02; // PTPv2
+        payload[2..4].copy_from_slice(&44u16.to_be_bytes());
+        payload
+    }
+
+    fn ipv4_frame(source: [u8; 4], protocol: u8, l4: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x01, 0x00, 0x5e, 0x00, 0x01, 0x81];
+        frame.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        frame.extend_from_slice(&0x0800u16.to_be_bytes());
+
+        let total_length = (20 + l4.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0x00]);
+        frame.extend_from_slice(&total_length.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0, 0, 64, protocol, 0, 0]);
+        frame.extend_from_slice(&source);
+        frame.extend_from_slice(&[224, 0, 1, 129]);
+        frame.extend_from_slice(l4);
+        frame
+    }
+
+    fn udp(dest_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut datagram = Vec::new();
+        datagram.extend_from_slice(&dest_port.to_be_bytes());
+        datagram.extend_from_slice(&dest_port.to_be_bytes());
+        datagram.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        datagram.extend_from_slice(&[0, 0]);
+        datagram.extend_from_slice(payload);
+        datagram
+    }
+
+    fn inner_ptp_frame() -> Vec<u8> {
+        ipv4_frame(INNER_SOURCE, 17, &udp(PTP_EVENT_PORT, &ptp_sync_payload()))
+    }
+
+    fn gre(protocol: u16, sequence: bool, payload: &[u8]) -> Vec<u8> {
+        let flags: u16 = if sequence { 0x1000 } else { 0 };
+        let mut header = flags.to_be_bytes().to_vec();
+        header.extend_from_slice(&protocol.to_be_bytes());
+        if sequence {
+            header.extend_from_slice(&1u32.to_be_bytes());
+        }
+        header.extend_from_slice(payload);
+        header
+    }
+
+    #[test]
+    fn test_plain_ptp_has_no_tunnel() {
+        let packet =
+            process_ethernet_packet(&inner_ptp_frame(), "eth0", None, SystemTime::now()).unwrap();
+        assert!(packet.tunnel.is_none());
+        assert_eq!(packet.ptp_payload, ptp_sync_payload());
+    }
+
+    #[test]
+    fn test_vxlan_decapsulation() {
+        let mut vxlan = vec![0x08, 0, 0, 0, 0x00, 0x12, 0x34, 0x00];
+        vxlan.extend_from_slice(&inner_ptp_frame());
+        let frame = ipv4_frame(OUTER_SOURCE, 17, &udp(VXLAN_PORT, &vxlan));
+
+        let packet = process_ethernet_packet(&frame, "eth0", Some(100), SystemTime::now()).unwrap();
+        let tunnel = packet.tunnel.as_ref().unwrap();
+        assert_eq!(tunnel.tunnel_type, TunnelType::Vxlan);
+        assert_eq!(tunnel.source, IpAddr::from(OUTER_SOURCE));
+        assert_eq!(tunnel.session_id, Some(0x1234));
+        assert_eq!(packet.source_addr.unwrap().ip(), IpAddr::from(INNER_SOURCE));
+        assert_eq!(packet.ptp_payload, ptp_sync_payload());
+        // Native VLAN of the capture interface doesn't apply to mirrored frames
+        assert_eq!(packet.vlan_id, None);
+        assert_eq!(packet.data, frame);
+    }
+
+    #[test]
+    fn test_erspan_ii_decapsulation() {
+        // Version 1, VLAN 42, session 7
+        let mut erspan = vec![0x10, 42, 0x00, 0x07, 0, 0, 0, 0];
+        erspan.extend_from_slice(&inner_ptp_frame());
+        let frame = ipv4_frame(OUTER_SOURCE, 47, &gre(GRE_PROTO_ERSPAN_II, true, &erspan));
+
+        let packet = process_ethernet_packet(&frame, "eth0", None, SystemTime::now()).unwrap();
+        let tunnel = packet.tunnel.as_ref().unwrap();
+        assert_eq!(tunnel.tunnel_type, TunnelType::ErspanII);
+        assert_eq!(tunnel.session_id, Some(7));
+        assert_eq!(packet.vlan_id, Some(42));
+    }
+
+    #[test]
+    fn test_erspan_iii_hardware_timestamp() {
+        let seconds: u32 = 1_704_067_200 + 37;
+        let nanoseconds: u32 = 123_456_789;
+
+        // Version 2, no VLAN, session 3, IEEE 1588 granularity with platform subheader
+        let mut erspan = vec![0x20, 0x00, 0x00, 0x03];
+        erspan.extend_from_slice(&nanoseconds.to_be_bytes());
+        erspan.extend_from_slice(&[0, 0, 0x00, 0b0000_0111]);
+        erspan.extend_from_slice(&[0x03 << 2, 0, 0, 0]);
+        erspan.extend_from_slice(&seconds.to_be_bytes());
+        erspan.extend_from_slice(&inner_ptp_frame());
+        let frame = ipv4_frame(OUTER_SOURCE, 47, &gre(GRE_PROTO_ERSPAN_III, true, &erspan));
+
+        let packet = process_ethernet_packet(&frame, "eth0", None, SystemTime::now()).unwrap();
+        let tunnel = packet.tunnel.as_ref().unwrap();
+        assert_eq!(tunnel.tunnel_type, TunnelType::ErspanIII);
+        assert_eq!(tunnel.session_id, Some(3));
+        assert_eq!(
+            packet.timestamp,
+            std::time::UNIX_EPOCH + Duration::new(1_704_067_200, nanoseconds)
+        );
+    }
+
+    #[test]
+    fn test_prp_trailer() {
+        let mut frame = inner_ptp_frame();
+        // Sequence 0x1234, LAN B with LSDU size 78 (72 bytes of IPv4 plus the trailer),
+        // PRP suffix
+        frame.extend_from_slice(&[0x12, 0x34, 0xb0, 0x4e, 0x88, 0xfb]);
+
+        let packet = process_ethernet_packet(&frame, "eth0", None, SystemTime::now()).unwrap();
+        let tag = packet.redundancy.unwrap();
+        assert_eq!(tag.protocol, RedundancyProtocol::Prp);
+        assert_eq!(tag.lan, RedundancyLan::B);
+        assert_eq!(tag.sequence, 0x1234);
+        assert_eq!(packet.ptp_payload, ptp_sync_payload());
+        assert_eq!(packet.data, frame);
+    }
+
+    #[test]
+    fn test_prp_trailer_with_wrong_lsdu_size() {
+        let mut frame = inner_ptp_frame();
+        // LAN A and the PRP suffix, but an LSDU size that doesn't match the frame
+        frame.extend_from_slice(&[0x12, 0x34, 0xa0, 0x4a, 0x88, 0xfb]);
+
+        let packet = process_ethernet_packet(&frame, "eth0", None, SystemTime::now()).unwrap();
+        assert!(packet.redundancy.is_none());
+        assert_eq!(packet.data, frame);
+    }
+
+    #[test]
+    fn test_hsr_tag() {
+        let plain = inner_ptp_frame();
+        let mut frame = plain[..12].to_vec();
+        frame.extend_from_slice(&HSR_ETHERTYPE.to_be_bytes());
+        // Path 1, sequence 42, encapsulated IPv4
+        frame.extend_from_slice(&[0x10, 0x4a, 0x00, 0x2a, 0x08, 0x00]);
+        frame.extend_from_slice(&plain[14..]);
+
+        let packet = process_ethernet_packet(&frame, "eth0", None, SystemTime::now()).unwrap();
+        let tag = packet.redundancy.unwrap();
+        assert_eq!(tag.protocol, RedundancyProtocol::Hsr);
+        assert_eq!(tag.lan, RedundancyLan::B);
+        assert_eq!(tag.sequence, 42);
+        assert_eq!(packet.ptp_payload, ptp_sync_payload());
+    }
+
+    #[test]
+    fn test_nested_gre_in_vxlan_reports_outer_tunnel() {
+        let gre_frame = ipv4_frame(
+            [172, 16, 0, 1],
+            47,
+            &gre(GRE_PROTO_TEB, false, &inner_ptp_frame()),
+        );
+        let mut vxlan = vec![0x08, 0, 0, 0, 0x00, 0x00, 0x01, 0x00];
+        vxlan.extend_from_slice(&gre_frame);
+        let frame = ipv4_frame(OUTER_SOURCE, 17, &udp(VXLAN_PORT, &vxlan));
+
+        let packet = process_ethernet_packet(&frame, "eth0", None, SystemTime::now()).unwrap();
+        let tunnel = packet.tunnel.as_ref().unwrap();
+        assert_eq!(tunnel.tunnel_type, TunnelType::Vxlan);
+        assert_eq!(tunnel.source, IpAddr::from(OUTER_SOURCE));
+        assert_eq!(packet.ptp_payload, ptp_sync_payload());
+    }
+}
@@ -204,7 +204,7 @@ impl Theme {
         match state {
             PtpHostState::TimeTransmitter(_) => self.state_transmitter,
             PtpHostState::TimeReceiver(_) => self.state_receiver,
-            PtpHostState::Listening => self.state_listening,
+            PtpHostState::Listening | PtpHostState::Stale(_) => self.state_listening,
         }
     }
 
@@ -21,6 +21,19 @@ impl PtpTimestamp {
         vec![44100, 48000, 96000]
     }
 
+    /// Convert this PTP (TAI) timestamp to system (UTC) time, accounting for leap seconds
+    pub fn to_system_time(self) -> std::time::SystemTime {
+        use hifitime::{Epoch, TimeScale};
+
+        let epoch = Epoch::from_ptp_nanoseconds(self.total_nanoseconds() as u64);
+        let dur_utc = epoch.to_duration_in_time_scale(TimeScale::UTC);
+        let dur_tai = epoch.to_duration_in_time_scale(TimeScale::TAI);
+        let tai_offset_ns = (dur_tai - dur_utc).total_nanoseconds().max(0) as u64;
+
+        let unix_ns = (self.total_nanoseconds() as u64).saturating_sub(tai_offset_ns);
+        std::time::UNIX_EPOCH + std::time::Duration::from_nanos(unix_ns)
+    }
+
     pub fn format_common_samplerates(&self, prefix: &str) -> Vec<(String, String)> {
         if self.seconds == 0 && self.nanoseconds == 0 {
             return vec![];
@@ -80,6 +93,17 @@ impl Display for PtpTimestamp {
     }
 }
 
+#[test]
+fn test_ptp_timestamp_to_system_time() {
+    // 2024-01-01 00:00:00 UTC is 37 leap seconds behind TAI
+    let timestamp = PtpTimestamp {
+        seconds: 1_704_067_200 + 37,
+        nanoseconds: 500,
+    };
+    let expected = std::time::UNIX_EPOCH + std::time::Duration::new(1_704_067_200, 500);
+    assert_eq!(timestamp.to_system_time(), expected);
+}
+
 #[test]
 fn test_ptp_timestamp_rtp_samples() {
     let timestamp = PtpTimestamp {
@@ -136,7 +160,7 @@ fn test_ptp_timestamp_formatting() {
     assert_eq!(timestamp[9], 0x15);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum PtpMessageType {
     Sync = 0x0,
     DelayReq = 0x1,   // End-to-end delay request (transmitter-receiver mode)
@@ -187,6 +211,27 @@ impl Display for PtpMessageType {
     }
 }
 
+impl std::str::FromStr for PtpMessageType {
+    type Err = anyhow::Error;
+
+    /// Parse a message type name as displayed, case-insensitively
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().replace('-', "_").as_str() {
+            "SYNC" => Ok(PtpMessageType::Sync),
+            "DELAY_REQ" => Ok(PtpMessageType::DelayReq),
+            "PDELAY_REQ" => Ok(PtpMessageType::PDelayReq),
+            "PDELAY_RESP" => Ok(PtpMessageType::PDelayResp),
+            "FOLLOW_UP" => Ok(PtpMessageType::FollowUp),
+            "DELAY_RESP" => Ok(PtpMessageType::DelayResp),
+            "PDELAY_RESP_FU" | "PDELAY_RESP_FOLLOW_UP" => Ok(PtpMessageType::PDelayRespFollowUp),
+            "ANNOUNCE" => Ok(PtpMessageType::Announce),
+            "SIGNALING" => Ok(PtpMessageType::Signaling),
+            "MANAGEMENT" => Ok(PtpMessageType::Management),
+            _ => Err(anyhow::anyhow!("Unknown PTP message type '{}'", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PtpVersion {
     V1,
@@ -233,6 +278,45 @@ impl ClockIdentity {
 
         lookup_vendor_bytes(mac_bytes)
     }
+
+    /// The MAC address an EUI-64 clock identity was derived from by inserting FF:FE in its
+    /// middle, as most implementations do. None for identities of another form.
+    pub fn derived_mac_address(&self) -> Option<MacAddress> {
+        let id = self.clock_id;
+        (id[3] == 0xff && id[4] == 0xfe)
+            .then_some(MacAddress([id[0], id[1], id[2], id[5], id[6], id[7]]))
+    }
+}
+
+#[test]
+fn test_derived_mac_address() {
+    let clock_identity: ClockIdentity = "00:11:22:ff:fe:33:44:55".parse().unwrap();
+    assert_eq!(
+        clock_identity.derived_mac_address(),
+        Some(MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]))
+    );
+    assert_eq!(
+        clock_identity.derived_mac_address().unwrap().to_string(),
+        "00:11:22:33:44:55"
+    );
+
+    let clock_identity: ClockIdentity = "00:11:22:33:44:55:66:77".parse().unwrap();
+    assert_eq!(clock_identity.derived_mac_address(), None);
+}
+
+/// An Ethernet MAC address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mac = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+        )
+    }
 }
 
 impl TryFrom<&[u8]> for ClockIdentity {
@@ -245,6 +329,42 @@ impl TryFrom<&[u8]> for ClockIdentity {
     }
 }
 
+impl std::str::FromStr for ClockIdentity {
+    type Err = anyhow::Error;
+
+    /// Parse a clock identity as 8 hex bytes, optionally separated by ':' or '-'
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex: String = s.chars().filter(|c| *c != ':' && *c != '-').collect();
+        if hex.len() != 16 || !hex.is_ascii() {
+            return Err(anyhow::anyhow!("Invalid clock identity '{}'", s));
+        }
+
+        let mut clock_id = [0u8; 8];
+        for (i, byte) in clock_id.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| anyhow::anyhow!("Invalid clock identity '{}'", s))?;
+        }
+
+        Ok(Self { clock_id })
+    }
+}
+
+#[test]
+fn test_clock_identity_from_str() {
+    let expected = ClockIdentity {
+        clock_id: [0x00, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55],
+    };
+    assert_eq!(
+        "00:11:22:ff:fe:33:44:55".parse::<ClockIdentity>().unwrap(),
+        expected
+    );
+    assert_eq!(
+        "001122fffe334455".parse::<ClockIdentity>().unwrap(),
+        expected
+    );
+    assert!("00:11:22".parse::<ClockIdentity>().is_err());
+}
+
 #[test]
 fn test_oui_vendor_lookup() {
     // Test Cisco OUI-24 (00:00:0c)
@@ -296,6 +416,21 @@ impl PtpClockClass {
     pub fn class(&self) -> u8 {
         self.class
     }
+
+    /// Position on the usual degradation path of a grandmaster, lower is better: locked (6/13),
+    /// holdover (7/14), degraded A (52/58), degraded B (187/193), free-running (248) and
+    /// follower-only (255). None for profile specific and reserved classes.
+    pub fn degradation_rank(&self) -> Option<u8> {
+        match self.class {
+            6 | 13 => Some(0),
+            7 | 14 => Some(1),
+            52 | 58 => Some(2),
+            187 | 193 => Some(3),
+            248 => Some(4),
+            255 => Some(5),
+            _ => None,
+        }
+    }
 }
 
 impl Display for PtpClockClass {
@@ -411,16 +546,19 @@ impl PtpLogInterval {
     pub fn new(exponent: i8) -> Self {
         Self { exponent }
     }
+
+    /// Interval in seconds, or None for the reserved value 0x7f (not specified)
+    pub fn seconds(&self) -> Option<f64> {
+        (self.exponent != 0x7f).then(|| 2.0_f64.powi(self.exponent as i32))
+    }
 }
 
 impl Display for PtpLogInterval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.exponent == 0x7f {
-            return write!(f, "-");
+        match self.seconds() {
+            Some(interval_seconds) => write!(f, "{:.2}s ({})", interval_seconds, self.exponent),
+            None => write!(f, "-"),
         }
-
-        let interval_seconds = 2.0_f64.powf(self.exponent as f64);
-        write!(f, "{:.2}s ({})", interval_seconds, self.exponent)
     }
 }
 
@@ -448,6 +586,11 @@ impl PtpCorrectionField {
     pub fn new(value: i64) -> Self {
         Self { value }
     }
+
+    /// Correction in whole nanoseconds, dropping the sub-nanosecond fraction
+    pub fn nanoseconds(&self) -> i64 {
+        self.value >> 16
+    }
 }
 
 impl Display for PtpCorrectionField {
@@ -466,7 +609,7 @@ impl Display for PtpCorrectionField {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Ord, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Ord, PartialOrd, Default)]
 pub struct PortIdentity {
     pub clock_identity: ClockIdentity,
     pub port_number: u16,
@@ -507,6 +650,34 @@ pub struct PtpHeaderFlags {
 }
 
 impl PtpHeaderFlags {
+    pub fn two_step(&self) -> bool {
+        self.two_step_flag
+    }
+
+    pub fn leap61(&self) -> bool {
+        self.leap61
+    }
+
+    pub fn leap59(&self) -> bool {
+        self.leap59
+    }
+
+    pub fn current_utc_offset_valid(&self) -> bool {
+        self.current_utc_offset_valid
+    }
+
+    pub fn ptp_timescale(&self) -> bool {
+        self.ptp_timescale
+    }
+
+    pub fn time_traceable(&self) -> bool {
+        self.time_traceable
+    }
+
+    pub fn frequency_traceable(&self) -> bool {
+        self.frequency_traceable
+    }
+
     pub fn short(&self) -> String {
         format!("{:02x}{:02x}", self.v[0], self.v[1])
     }
@@ -574,6 +745,8 @@ fn format_kv(kv: Vec<(String, String)>) -> String {
 
 #[derive(Debug, Clone, Copy)]
 pub struct PtpHeader {
+    /// majorSdoId, formerly transportSpecific: 1 for 802.1AS
+    pub major_sdo_id: u8,
     pub message_type: PtpMessageType,
     pub version: PtpVersion,
     pub message_length: u16,
@@ -594,6 +767,7 @@ impl TryFrom<&[u8]> for PtpHeader {
             Err(anyhow::anyhow!("Packet too short for PTP header"))
         } else {
             Ok(PtpHeader {
+                major_sdo_id: data[0] >> 4,
                 message_type: PtpMessageType::try_from(data[0] & 0x0f)?,
                 version: PtpVersion::try_from(data[1] & 0x0f)?,
                 message_length: u16::from_be_bytes([data[2], data[3]]),
@@ -1016,6 +1190,15 @@ impl Display for SignalingMessage {
     }
 }
 
+/// actionField of a management RESPONSE
+pub const MANAGEMENT_ACTION_RESPONSE: u8 = 2;
+
+/// tlvType of a MANAGEMENT TLV
+const TLV_TYPE_MANAGEMENT: u16 = 0x0001;
+
+/// managementId of the PORT_DATA_SET (IEEE 1588-2019 15.5.3.6.1)
+const MANAGEMENT_ID_PORT_DATA_SET: u16 = 0x2004;
+
 #[derive(Debug, Clone, Copy)]
 pub struct ManagementMessage {
     pub header: PtpHeader,
@@ -1023,12 +1206,14 @@ pub struct ManagementMessage {
     pub starting_boundary_hops: u8,
     pub boundary_hops: u8,
     pub action_field: u8,
-    // FIXME!
+    /// portIdentity and portState of a PORT_DATA_SET in the management TLV. Other
+    /// management TLVs are not decoded.
+    pub port_data_set: Option<(PortIdentity, u8)>,
 }
 
 impl ManagementMessage {
     pub fn details(&self) -> Vec<(String, String)> {
-        vec![
+        let mut details = vec![
             (
                 "Target PI".to_string(),
                 format!("{}", self.target_port_identity),
@@ -1042,7 +1227,22 @@ impl ManagementMessage {
                 format!("{}", self.boundary_hops),
             ),
             ("ActionField".to_string(), format!("{}", self.action_field)),
-        ]
+        ];
+        if let Some((port_identity, port_state)) = self.port_data_set {
+            details.push(("PortDS PI".to_string(), port_identity.to_string()));
+            details.push(("PortDS State".to_string(), port_state.to_string()));
+        }
+        details
+    }
+
+    fn parse_port_data_set(data: &[u8]) -> Option<(PortIdentity, u8)> {
+        let tlv = data.get(48..65)?;
+        let tlv_type = u16::from_be_bytes([tlv[0], tlv[1]]);
+        let management_id = u16::from_be_bytes([tlv[4], tlv[5]]);
+        if tlv_type != TLV_TYPE_MANAGEMENT || management_id != MANAGEMENT_ID_PORT_DATA_SET {
+            return None;
+        }
+        Some((PortIdentity::try_from(&tlv[6..16]).ok()?, tlv[16]))
     }
 }
 
@@ -1059,6 +1259,7 @@ impl TryFrom<&[u8]> for ManagementMessage {
                 starting_boundary_hops: data[44],
                 boundary_hops: data[45],
                 action_field: data[46] & 0x0f,
+                port_data_set: Self::parse_port_data_set(data),
             })
         }
     }
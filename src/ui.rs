@@ -7,8 +7,12 @@ use ratatui::{
 };
 
 use crate::{
-    app::{ActiveView, App, SortColumn, TreeNode},
-    ptp::{PtpHost, PtpHostState},
+    app::{ActiveView, App, SortColumn, TreeNode, TreeNodeKind},
+    ptp::{
+        DatasetChangeDirection, PortState, PtpHost, PtpHostState, PtpSegment, PtpTimescale,
+        SegmentStatistics, SyncFollowUpIssue,
+    },
+    source::CaptureMode,
     types::{ParsedPacket, PtpClockAccuracy, PtpClockClass, format_timestamp},
     version,
 };
@@ -72,7 +76,7 @@ fn create_host_row<'a>(
         Style::default()
     };
 
-    let mut state_display = host.state.short_string().to_string();
+    let mut state_display = host.state_short_string().to_string();
     if host.has_local_ip(local_ips) {
         state_display = format!("{}*", state_display);
     }
@@ -128,7 +132,10 @@ fn create_host_row<'a>(
                         };
 
                     Cell::from(Line::from(vec![
-                        Span::styled(id.to_string(), Style::default().fg(theme.text_primary)),
+                        Span::styled(
+                            id.clock_identity.to_string(),
+                            Style::default().fg(theme.text_primary),
+                        ),
                         Span::styled(confidence_symbol, Style::default().fg(confidence_color)),
                     ]))
                 })
@@ -137,6 +144,14 @@ fn create_host_row<'a>(
         _ => Cell::from("-"),
     };
 
+    let loss_cell = match host.loss_percent() {
+        Some(loss) if loss > 0.0 => {
+            Cell::from(format!("{:.1}%", loss)).style(Style::default().fg(theme.confidence_low))
+        }
+        Some(_) => Cell::from("0%"),
+        None => Cell::from("-"),
+    };
+
     let interfaces_display = if let Some(primary_interface) = host.get_primary_interface() {
         if host.has_multiple_interfaces() {
             format!(
@@ -151,25 +166,89 @@ fn create_host_row<'a>(
         "-".to_string()
     };
 
+    // Flag clock identities that several devices seem to share
+    let clock_identity_cell = if host.identity_conflicts.iter().any(|c| c.is_duplicate()) {
+        Cell::from(format!("{} DUP", clock_identity_display))
+            .style(Style::default().fg(theme.confidence_low))
+    } else if !host.identity_conflicts.is_empty() {
+        Cell::from(clock_identity_display).style(Style::default().fg(theme.confidence_medium))
+    } else {
+        Cell::from(clock_identity_display)
+    };
+
     Row::new(vec![
         Cell::from(state_display).style(Style::default().fg(state_color)),
-        Cell::from(clock_identity_display),
+        clock_identity_cell,
         Cell::from(ip_display),
         Cell::from(interfaces_display),
         Cell::from(host.get_vendor_name().unwrap_or("-")),
-        Cell::from(
-            host.domain_number
-                .map_or("-".to_string(), |domain| domain.to_string()),
-        ),
+        Cell::from(host.domains_string(app.host_timeout(host))),
         Cell::from(priority1_display),
         Cell::from(clock_class_display),
         selected_transmitter_cell,
         Cell::from(host.total_messages_sent_count.to_string()),
+        loss_cell,
         Cell::from(last_seen_str),
     ])
     .style(style)
 }
 
+// Helper function to create the group row of a segment in tree view
+fn create_segment_row<'a>(
+    segment: &PtpSegment,
+    label: String,
+    actual_i: usize,
+    selected_index: usize,
+    theme: &crate::themes::Theme,
+    app: &App,
+) -> Row<'a> {
+    let style = if actual_i == selected_index {
+        Style::default()
+            .fg(theme.text_accent)
+            .bg(theme.selected_row_background)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(theme.text_accent)
+            .add_modifier(Modifier::BOLD)
+    };
+
+    let stats = app
+        .cached_stats
+        .segments
+        .iter()
+        .find(|stats| stats.segment == *segment);
+    let count = |count: fn(&SegmentStatistics) -> usize| {
+        stats.map_or("-".to_string(), |stats| count(stats).to_string())
+    };
+
+    Row::new(vec![
+        Cell::from("SEG"),
+        Cell::from(label),
+        Cell::from(format!(
+            "{} hosts: {} TT, {} TR, {} L",
+            count(|s| s.host_count),
+            count(|s| s.transmitter_count),
+            count(|s| s.receiver_count),
+            count(|s| s.listening_count)
+        )),
+        Cell::from(segment.interface_name.clone()),
+        Cell::from(segment.transport.to_string()),
+        Cell::from(segment.domain_number.to_string()),
+        Cell::from("-"),
+        Cell::from("-"),
+        Cell::from(
+            stats
+                .and_then(|stats| stats.grandmaster)
+                .map_or("no GM".to_string(), |gm| format!("GM {}", gm)),
+        ),
+        Cell::from(stats.map_or("-".to_string(), |stats| stats.message_count.to_string())),
+        Cell::from("-"),
+        Cell::from("-"),
+    ])
+    .style(style)
+}
+
 // Helper function to create aligned label-value pairs
 fn create_aligned_field(
     label: String,
@@ -247,6 +326,60 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     if app.show_packet_modal {
         render_packet_modal(f, f.area(), app);
     }
+
+    // Render filter prompt on top of everything else
+    if app.filter_input.is_some() {
+        render_filter_prompt(f, f.area(), app);
+    }
+}
+
+fn render_filter_prompt(f: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let input = app.filter_input.as_deref().unwrap_or_default();
+
+    let width = (area.width as f32 * 0.6) as u16;
+    let width = width.max(60).min(area.width);
+    let height = 5.min(area.height);
+    let prompt_area = Rect {
+        x: (area.width - width) / 2,
+        y: (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let status_line = match &app.filter_error {
+        Some(error) => Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))),
+        None => Line::from(Span::styled(
+            format!(
+                "key=value[,value] terms, '!' excludes. Keys: {}",
+                crate::filter::FILTER_KEYS.join(", ")
+            ),
+            Style::default().fg(theme.text_secondary),
+        )),
+    };
+
+    let content = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.text_accent)),
+            Span::styled(input.to_string(), Style::default().fg(theme.text_primary)),
+            Span::styled("█", Style::default().fg(theme.text_accent)),
+        ]),
+        Line::from(""),
+        status_line,
+    ];
+
+    let prompt = Paragraph::new(content)
+        .style(Style::default().bg(theme.background))
+        .block(
+            Block::default()
+                .title("Packet Filter (Enter to apply, Esc to cancel, empty to clear)")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.border_focused)),
+        );
+
+    f.render_widget(Clear, prompt_area);
+    f.render_widget(prompt, prompt_area);
 }
 
 fn render_header(f: &mut Frame, area: Rect, app: &App) {
@@ -278,6 +411,40 @@ fn render_header(f: &mut Frame, area: Rect, app: &App) {
         ));
     }
 
+    // Make it obvious that link-layer fields are missing in UDP capture mode
+    if app.cached_stats.capture_mode == CaptureMode::Udp {
+        header_spans.push(Span::styled(
+            " [UDP MODE]",
+            Style::default()
+                .fg(theme.text_secondary)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Count down to a leap second announced by a grandmaster
+    if let Some(leap_second) = &app.cached_stats.upcoming_leap_second {
+        let now = app
+            .get_reference_timestamp()
+            .unwrap_or_else(std::time::SystemTime::now);
+        let remaining = leap_second
+            .at
+            .duration_since(now)
+            .unwrap_or_default()
+            .as_secs();
+        header_spans.push(Span::styled(
+            format!(
+                " [LEAP {} in {}h {:02}m {:02}s]",
+                leap_second.leap,
+                remaining / 3600,
+                remaining / 60 % 60,
+                remaining % 60
+            ),
+            Style::default()
+                .fg(theme.confidence_medium)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     let header_content = vec![
         Line::from(header_spans),
         Line::from(vec![Span::styled(
@@ -322,6 +489,11 @@ pub fn format_clock_accuracy(ca: Option<PtpClockAccuracy>) -> String {
 }
 
 fn render_main_content(f: &mut Frame, area: Rect, app: &mut App) {
+    if app.active_view == ActiveView::Domains {
+        render_domains_view(f, area, app);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
@@ -337,6 +509,310 @@ fn render_main_content(f: &mut Frame, area: Rect, app: &mut App) {
     render_stats_panel(f, chunks[1], app);
 }
 
+fn render_domains_view(f: &mut Frame, area: Rect, app: &mut App) {
+    // The host table and details are hidden, so clicks must not select in them
+    app.host_table_area = None;
+    app.host_details_area = None;
+
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+
+    let header = Row::new(
+        ["Segment", "Profile", "Grandmaster", "TT", "TR", "GM Since"].map(|name| {
+            Cell::from(name).style(
+                Style::default()
+                    .fg(theme.table_header)
+                    .add_modifier(Modifier::BOLD),
+            )
+        }),
+    );
+    let rows: Vec<Row> = app
+        .cached_domains
+        .iter()
+        .enumerate()
+        .map(|(i, domain)| {
+            let style = if i == app.selected_domain_index {
+                Style::default()
+                    .bg(theme.selected_row_background)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(domain.segment.to_string()),
+                Cell::from(domain.profile.to_string()),
+                if domain.competing_sources.is_empty() {
+                    Cell::from(
+                        domain
+                            .grandmaster
+                            .map_or("-".to_string(), |gm| gm.clock_identity.to_string()),
+                    )
+                    .style(Style::default().fg(theme.state_transmitter))
+                } else {
+                    Cell::from(format!(
+                        "SPLIT ({} sources)",
+                        domain.competing_sources.len()
+                    ))
+                    .style(Style::default().fg(theme.confidence_low))
+                },
+                Cell::from(domain.statistics.transmitter_count.to_string()),
+                Cell::from(domain.statistics.receiver_count.to_string()),
+                Cell::from(
+                    domain
+                        .since_gm_change
+                        .map_or("-".to_string(), |since| format!("{}s", since.as_secs())),
+                ),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(28),
+            Constraint::Length(18),
+            Constraint::Length(23),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(9),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("PTP Domains [ACTIVE - TAB to switch, ENTER to show the GM]")
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border_focused)),
+    )
+    .style(Style::default().bg(theme.background));
+    f.render_widget(table, chunks[0]);
+
+    const LABEL_WIDTH: usize = 18;
+    let details_text = match app.cached_domains.get(app.selected_domain_index) {
+        Some(domain) => {
+            let interval = |interval: Option<crate::types::PtpLogInterval>| {
+                interval.map_or("-".to_string(), |i| i.to_string())
+            };
+            let mut lines = vec![
+                create_aligned_field(
+                    "Segment: ".to_string(),
+                    domain.segment.to_string(),
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field(
+                    "Profile: ".to_string(),
+                    match domain.delay_mechanism {
+                        Some(mechanism) => format!("{} ({})", domain.profile, mechanism),
+                        None => domain.profile.to_string(),
+                    },
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field_with_vendor(
+                    "Grandmaster: ".to_string(),
+                    domain
+                        .grandmaster
+                        .map_or("None".to_string(), |gm| gm.to_string()),
+                    domain
+                        .grandmaster
+                        .and_then(|gm| gm.clock_identity.extract_vendor_name())
+                        .map(|vendor| format!(" ({})", vendor))
+                        .unwrap_or_default(),
+                    LABEL_WIDTH,
+                    theme,
+                    theme.state_transmitter,
+                ),
+            ];
+            if let Some(dataset) = domain.gm_dataset {
+                lines.extend([
+                    create_aligned_field(
+                        "GM Identity: ".to_string(),
+                        dataset.gm_identity.to_string(),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Priority 1/2: ".to_string(),
+                        format!("{}/{}", dataset.priority1, dataset.priority2),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Class/Accuracy: ".to_string(),
+                        format!("{} / {}", dataset.clock_class, dataset.clock_accuracy),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                ]);
+            }
+            lines.extend([
+                create_aligned_field(
+                    "Timescale: ".to_string(),
+                    match domain.timescale {
+                        Some(PtpTimescale::Ptp { utc_offset }) => {
+                            format!("PTP, UTC offset {}s", utc_offset)
+                        }
+                        Some(PtpTimescale::Arbitrary) => "ARB".to_string(),
+                        None => "-".to_string(),
+                    },
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field(
+                    "GM Since: ".to_string(),
+                    domain
+                        .since_gm_change
+                        .map_or("-".to_string(), |since| format!("{}s", since.as_secs())),
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field(
+                    "Hosts: ".to_string(),
+                    format!(
+                        "{} ({} TT, {} TR, {} L)",
+                        domain.statistics.host_count,
+                        domain.statistics.transmitter_count,
+                        domain.statistics.receiver_count,
+                        domain.statistics.listening_count
+                    ),
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field(
+                    "Announce Interval: ".to_string(),
+                    interval(domain.announce_interval),
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field(
+                    "Sync Interval: ".to_string(),
+                    interval(domain.sync_interval),
+                    LABEL_WIDTH,
+                    theme,
+                ),
+                create_aligned_field(
+                    "Delay Req Interval: ".to_string(),
+                    interval(domain.delay_req_interval),
+                    LABEL_WIDTH,
+                    theme,
+                ),
+            ]);
+
+            if !domain.competing_sources.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![Span::styled(
+                    "Competing Sync Sources:",
+                    Style::default()
+                        .fg(theme.confidence_low)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for source in &domain.competing_sources {
+                    let receivers = if source.receivers.is_empty() {
+                        "no receivers".to_string()
+                    } else {
+                        source
+                            .receivers
+                            .iter()
+                            .map(|r| r.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    lines.push(create_aligned_field(
+                        format!("{}: ", source.port_identity),
+                        receivers,
+                        LABEL_WIDTH,
+                        theme,
+                    ));
+                }
+            }
+
+            if !domain.time_property_issues.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(vec![Span::styled(
+                    "Time Properties:",
+                    Style::default()
+                        .fg(theme.confidence_low)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for issue in &domain.time_property_issues {
+                    lines.push(Line::from(Span::styled(
+                        format!("  {}", issue),
+                        Style::default().fg(if issue.is_disagreement() {
+                            theme.confidence_low
+                        } else {
+                            theme.confidence_medium
+                        }),
+                    )));
+                }
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![Span::styled(
+                "Backup Candidates:",
+                Style::default()
+                    .fg(theme.text_accent)
+                    .add_modifier(Modifier::BOLD),
+            )]));
+            if domain.backup_candidates.is_empty() {
+                lines.push(Line::from("  None"));
+            }
+            for (rank, (port, dataset)) in domain.backup_candidates.iter().enumerate() {
+                lines.push(create_aligned_field(
+                    format!("{}. ", rank + 1),
+                    match dataset {
+                        Some(d) => format!(
+                            "{} (P1 {}, class {}, P2 {})",
+                            port,
+                            d.priority1,
+                            d.clock_class.class(),
+                            d.priority2
+                        ),
+                        None => port.to_string(),
+                    },
+                    4,
+                    theme,
+                ));
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![Span::styled(
+                "Packet Rates:",
+                Style::default()
+                    .fg(theme.text_accent)
+                    .add_modifier(Modifier::BOLD),
+            )]));
+            for (message_type, rate) in &domain.packet_rates {
+                lines.push(create_aligned_field(
+                    format!("{}: ", message_type),
+                    format!("{:.2}/s", rate),
+                    LABEL_WIDTH,
+                    theme,
+                ));
+            }
+            lines
+        }
+        None => vec![Line::from("No domains seen yet")],
+    };
+
+    let details = Paragraph::new(details_text)
+        .style(Style::default().fg(theme.text_primary).bg(theme.background))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Domain Details")
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.border_normal)),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(details, chunks[1]);
+}
+
 fn render_hosts_table(f: &mut Frame, area: Rect, app: &mut App) {
     // Calculate visible rows (subtract 4 for top border, header row, header bottom margin, and bottom border)
     let visible_height = area.height.saturating_sub(4) as usize;
@@ -370,6 +846,7 @@ fn render_hosts_table(f: &mut Frame, area: Rect, app: &mut App) {
         (SortColumn::ClockClass, "CC"),
         (SortColumn::SelectedTransmitter, "Selected Transmitter"),
         (SortColumn::MessageCount, "Msgs"),
+        (SortColumn::Loss, "Loss"),
         (SortColumn::LastSeen, "Last Seen"),
     ];
 
@@ -407,18 +884,49 @@ fn render_hosts_table(f: &mut Frame, area: Rect, app: &mut App) {
             .enumerate()
             .map(|(visible_i, (node, _flat_index, is_last_child))| {
                 let actual_i = visible_i + updated_scroll_offset;
-                let host = &node.host;
 
                 // Create indentation for tree structure
                 let indent = "  ".repeat(node.depth);
-                let tree_prefix = if node.depth > 0 {
-                    if *is_last_child { "└─ " } else { "├─ " }
+                let branch = if *is_last_child { "└─" } else { "├─" };
+                // Label the edge to the parent with the measured path delay, and boundary
+                // clocks whose stepsRemoved doesn't follow from their upstream's
+                let edge_labels: Vec<String> = node
+                    .tc_in_path
+                    .then(|| "TC".to_string())
+                    .into_iter()
+                    .chain(node.path_delay_ns.map(format_delay_ns))
+                    .chain(node.steps_removed_mismatch.map(|mismatch| {
+                        format!("SR {}≠{}+1", mismatch.announced, mismatch.upstream_steps)
+                    }))
+                    .collect();
+                let tree_prefix = if node.depth == 0 {
+                    String::new()
+                } else if edge_labels.is_empty() {
+                    format!("{} ", branch)
                 } else {
-                    ""
+                    format!("{}[{}]─ ", branch, edge_labels.join(" "))
+                };
+
+                let host = match &node.kind {
+                    TreeNodeKind::Host(host) => host,
+                    // Segments behind a boundary clock are nested below it
+                    TreeNodeKind::Segment(segment) => {
+                        let label = format!("{}{}{}", indent, tree_prefix, segment);
+                        return create_segment_row(
+                            segment,
+                            label,
+                            actual_i,
+                            selected_index,
+                            theme,
+                            app,
+                        );
+                    }
                 };
 
-                let clock_identity_display =
-                    format!("{}{}{}", indent, tree_prefix, host.clock_identity);
+                let clock_identity_display = match node.port {
+                    Some(port) => format!("{}{}port {}", indent, tree_prefix, port.port_number),
+                    None => format!("{}{}{}", indent, tree_prefix, host.clock_identity),
+                };
 
                 create_host_row(
                     host,
@@ -475,11 +983,12 @@ fn render_hosts_table(f: &mut Frame, area: Rect, app: &mut App) {
         Constraint::Length(24), // IP Address
         Constraint::Length(20), // Interfaces
         Constraint::Length(20), // Vendor
-        Constraint::Length(3),  // Domain
+        Constraint::Length(7),  // Domain
         Constraint::Length(3),  // Priority
         Constraint::Length(3),  // Clock Class
         Constraint::Length(25), // Selected Transmitter
         Constraint::Length(5),  // Message Count
+        Constraint::Length(6),  // Loss
         Constraint::Length(10), // Last Seen
     ];
 
@@ -491,8 +1000,9 @@ fn render_hosts_table(f: &mut Frame, area: Rect, app: &mut App) {
 
     let view_indicator = match app.active_view {
         ActiveView::HostTable => " [ACTIVE - TAB to switch]",
-        ActiveView::HostDetails => " [TAB to switch]",
-        ActiveView::PacketHistory => " [TAB to switch]",
+        ActiveView::HostDetails | ActiveView::PacketHistory | ActiveView::Domains => {
+            " [TAB to switch]"
+        }
     };
 
     let title = if app.tree_view_mode {
@@ -520,8 +1030,9 @@ fn render_hosts_table(f: &mut Frame, area: Rect, app: &mut App) {
                 .border_type(BorderType::Rounded)
                 .border_style(match app.active_view {
                     ActiveView::HostTable => Style::default().fg(theme.border_focused),
-                    ActiveView::HostDetails => Style::default().fg(theme.border_normal),
-                    ActiveView::PacketHistory => Style::default().fg(theme.border_normal),
+                    ActiveView::HostDetails | ActiveView::PacketHistory | ActiveView::Domains => {
+                        Style::default().fg(theme.border_normal)
+                    }
                 }),
         )
         .style(Style::default().bg(theme.background))
@@ -547,7 +1058,7 @@ fn render_stats_panel(f: &mut Frame, area: Rect, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(8), // Summary stats
+            Constraint::Length(9), // Summary stats
             Constraint::Min(5),    // Details panel (host or packet)
         ])
         .split(area);
@@ -562,6 +1073,19 @@ fn render_stats_panel(f: &mut Frame, area: Rect, app: &mut App) {
     render_host_details(f, chunks[1], app);
 }
 
+/// Offset of the local capture clock from the first grandmaster with offset samples,
+/// in nanoseconds
+fn local_clock_offset(hosts: &[&PtpHost]) -> Option<i64> {
+    hosts
+        .iter()
+        .flat_map(|host| host.ports.values())
+        .filter(
+            |port| matches!(port.state, PtpHostState::TimeTransmitter(ref s) if s.is_bmca_winner),
+        )
+        .find_map(|port| port.gm_offset.latest())
+        .map(|offset_ns| offset_ns.saturating_neg())
+}
+
 fn render_summary_stats(f: &mut Frame, area: Rect, app: &mut App) {
     let theme = &app.theme;
     let hosts = app.get_hosts();
@@ -595,12 +1119,37 @@ fn render_summary_stats(f: &mut Frame, area: Rect, app: &mut App) {
             theme,
             theme.state_receiver,
         ),
+        create_aligned_field(
+            "Segments: ".to_string(),
+            app.cached_stats.segments.len().to_string(),
+            STATS_LABEL_WIDTH,
+            theme,
+        ),
         create_aligned_field(
             "Last packet: ".to_string(),
             format!("{}s ago", app.cached_stats.last_packet_age_ms / 1000),
             STATS_LABEL_WIDTH,
             theme,
         ),
+        create_aligned_field(
+            "Local vs GM: ".to_string(),
+            local_clock_offset(&hosts).map_or("-".to_string(), format_offset_ns),
+            STATS_LABEL_WIDTH,
+            theme,
+        ),
+        create_aligned_field(
+            "Filtered: ".to_string(),
+            if app.cached_stats.filter.is_empty() {
+                "- (press f)".to_string()
+            } else {
+                format!(
+                    "{} pkts ({})",
+                    app.cached_stats.filtered_packets, app.cached_stats.filter
+                )
+            },
+            STATS_LABEL_WIDTH,
+            theme,
+        ),
     ];
 
     let paragraph = Paragraph::new(stats_text)
@@ -633,6 +1182,7 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
             let local_ips = app.cached_stats.local_ips.clone();
             // Define the width for label alignment
             const LABEL_WIDTH: usize = 22;
+            const MAX_DATASET_CHANGES: usize = 8;
 
             let mut details_text = vec![
                 // Host details section
@@ -689,6 +1239,33 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                 ));
             }
 
+            let source_macs: std::collections::BTreeSet<String> = host
+                .ports
+                .values()
+                .flat_map(|port| port.source_macs.keys())
+                .map(|mac| mac.to_string())
+                .collect();
+            if !source_macs.is_empty() {
+                details_text.push(create_aligned_field(
+                    "Source MAC: ".to_string(),
+                    source_macs.into_iter().collect::<Vec<_>>().join(", "),
+                    LABEL_WIDTH,
+                    theme,
+                ));
+            }
+
+            // Mirror sessions the host was seen through, sorted for a stable display
+            let mut tunnels: Vec<String> = host.tunnels.iter().map(|t| t.to_string()).collect();
+            tunnels.sort();
+            for tunnel in tunnels {
+                details_text.push(create_aligned_field(
+                    "Mirror Session: ".to_string(),
+                    tunnel,
+                    LABEL_WIDTH,
+                    theme,
+                ));
+            }
+
             details_text.extend(vec![
                 create_aligned_field_with_vendor(
                     "State: ".to_string(),
@@ -698,6 +1275,21 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                     theme,
                     theme.get_state_color(&host.state),
                 ),
+                create_aligned_field(
+                    "Port State: ".to_string(),
+                    format!(
+                        "{} ({}{})",
+                        host.inferred_state.state,
+                        host.inferred_state.evidence,
+                        if host.inferred_state.from_management {
+                            ""
+                        } else {
+                            ", inferred"
+                        }
+                    ),
+                    LABEL_WIDTH,
+                    theme,
+                ),
                 create_aligned_field(
                     "PTP Version: ".to_string(),
                     host.last_version
@@ -706,10 +1298,8 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                     theme,
                 ),
                 create_aligned_field(
-                    "Domain: ".to_string(),
-                    host.domain_number
-                        .map(|d| d.to_string())
-                        .unwrap_or("N/A".to_string()),
+                    "Domains: ".to_string(),
+                    host.domains_string(app.host_timeout(host)),
                     LABEL_WIDTH,
                     theme,
                 ),
@@ -734,11 +1324,16 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
 
             match &host.state {
                 PtpHostState::Listening => {}
-                PtpHostState::TimeTransmitter(s) => {
+                PtpHostState::TimeTransmitter(s) | PtpHostState::Stale(s) => {
+                    let heading = if matches!(host.state, PtpHostState::Stale(_)) {
+                        "Time Transmitter (stale):"
+                    } else {
+                        "Time Transmitter:"
+                    };
                     details_text.extend(vec![
                         Line::from(""),
                         Line::from(vec![Span::styled(
-                            "Time Transmitter:",
+                            heading,
                             Style::default()
                                 .fg(theme.text_accent)
                                 .add_modifier(Modifier::BOLD),
@@ -795,6 +1390,24 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                             LABEL_WIDTH,
                             theme,
                         ),
+                        create_aligned_field(
+                            "Leap Second: ".to_string(),
+                            s.dataset
+                                .and_then(|d| d.leap)
+                                .map_or("None".to_string(), |leap| {
+                                    format!("{} at midnight UTC", leap)
+                                }),
+                            LABEL_WIDTH,
+                            theme,
+                        ),
+                        create_aligned_field(
+                            "Last Announce: ".to_string(),
+                            s.last_announce_time.map_or("N/A".to_string(), |t| {
+                                format_system_time_ago(t, app.get_reference_timestamp())
+                            }),
+                            LABEL_WIDTH,
+                            theme,
+                        ),
                     ]);
 
                     details_text.push(create_aligned_field(
@@ -832,6 +1445,41 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                             ));
                         }
                     }
+
+                    // Dataset changes of the announced grandmaster, newest first
+                    let history = &app.cached_gm_dataset_history;
+                    if history.len() > 1 {
+                        details_text.push(Line::from(""));
+                        details_text.push(Line::from(vec![Span::styled(
+                            "GM Dataset Changes:",
+                            Style::default()
+                                .fg(theme.text_accent)
+                                .add_modifier(Modifier::BOLD),
+                        )]));
+                        for pair in history.windows(2).rev().take(MAX_DATASET_CHANGES) {
+                            let (time, dataset) = pair[1];
+                            for change in dataset.changes_from(&pair[0].1) {
+                                let color = match change.direction {
+                                    DatasetChangeDirection::Degraded => theme.confidence_low,
+                                    _ => theme.text_primary,
+                                };
+                                details_text.push(Line::from(vec![
+                                    Span::styled(
+                                        format!(
+                                            "{:<width$}",
+                                            format_system_time_ago(
+                                                time,
+                                                app.get_reference_timestamp()
+                                            ),
+                                            width = LABEL_WIDTH
+                                        ),
+                                        Style::default().fg(theme.text_secondary),
+                                    ),
+                                    Span::styled(change.to_string(), Style::default().fg(color)),
+                                ]));
+                            }
+                        }
+                    }
                 }
                 PtpHostState::TimeReceiver(s) => {
                     details_text.extend(vec![
@@ -849,7 +1497,7 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                                 None => "None".to_string(),
                             },
                             s.selected_transmitter_identity
-                                .and_then(|id| id.extract_vendor_name())
+                                .and_then(|id| id.clock_identity.extract_vendor_name())
                                 .map(|vendor| format!(" ({})", vendor))
                                 .unwrap_or_default(),
                             LABEL_WIDTH,
@@ -926,6 +1574,593 @@ fn render_host_details(f: &mut Frame, area: Rect, app: &mut App) {
                 ),
             ]);
 
+            if host.domains.len() > 1 {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Domains:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                let active: Vec<u8> = host.active_domains(app.host_timeout(host)).collect();
+                for (domain, participation) in &host.domains {
+                    details_text.push(create_aligned_field(
+                        format!("Domain {}: ", domain),
+                        format!(
+                            "{} ({} sent/{} received){}",
+                            participation.role(),
+                            participation.messages_sent,
+                            participation.messages_received,
+                            if active.contains(domain) {
+                                ""
+                            } else {
+                                " [left]"
+                            }
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                    ));
+                }
+            }
+
+            if host.has_multiple_ports() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Ports:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for port in host.ports.values() {
+                    let selected_transmitter = match &port.state {
+                        PtpHostState::TimeReceiver(s) => s
+                            .selected_transmitter_identity
+                            .map(|id| format!(" → {}", id))
+                            .unwrap_or_default(),
+                        _ => String::new(),
+                    };
+                    details_text.push(create_aligned_field_with_vendor(
+                        format!("Port {}: ", port.port_identity.port_number),
+                        format!(
+                            "{} [{}] (domain {}, {} sent/{} received){}",
+                            port.state,
+                            port.inferred_state.state,
+                            port.domain_number
+                                .map_or("N/A".to_string(), |d| d.to_string()),
+                            port.total_messages_sent_count,
+                            port.total_messages_received_count,
+                            selected_transmitter
+                        ),
+                        String::new(),
+                        LABEL_WIDTH,
+                        theme,
+                        theme.get_state_color(&port.state),
+                    ));
+                }
+            }
+
+            // Peers on the other end of each port's P2P link
+            let link_peers: Vec<_> = host
+                .ports
+                .values()
+                .flat_map(|p| {
+                    p.link_peers
+                        .iter()
+                        .map(move |(peer, link)| (p.port_identity.port_number, peer, link))
+                })
+                .collect();
+            if !link_peers.is_empty() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Link Peers:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (port_number, peer, link) in link_peers {
+                    let direction = match (link.requested, link.responded) {
+                        (true, true) => "both ways",
+                        (true, false) => "requested",
+                        _ => "answered",
+                    };
+                    details_text.push(create_aligned_field_with_vendor(
+                        format!("Port {}: ", port_number),
+                        format!("{} ({}, {})", peer, direction, link.interface_name),
+                        peer.clock_identity
+                            .extract_vendor_name()
+                            .map(|vendor| format!(" ({})", vendor))
+                            .unwrap_or_default(),
+                        LABEL_WIDTH,
+                        theme,
+                        theme.text_primary,
+                    ));
+                }
+            }
+
+            if !host.identity_conflicts.is_empty() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Identity Conflicts:",
+                    Style::default()
+                        .fg(theme.confidence_low)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for conflict in &host.identity_conflicts {
+                    let color = if conflict.is_duplicate() {
+                        theme.confidence_low
+                    } else {
+                        theme.confidence_medium
+                    };
+                    details_text.push(Line::from(vec![Span::styled(
+                        format!("  {}", conflict),
+                        Style::default().fg(color),
+                    )]));
+                }
+            }
+
+            if host.is_boundary_clock() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Boundary Clock:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                details_text.push(create_aligned_field(
+                    "Upstream: ".to_string(),
+                    host.boundary_clock_upstream(&app.cached_hosts)
+                        .map_or("Unknown".to_string(), |upstream| upstream.to_string()),
+                    LABEL_WIDTH,
+                    theme,
+                ));
+                let steps_removed = match host.steps_removed_mismatch(&app.cached_hosts) {
+                    Some(mismatch) => Span::styled(
+                        format!(
+                            "port {} announces {}, upstream {} announces {}",
+                            mismatch.port.port_number,
+                            mismatch.announced,
+                            mismatch.upstream,
+                            mismatch.upstream_steps
+                        ),
+                        Style::default().fg(theme.confidence_low),
+                    ),
+                    None => Span::styled(
+                        "consistent with upstream",
+                        Style::default().fg(theme.text_primary),
+                    ),
+                };
+                details_text.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:width$}", "Steps Removed: ", width = LABEL_WIDTH),
+                        Style::default().fg(theme.text_secondary),
+                    ),
+                    steps_removed,
+                ]));
+            }
+
+            // Sequence id analysis per port, message type and domain
+            let streams: Vec<_> = host
+                .ports
+                .values()
+                .flat_map(|p| {
+                    p.sequence_streams
+                        .iter()
+                        .map(move |(key, stats)| (p.port_identity.port_number, key, stats))
+                })
+                .collect();
+            if !streams.is_empty() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Sequence Loss:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (port_number, (message_type, domain), stats) in streams {
+                    let label = if host.has_multiple_ports() {
+                        format!("{} p{} d{}: ", message_type, port_number, domain)
+                    } else {
+                        format!("{} d{}: ", message_type, domain)
+                    };
+                    details_text.push(create_aligned_field_with_vendor(
+                        label,
+                        format!(
+                            "{:.2}% ({} lost, {} dup, {} reord)",
+                            stats.loss_percent(),
+                            stats.lost_count,
+                            stats.duplicate_count,
+                            stats.reordered_count
+                        ),
+                        String::new(),
+                        LABEL_WIDTH,
+                        theme,
+                        if stats.lost_count > 0 || stats.duplicate_count > 0 {
+                            theme.confidence_low
+                        } else {
+                            theme.text_primary
+                        },
+                    ));
+                }
+            }
+
+            // Measured message intervals vs. the advertised logMessageInterval
+            let intervals: Vec<_> = host
+                .ports
+                .values()
+                .flat_map(|p| {
+                    p.message_intervals
+                        .iter()
+                        .filter(|(_, stats)| stats.count > 0)
+                        .map(move |(message_type, stats)| {
+                            (p.port_identity.port_number, message_type, stats)
+                        })
+                })
+                .collect();
+            if !intervals.is_empty() {
+                let tolerance = app.cached_stats.interval_tolerance_percent;
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Message Intervals (measured/advertised):",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (port_number, message_type, stats) in intervals {
+                    let label = if host.has_multiple_ports() {
+                        format!("{} p{}: ", message_type, port_number)
+                    } else {
+                        format!("{}: ", message_type)
+                    };
+                    let advertised = stats
+                        .advertised
+                        .and_then(|a| a.seconds())
+                        .map_or("-".to_string(), |secs| format!("{:.2}/s", 1.0 / secs));
+                    let deviation = stats
+                        .deviation_percent()
+                        .map_or(String::new(), |d| format!(" ({:+.1}%)", d));
+                    details_text.push(create_aligned_field_with_vendor(
+                        label,
+                        format!(
+                            "{}/{}{} jitter {}",
+                            stats
+                                .rate()
+                                .map_or("-".to_string(), |rate| format!("{:.2}/s", rate)),
+                            advertised,
+                            deviation,
+                            format_latency(stats.std_dev())
+                        ),
+                        format!(
+                            " [{}..{}]",
+                            format_latency(stats.min),
+                            format_latency(stats.max)
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                        if stats.deviates(tolerance) {
+                            theme.confidence_low
+                        } else {
+                            theme.text_primary
+                        },
+                    ));
+                }
+            }
+
+            // Path delay per port and responder
+            let links: Vec<_> = host
+                .ports
+                .values()
+                .flat_map(|p| {
+                    p.path_delays.links.iter().map(move |(responder, stats)| {
+                        (p.port_identity.port_number, responder, stats)
+                    })
+                })
+                .collect();
+            if !links.is_empty() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Path Delay:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (port_number, responder, stats) in links {
+                    let label = if host.has_multiple_ports() {
+                        format!("{} p{}: ", stats.mechanism, port_number)
+                    } else {
+                        format!("{}: ", stats.mechanism)
+                    };
+                    let turnaround = stats.turnaround_ns.map_or(String::new(), |t| {
+                        format!(" turnaround {}", format_delay_ns(t as f64))
+                    });
+                    details_text.push(create_aligned_field_with_vendor(
+                        label,
+                        format!(
+                            "{} ±{} to {}",
+                            format_delay_ns(stats.mean_ns()),
+                            format_delay_ns(stats.std_dev_ns()),
+                            responder
+                        ),
+                        format!(
+                            " [{}..{}] n={}{}",
+                            format_delay_ns(stats.min_ns as f64),
+                            format_delay_ns(stats.max_ns as f64),
+                            stats.count,
+                            turnaround
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                        theme.text_primary,
+                    ));
+                }
+            }
+
+            // correctionField statistics per port and message type
+            let corrections: Vec<_> = host
+                .ports
+                .values()
+                .flat_map(|p| {
+                    p.corrections.iter().map(move |(message_type, stats)| {
+                        (p.port_identity.port_number, message_type, stats)
+                    })
+                })
+                .filter(|(_, _, stats)| stats.nonzero_count > 0)
+                .collect();
+            if !corrections.is_empty() {
+                details_text.push(Line::from(""));
+                details_text.push(Line::from(vec![Span::styled(
+                    "Correction Field:",
+                    Style::default()
+                        .fg(theme.text_accent)
+                        .add_modifier(Modifier::BOLD),
+                )]));
+                for (port_number, message_type, stats) in corrections {
+                    let label = if host.has_multiple_ports() {
+                        format!("{} p{}: ", message_type, port_number)
+                    } else {
+                        format!("{}: ", message_type)
+                    };
+                    let flags: Vec<String> = [
+                        (stats.indicates_transparent_clock(), "TC".to_string()),
+                        (
+                            stats.negative_count > 0,
+                            format!("{} negative", stats.negative_count),
+                        ),
+                        (stats.huge_count > 0, format!("{} huge", stats.huge_count)),
+                        (
+                            stats.fractional_count > 0,
+                            format!("{} sub-ns", stats.fractional_count),
+                        ),
+                    ]
+                    .into_iter()
+                    .filter_map(|(set, flag)| set.then_some(flag))
+                    .collect();
+                    details_text.push(create_aligned_field_with_vendor(
+                        label,
+                        format!(
+                            "{} p50 {} p99 {} [{}..{}]",
+                            stats
+                                .mean_ns()
+                                .map_or("-".to_string(), |ns| format_delay_ns(ns as f64)),
+                            stats
+                                .percentile_ns(50.0)
+                                .map_or("-".to_string(), |ns| format_delay_ns(ns as f64)),
+                            stats
+                                .percentile_ns(99.0)
+                                .map_or("-".to_string(), |ns| format_delay_ns(ns as f64)),
+                            format_delay_ns(stats.min_ns as f64),
+                            format_delay_ns(stats.max_ns as f64)
+                        ),
+                        if flags.is_empty() {
+                            String::new()
+                        } else {
+                            format!(" ({})", flags.join(", "))
+                        },
+                        LABEL_WIDTH,
+                        theme,
+                        if stats.is_suspicious() {
+                            theme.confidence_low
+                        } else {
+                            theme.text_primary
+                        },
+                    ));
+                }
+            }
+
+            // Sync/Follow_Up pairing per transmitting port
+            for port in host
+                .ports
+                .values()
+                .filter(|p| p.sync_count > 0 || p.follow_up_count > 0)
+            {
+                let stats = &port.sync_follow_up;
+                let title = if host.has_multiple_ports() {
+                    format!("Sync/Follow_Up (port {}):", port.port_identity.port_number)
+                } else {
+                    "Sync/Follow_Up:".to_string()
+                };
+                details_text.extend(vec![
+                    Line::from(""),
+                    Line::from(vec![Span::styled(
+                        title,
+                        Style::default()
+                            .fg(theme.text_accent)
+                            .add_modifier(Modifier::BOLD),
+                    )]),
+                    create_aligned_field(
+                        "Paired: ".to_string(),
+                        stats.matched_count.to_string(),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Latency min/avg/max: ".to_string(),
+                        format!(
+                            "{}/{}/{}",
+                            format_latency(stats.min_latency),
+                            format_latency(stats.mean_latency()),
+                            format_latency(stats.max_latency)
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Latency p50/p99: ".to_string(),
+                        format!(
+                            "{}/{}",
+                            format_latency(stats.latency_percentile(50.0)),
+                            format_latency(stats.latency_percentile(99.0))
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                ]);
+                for (label, issue) in [
+                    ("Missing FU: ", SyncFollowUpIssue::MissingFollowUp),
+                    ("Late FU: ", SyncFollowUpIssue::LateFollowUp),
+                    ("FU Without Sync: ", SyncFollowUpIssue::FollowUpWithoutSync),
+                    ("Two-Step Mismatch: ", SyncFollowUpIssue::TwoStepMismatch),
+                ] {
+                    let count = stats.issue_count(issue);
+                    details_text.push(create_aligned_field_with_vendor(
+                        label.to_string(),
+                        count.to_string(),
+                        String::new(),
+                        LABEL_WIDTH,
+                        theme,
+                        if count > 0 {
+                            theme.confidence_low
+                        } else {
+                            theme.text_primary
+                        },
+                    ));
+                }
+            }
+
+            // Offset of the distributed time against the local capture clock
+            for port in host
+                .ports
+                .values()
+                .filter(|p| !p.gm_offset.samples.items.is_empty())
+            {
+                let stats = &port.gm_offset;
+                let title = if host.has_multiple_ports() {
+                    format!(
+                        "Time vs Local Clock (port {}):",
+                        port.port_identity.port_number
+                    )
+                } else {
+                    "Time vs Local Clock:".to_string()
+                };
+                let timescale = match stats.timescale {
+                    Some(PtpTimescale::Ptp { utc_offset }) => {
+                        format!("PTP (UTC offset {:+}s)", utc_offset)
+                    }
+                    Some(PtpTimescale::Arbitrary) => "ARB".to_string(),
+                    None => "-".to_string(),
+                };
+                let (mean, min, max) = stats.summary().unwrap_or_default();
+                details_text.extend(vec![
+                    Line::from(""),
+                    Line::from(vec![Span::styled(
+                        title,
+                        Style::default()
+                            .fg(theme.text_accent)
+                            .add_modifier(Modifier::BOLD),
+                    )]),
+                    create_aligned_field("Timescale: ".to_string(), timescale, LABEL_WIDTH, theme),
+                    create_aligned_field(
+                        "Offset: ".to_string(),
+                        stats.latest().map_or("-".to_string(), format_offset_ns),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Offset avg/min/max: ".to_string(),
+                        format!(
+                            "{}/{}/{}",
+                            format_offset_ns(mean),
+                            format_offset_ns(min),
+                            format_offset_ns(max)
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Drift: ".to_string(),
+                        stats
+                            .drift_ppm()
+                            .map_or("-".to_string(), |ppm| format!("{:+.3} ppm", ppm)),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field_with_vendor(
+                        "Time Steps: ".to_string(),
+                        stats.step_count.to_string(),
+                        stats.last_step.map_or(String::new(), |step| {
+                            format!(
+                                " (last {}, {})",
+                                format_offset_ns(step.step_ns),
+                                format_system_time_ago(
+                                    step.capture_time,
+                                    app.get_reference_timestamp()
+                                )
+                            )
+                        }),
+                        LABEL_WIDTH,
+                        theme,
+                        if stats.step_count > 0 {
+                            theme.confidence_low
+                        } else {
+                            theme.text_primary
+                        },
+                    ),
+                ]);
+            }
+
+            if let Some(redundancy) = &host.redundancy {
+                details_text.extend(vec![
+                    Line::from(""),
+                    Line::from(vec![Span::styled(
+                        format!("{} Redundancy:", redundancy.protocol),
+                        Style::default()
+                            .fg(theme.text_accent)
+                            .add_modifier(Modifier::BOLD),
+                    )]),
+                    create_aligned_field(
+                        "LAN A Received: ".to_string(),
+                        format!(
+                            "{} ({} missing)",
+                            redundancy.lan_a_count,
+                            redundancy.missing_on_lan_a()
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "LAN B Received: ".to_string(),
+                        format!(
+                            "{} ({} missing)",
+                            redundancy.lan_b_count,
+                            redundancy.missing_on_lan_b()
+                        ),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Duplicates Discarded: ".to_string(),
+                        redundancy.duplicates_discarded().to_string(),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                    create_aligned_field(
+                        "Loss Asymmetry: ".to_string(),
+                        format!("{:+.2}% (B - A)", redundancy.loss_asymmetry_percent()),
+                        LABEL_WIDTH,
+                        theme,
+                    ),
+                ]);
+            }
+
             details_text
         } else {
             vec![
@@ -1015,12 +2250,15 @@ fn render_help(f: &mut Frame, area: Rect, app: &App) {
                 .fg(theme.table_header)
                 .add_modifier(Modifier::BOLD),
         )]),
-        Line::from("  Tab        - Cycle: Host Table → Host Details → Packet History"),
+        Line::from("  Tab        - Cycle: Host Table → Host Details → Packet History → Domains"),
         Line::from("  ↑/k        - Move selection up (host table) or scroll (details/packets)"),
         Line::from("  ↓/j        - Move selection down (host table) or scroll (details/packets)"),
         Line::from("  PgUp/PgDn  - Page up/down (10 items or 1 page scroll)"),
         Line::from("  Home/End   - Jump to top/bottom"),
         Line::from("  Enter      - Show packet details (when packet history active)"),
+        Line::from(
+            "  Enter      - Show the domain's grandmaster in the tree (when domains active)",
+        ),
         Line::from("  q          - Close packet details modal (when modal open)"),
         Line::from("  ↑↓/k/j     - Scroll modal content (when modal open)"),
         Line::from("  PgUp/PgDn/Space - Page scroll modal content (when modal open)"),
@@ -1073,6 +2311,7 @@ fn render_help(f: &mut Frame, area: Rect, app: &App) {
                 .fg(theme.table_header)
                 .add_modifier(Modifier::BOLD),
         )]),
+        Line::from("  f          - Edit packet filter (e.g. domain=0,1 message-type=!signaling)"),
         Line::from("  h/F1       - Show/hide this help"),
         Line::from("  Esc/q      - Close help"),
         Line::from("  q          - Close modal/help or quit application"),
@@ -1087,6 +2326,19 @@ fn render_help(f: &mut Frame, area: Rect, app: &App) {
         Line::from("  • Packet selection preserved when switching views"),
         Line::from("  • Scroll positions reset when selecting different host"),
         Line::from("  • Auto-scroll disabled when manually navigating packets"),
+    ]);
+
+    let capture_mode = app.cached_stats.capture_mode;
+    let unavailable_fields = capture_mode.unavailable_fields();
+    if !unavailable_fields.is_empty() {
+        help_text.push(Line::from(format!(
+            "  • {} capture mode: {} not available",
+            capture_mode.as_str().to_uppercase(),
+            unavailable_fields.join(", ")
+        )));
+    }
+
+    help_text.extend_from_slice(&[
         Line::from(""),
         Line::from(vec![Span::styled(
             "Legend:",
@@ -1108,6 +2360,13 @@ fn render_help(f: &mut Frame, area: Rect, app: &App) {
             ),
             Span::raw(format!(" - {} (Grandmaster)", time_transmitter_state)),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  BC",
+                Style::default().fg(theme.get_state_color(&time_transmitter_state)),
+            ),
+            Span::raw(" - Boundary clock port relaying the grandmaster"),
+        ]),
         Line::from(vec![
             Span::styled(
                 format!("  {}", time_receiver_state.short_string()),
@@ -1122,6 +2381,45 @@ fn render_help(f: &mut Frame, area: Rect, app: &App) {
             ),
             Span::raw(format!("  - {}", listening_state)),
         ]),
+        Line::from(vec![
+            Span::styled(
+                "  ST",
+                Style::default().fg(theme.get_state_color(&listening_state)),
+            ),
+            Span::raw(" - Stale, no Announce within the announceReceiptTimeout"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("  {}", PortState::Passive.short_string()),
+                Style::default().fg(theme.get_state_color(&time_transmitter_state)),
+            ),
+            Span::raw(" - Passive, announcing without Sync, e.g. a backup grandmaster"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("  {}", PortState::PreTimeTransmitter.short_string()),
+                Style::default().fg(theme.get_state_color(&time_transmitter_state)),
+            ),
+            Span::raw(" - Pre-Time Transmitter, announcing but not yet qualified"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("  {}", PortState::Uncalibrated.short_string()),
+                Style::default().fg(theme.get_state_color(&time_receiver_state)),
+            ),
+            Span::raw(" - Uncalibrated, delay requests without response"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("  {}", PortState::Faulty.short_string()),
+                Style::default().fg(theme.confidence_low),
+            ),
+            Span::raw(" - Faulty, e.g. two-step Syncs without Follow_Up"),
+        ]),
+        Line::from(vec![
+            Span::styled("  SEG", Style::default().fg(theme.text_accent)),
+            Span::raw(" - Segment (interface, VLAN, domain, transport) in tree view"),
+        ]),
         Line::from(vec![
             Span::styled("  *", Style::default().fg(theme.text_primary)),
             Span::raw("  - Local machine (your own host)"),
@@ -1178,13 +2476,11 @@ fn render_scrollbar(
 
     // Calculate thumb position properly - when at max scroll, thumb should be at bottom
     let max_scroll_offset = total_items.saturating_sub(visible_items);
-    let thumb_position = if max_scroll_offset == 0 {
-        0
-    } else {
-        // Scale scroll position to scrollbar height, ensuring thumb can reach the bottom
-        let max_thumb_position = scrollbar_height.saturating_sub(thumb_size);
-        (scroll_offset * max_thumb_position) / max_scroll_offset
-    };
+    // Scale scroll position to scrollbar height, ensuring thumb can reach the bottom
+    let max_thumb_position = scrollbar_height.saturating_sub(thumb_size);
+    let thumb_position = (scroll_offset * max_thumb_position)
+        .checked_div(max_scroll_offset)
+        .unwrap_or(0);
 
     // Draw scrollbar track
     for y in 0..scrollbar_height {
@@ -1284,8 +2580,7 @@ fn render_packet_history(f: &mut Frame, area: Rect, app: &mut App) {
 
     let view_indicator = match app.active_view {
         ActiveView::PacketHistory => " [ACTIVE - TAB to switch]",
-        ActiveView::HostTable => " [TAB to switch]",
-        ActiveView::HostDetails => " [TAB to switch]",
+        ActiveView::HostTable | ActiveView::HostDetails | ActiveView::Domains => " [TAB to switch]",
     };
 
     let expanded_status = if app.is_packet_history_expanded() {
@@ -1309,8 +2604,9 @@ fn render_packet_history(f: &mut Frame, area: Rect, app: &mut App) {
 
     let border_style = match app.active_view {
         ActiveView::PacketHistory => Style::default().fg(theme.border_focused),
-        ActiveView::HostTable => Style::default().fg(theme.border_normal),
-        ActiveView::HostDetails => Style::default().fg(theme.border_normal),
+        ActiveView::HostTable | ActiveView::HostDetails | ActiveView::Domains => {
+            Style::default().fg(theme.border_normal)
+        }
     };
 
     let block = Block::default()
@@ -1515,6 +2811,47 @@ fn render_packet_modal(f: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
+/// Format a delay in nanoseconds with a unit that fits its size
+fn format_delay_ns(delay_ns: f64) -> String {
+    if delay_ns.abs() < 1e3 {
+        format!("{:.0}ns", delay_ns)
+    } else if delay_ns.abs() < 1e6 {
+        format!("{:.2}µs", delay_ns / 1e3)
+    } else {
+        format!("{:.2}ms", delay_ns / 1e6)
+    }
+}
+
+/// Format a signed offset in nanoseconds with a unit that fits its size
+fn format_offset_ns(offset_ns: i64) -> String {
+    let ns = offset_ns as f64;
+    if offset_ns.unsigned_abs() < 1_000 {
+        format!("{:+}ns", offset_ns)
+    } else if offset_ns.unsigned_abs() < 1_000_000 {
+        format!("{:+.3}µs", ns / 1e3)
+    } else if offset_ns.unsigned_abs() < 1_000_000_000 {
+        format!("{:+.3}ms", ns / 1e6)
+    } else {
+        format!("{:+.3}s", ns / 1e9)
+    }
+}
+
+/// Format an optional duration in milliseconds
+fn format_latency(latency: Option<std::time::Duration>) -> String {
+    latency.map_or("N/A".to_string(), |l| {
+        format!("{:.3}ms", l.as_secs_f64() * 1000.0)
+    })
+}
+
+/// Format an optional MAC address, explaining why it is missing in UDP capture mode
+fn format_mac_field(mac: Option<[u8; 6]>, capture_mode: CaptureMode) -> String {
+    match mac {
+        Some(mac) => crate::types::MacAddress(mac).to_string(),
+        None if capture_mode == CaptureMode::Udp => "n/a (UDP capture mode)".to_string(),
+        None => "-".to_string(),
+    }
+}
+
 fn render_packet_details(
     f: &mut Frame,
     area: Rect,
@@ -1524,6 +2861,7 @@ fn render_packet_details(
 ) {
     let header = packet.ptp.header();
     let time_ago_str = format_system_time_ago(packet.raw.timestamp, app.get_reference_timestamp());
+    let capture_mode = app.cached_stats.capture_mode;
 
     let duration = packet.raw.timestamp.duration_since(UNIX_EPOCH).unwrap();
 
@@ -1560,15 +2898,7 @@ fn render_packet_details(
         ),
         create_aligned_field(
             "Source MAC:".to_string(),
-            format!(
-                "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-                packet.raw.source_mac[0],
-                packet.raw.source_mac[1],
-                packet.raw.source_mac[2],
-                packet.raw.source_mac[3],
-                packet.raw.source_mac[4],
-                packet.raw.source_mac[5]
-            ),
+            format_mac_field(packet.raw.source_mac, capture_mode),
             LABEL_WIDTH,
             theme,
         ),
@@ -1583,15 +2913,7 @@ fn render_packet_details(
         ),
         create_aligned_field(
             "Dest MAC:".to_string(),
-            format!(
-                "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-                packet.raw.dest_mac[0],
-                packet.raw.dest_mac[1],
-                packet.raw.dest_mac[2],
-                packet.raw.dest_mac[3],
-                packet.raw.dest_mac[4],
-                packet.raw.dest_mac[5]
-            ),
+            format_mac_field(packet.raw.dest_mac, capture_mode),
             LABEL_WIDTH,
             theme,
         ),
@@ -1619,6 +2941,25 @@ fn render_packet_details(
             LABEL_WIDTH,
             theme,
         ),
+        create_aligned_field(
+            "Redundancy:".to_string(),
+            packet
+                .raw
+                .redundancy
+                .map_or("-".to_string(), |tag| tag.to_string()),
+            LABEL_WIDTH,
+            theme,
+        ),
+        create_aligned_field(
+            "Tunnel:".to_string(),
+            packet
+                .raw
+                .tunnel
+                .as_ref()
+                .map_or("-".to_string(), |tunnel| tunnel.to_string()),
+            LABEL_WIDTH,
+            theme,
+        ),
         Line::from(""),
         Line::from(vec![Span::styled(
             "PTP Header:",